@@ -292,7 +292,11 @@ mod tests {
 
     let cache = DiskCache::new(&cache_location);
 
-    let mut test_cases = vec!["unknown://localhost/test.ts"];
+    let mut test_cases = vec![
+      "unknown://localhost/test.ts",
+      // Blob URLs only live as long as the program.
+      "blob:null/a3d1c5c6-5b8c-4c57-8e2e-63b7b5a6f0a1",
+    ];
 
     if cfg!(target_os = "windows") {
       test_cases.push("file://");
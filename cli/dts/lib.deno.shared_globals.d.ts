@@ -8,6 +8,7 @@
 /// <reference lib="deno.web" />
 /// <reference lib="deno.fetch" />
 /// <reference lib="deno.websocket" />
+/// <reference lib="deno.crypto" />
 
 declare namespace WebAssembly {
   /**
@@ -343,8 +344,6 @@ interface VoidFunction {
  */
 declare function queueMicrotask(func: VoidFunction): void;
 
-declare var crypto: Crypto;
-
 /** Registers an event listener in the global scope, which will be called
  * synchronously whenever the event `type` is dispatched.
  *
@@ -416,26 +415,6 @@ declare interface Console {
 
 declare var console: Console;
 
-declare interface Crypto {
-  readonly subtle: null;
-  getRandomValues<
-    T extends
-      | Int8Array
-      | Int16Array
-      | Int32Array
-      | Uint8Array
-      | Uint16Array
-      | Uint32Array
-      | Uint8ClampedArray
-      | Float32Array
-      | Float64Array
-      | DataView
-      | null,
-  >(
-    array: T,
-  ): T;
-}
-
 declare class URLSearchParams {
   constructor(
     init?: string[][] | Record<string, string> | string | URLSearchParams,
@@ -586,8 +565,21 @@ declare class URLSearchParams {
 /** The URL interface represents an object providing static methods used for creating object URLs. */
 declare class URL {
   constructor(url: string, base?: string | URL);
-  createObjectURL(object: any): string;
-  revokeObjectURL(url: string): void;
+  /** Creates a `blob:` URL that refers to the contents of `blob` until it is
+   * revoked. The URL can be fetched, imported and used as a worker script.
+   *
+   * ```ts
+   * const blob = new Blob(["export default 42;"], {
+   *   type: "application/javascript",
+   * });
+   * const url = URL.createObjectURL(blob);
+   * const { default: answer } = await import(url);
+   * URL.revokeObjectURL(url);
+   * ```
+   */
+  static createObjectURL(blob: Blob): string;
+  /** Releases a `blob:` URL that was created with `URL.createObjectURL()`. */
+  static revokeObjectURL(url: string): void;
 
   hash: string;
   host: string;
@@ -880,11 +880,70 @@ declare namespace Deno {
     close(): void;
     /** Return the address of the `UDPConn`. */
     readonly addr: Addr;
+    /** **UNSTABLE**: new API, yet to be vetted.
+     *
+     * Sets whether messages may be sent to broadcast addresses. Only
+     * supported by UDP sockets. */
+    setBroadcast(broadcast?: boolean): void;
+    /** **UNSTABLE**: new API, yet to be vetted.
+     *
+     * Sets whether multicast messages that the socket sends are looped back
+     * to the local host. Only supported by UDP sockets. */
+    setMulticastLoopback(loopback?: boolean): void;
+    /** **UNSTABLE**: new API, yet to be vetted.
+     *
+     * Sets the TTL of the messages that the socket sends, which is the hop
+     * limit for IPv6. Only supported by UDP sockets. */
+    setTTL(ttl: number): void;
+    /** **UNSTABLE**: new API, yet to be vetted.
+     *
+     * Sets the TTL of the multicast messages that the socket sends, which is
+     * the hop limit for IPv6. Only supported by UDP sockets. */
+    setMulticastTTL(ttl: number): void;
+    /** **UNSTABLE**: new API, yet to be vetted.
+     *
+     * Joins the multicast group at `address`. The interface is given by its
+     * IPv4 address for IPv4 groups, and by its index for IPv6 ones. By
+     * default, the system chooses it. Only supported by UDP sockets. */
+    joinMulticast(address: string, networkInterface?: string | number): void;
+    /** **UNSTABLE**: new API, yet to be vetted.
+     *
+     * Leaves a multicast group that was joined with `joinMulticast()`. */
+    leaveMulticast(address: string, networkInterface?: string | number): void;
     [Symbol.asyncIterator](): AsyncIterableIterator<[Uint8Array, Addr]>;
   }
 
+  export interface ListenOptions {
+    /** **UNSTABLE**: new API, yet to be vetted.
+     *
+     * Whether other sockets may listen on the same address, which the
+     * system balances the load between. Only supported on Unix. */
+    reusePort?: boolean;
+    /** **UNSTABLE**: new API, yet to be vetted.
+     *
+     * The length of the queue of connections that haven't been accepted yet.
+     * Defaults to `128`. */
+    backlog?: number;
+  }
+
+  export interface Conn {
+    /** **UNSTABLE**: new API, yet to be vetted.
+     *
+     * Enables or disables Nagle's algorithm, which holds back small writes to
+     * send them together. Only supported by TCP connections. */
+    setNoDelay(noDelay?: boolean): Promise<void>;
+    /** **UNSTABLE**: new API, yet to be vetted.
+     *
+     * Enables or disables TCP keepalive probes. `initialDelay` is the idle
+     * time before the first probe in milliseconds, rounded up to seconds, and
+     * defaults to two hours. Only supported by TCP connections. */
+    setKeepAlive(keepAlive?: boolean, initialDelay?: number): Promise<void>;
+  }
+
   export interface UnixListenOptions {
-    /** A Path to the Unix Socket. */
+    /** A Path to the Unix Socket. On Linux, a path that starts with a NUL
+     * byte (`"\0name"`) is an address in the abstract namespace, which isn't
+     * a file and requires `allow-net` permission instead. */
     path: string;
   }
 
@@ -962,12 +1021,164 @@ declare namespace Deno {
     options: ConnectOptions | UnixConnectOptions,
   ): Promise<Conn>;
 
-  export interface StartTlsOptions {
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * The process and user of the peer of a Unix connection, at the time it
+   * connected or started listening. */
+  export interface UnixCredentials {
+    /** The process ID of the peer, which is only known on Linux. */
+    pid: number | null;
+    uid: number;
+    gid: number;
+  }
+
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * A resource that was received from another process by
+   * `UnixConn.receiveFds()`. Sockets that are neither TCP, UDP nor Unix
+   * sockets are received as files. */
+  export type ReceivedResource = File | Conn | Listener | DatagramConn;
+
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * A Unix stream connection, as returned by `Deno.connect()` and the
+   * listener of `Deno.listen()` for the "unix" transport.
+   *
+   * ```ts
+   * const conn = await Deno.connect({ path: "/run/handoff.sock", transport: "unix" }) as Deno.UnixConn;
+   * const listener = Deno.listen({ port: 8080 });
+   * await conn.sendFds([listener]);
+   * listener.close();
+   * ```
+   */
+  export interface UnixConn extends Conn {
+    /** The credentials of the peer, or `null` if they are unknown. */
+    readonly peerCredentials: UnixCredentials | null;
+    /** Sends the descriptors of files, connections, listeners and datagram
+     * sockets to the peer along with `data`, which defaults to a single zero
+     * byte, as at least one byte needs to be sent. The resources stay open
+     * and can be closed once this resolves. Resolves to the number of bytes
+     * of `data` that were sent. */
+    sendFds(
+      resources: Array<{ readonly rid: number }>,
+      data?: Uint8Array,
+    ): Promise<number>;
+    /** Reads data into `p` like `read()`, together with the descriptors that
+     * were sent along with it, of which at most 64 are received at once.
+     * `nread` is `null` at EOF. */
+    receiveFds(
+      p: Uint8Array,
+    ): Promise<{ nread: number | null; resources: ReceivedResource[] }>;
+  }
+
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * The client certificate that is presented to servers that request one, for
+   * mutual TLS. The chain and the key are given either as PEM or as the path
+   * of a PEM file, which requires `allow-read` permission. */
+  export interface TlsClientCertOptions {
+    /** The client certificate chain, leaf first. */
+    certChain?: string;
+    certChainFile?: string;
+    /** The private key of the client certificate. */
+    privateKey?: string;
+    privateKeyFile?: string;
+  }
+
+  export interface ConnectTlsOptions extends TlsClientCertOptions {
+    /** **UNSTABLE**: new API, yet to be vetted.
+     *
+     * The application protocols that are offered to the server with ALPN, in
+     * order of preference. */
+    alpnProtocols?: string[];
+  }
+
+  export interface ListenTlsOptions {
+    /** **UNSTABLE**: new API, yet to be vetted.
+     *
+     * The CA certificates that client certificates are verified against, as
+     * PEM. Client certificates are only requested if a CA is given. */
+    clientCa?: string;
+    /** **UNSTABLE**: new API, yet to be vetted.
+     *
+     * The path of a PEM file with the client CA certificates. */
+    clientCaFile?: string;
+    /** **UNSTABLE**: new API, yet to be vetted.
+     *
+     * Whether clients that don't present a certificate are rejected. Defaults
+     * to `true`. */
+    requireClientCert?: boolean;
+    /** **UNSTABLE**: new API, yet to be vetted.
+     *
+     * The application protocols that the server supports with ALPN, in order
+     * of preference. */
+    alpnProtocols?: string[];
+  }
+
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * A certificate that `Deno.listenTls` presents to the clients that ask for
+   * `hostname` with SNI. The hostname may start with a `*.` wildcard label. */
+  export interface TlsSniCertificate {
+    hostname: string;
+    certChain?: string;
+    certChainFile?: string;
+    privateKey?: string;
+    privateKeyFile?: string;
+  }
+
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * The options of `Deno.listenTls` where the server certificate is given as
+   * PEM, by `certChain` and `privateKey`, or selected by the SNI hostname of
+   * each connection. The default certificate is presented to clients that
+   * don't match any of `certificates`. */
+  export interface ListenTlsPemOptions
+    extends
+      Omit<ListenTlsOptions, "certFile" | "keyFile">,
+      TlsClientCertOptions {
+    certificates?: TlsSniCertificate[];
+  }
+
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * Listens over TLS with a certificate that is given as PEM, or chosen per
+   * connection with SNI.
+   *
+   * ```ts
+   * const listener = Deno.listenTls({
+   *   port: 443,
+   *   certChain: Deno.readTextFileSync("./server.crt"),
+   *   privateKey: Deno.readTextFileSync("./server.key"),
+   *   alpnProtocols: ["h2", "http/1.1"],
+   * });
+   * ```
+   *
+   * Requires `allow-net` permission, and `allow-read` for the files. */
+  export function listenTls(options: ListenTlsPemOptions): Listener;
+
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * A connection that is secured with TLS, as returned by `Deno.connectTls`,
+   * `Deno.startTls` and the listener of `Deno.listenTls`. */
+  export interface TlsConn extends Conn {
+    /** The certificate that the peer presented, as PEM, or `null` if it
+     * didn't present one. */
+    readonly peerCertificate: string | null;
+    /** The application protocol that was negotiated with ALPN, or `null` if
+     * none was. */
+    readonly alpnProtocol: string | null;
+  }
+
+  export interface StartTlsOptions extends TlsClientCertOptions {
     /** A literal IP address or host name that can be resolved to an IP address.
      * If not specified, defaults to `127.0.0.1`. */
     hostname?: string;
     /** Server certificate file. */
     certFile?: string;
+    /** The application protocols that are offered to the server with ALPN, in
+     * order of preference. */
+    alpnProtocols?: string[];
   }
 
   /** **UNSTABLE**: new API, yet to be vetted.
@@ -989,7 +1200,244 @@ declare namespace Deno {
   export function startTls(
     conn: Conn,
     options?: StartTlsOptions,
-  ): Promise<Conn>;
+  ): Promise<TlsConn>;
+
+  export interface UpgradeWebSocketOptions {
+    /** The subprotocols the server supports, in order of preference. The
+     * first one that the client offers is selected. */
+    protocols?: string[];
+    /** The headers of the upgrade request, if it has already been read from
+     * the connection. Otherwise the request is read from the connection. */
+    headers?: HeadersInit;
+    /** The URL of the `WebSocket`, used when `headers` are given, as the
+     * request target is known only when the request is read. */
+    url?: string;
+  }
+
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * Upgrades an accepted connection to a WebSocket, replying to the upgrade
+   * request of the client. The connection is consumed and can't be used
+   * afterwards. The returned `WebSocket` is already open, so no `"open"`
+   * event is dispatched, and its `url` is the request target.
+   *
+   * ```ts
+   * const listener = Deno.listen({ port: 8080 });
+   * for await (const conn of listener) {
+   *   const socket = await Deno.upgradeWebSocket(conn);
+   *   socket.onmessage = (e) => socket.send(e.data);
+   * }
+   * ```
+   */
+  export function upgradeWebSocket(
+    conn: Conn,
+    options?: UpgradeWebSocketOptions,
+  ): Promise<WebSocket>;
+
+  /** **UNSTABLE**: new API, yet to be vetted. */
+  export interface ResolveDnsOptions {
+    /** The name server to query instead of the ones of the system
+     * configuration. */
+    nameServer?: {
+      /** The IP address of the name server. */
+      ipAddr: string;
+      /** The port of the name server. Defaults to `53`. */
+      port?: number;
+    };
+  }
+
+  /** **UNSTABLE**: new API, yet to be vetted. */
+  export type RecordType = "A" | "AAAA" | "CNAME" | "MX" | "SRV" | "TXT";
+
+  /** **UNSTABLE**: new API, yet to be vetted. */
+  export interface MXRecord {
+    preference: number;
+    exchange: string;
+  }
+
+  /** **UNSTABLE**: new API, yet to be vetted. */
+  export interface SRVRecord {
+    priority: number;
+    weight: number;
+    port: number;
+    target: string;
+  }
+
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * Queries DNS for the records of `query` with the given type. Names, like
+   * the `exchange` of MX records, are fully qualified and end with a dot.
+   * A TXT record is returned as its character strings.
+   *
+   * Throws `Deno.errors.NotFound` if the name has no records of the type.
+   *
+   * ```ts
+   * const a = await Deno.resolveDns("example.com", "A");
+   * const srv = await Deno.resolveDns("_http._tcp.example.com", "SRV", {
+   *   nameServer: { ipAddr: "1.1.1.1", port: 53 },
+   * });
+   * ```
+   *
+   * Requires `allow-net` permission for the queried name, and for the name
+   * server if one is given. */
+  export function resolveDns(
+    query: string,
+    recordType: "A" | "AAAA" | "CNAME",
+    options?: ResolveDnsOptions,
+  ): Promise<string[]>;
+
+  export function resolveDns(
+    query: string,
+    recordType: "MX",
+    options?: ResolveDnsOptions,
+  ): Promise<MXRecord[]>;
+
+  export function resolveDns(
+    query: string,
+    recordType: "SRV",
+    options?: ResolveDnsOptions,
+  ): Promise<SRVRecord[]>;
+
+  export function resolveDns(
+    query: string,
+    recordType: "TXT",
+    options?: ResolveDnsOptions,
+  ): Promise<string[][]>;
+
+  export function resolveDns(
+    query: string,
+    recordType: RecordType,
+    options?: ResolveDnsOptions,
+  ): Promise<string[] | MXRecord[] | SRVRecord[] | string[][]>;
+
+  export interface RunOptions {
+    /** **UNSTABLE**: new API, yet to be vetted.
+     *
+     * The user id that the subprocess runs as. Only supported on Unix. */
+    uid?: number;
+    /** **UNSTABLE**: new API, yet to be vetted.
+     *
+     * The group id that the subprocess runs as. Only supported on Unix. */
+    gid?: number;
+    /** **UNSTABLE**: new API, yet to be vetted.
+     *
+     * Start the subprocess in a new process group (`"group"` or `true`), or
+     * in a new session without a controlling terminal (`"session"`). On Unix
+     * the id of the group is the pid of the subprocess, so the whole group can
+     * be signalled with `Deno.kill(-p.pid, signo)`. */
+    detached?: boolean | "group" | "session";
+    /** **UNSTABLE**: new API, yet to be vetted.
+     *
+     * Don't inherit the environment variables of Deno, so that the
+     * subprocess only gets those in `env`. */
+    clearEnv?: boolean;
+    /** **UNSTABLE**: new API, yet to be vetted.
+     *
+     * Whether to kill the subprocess, and its process group if `detached`,
+     * when the process resource is closed or Deno exits. Defaults to `true`.
+     */
+    killOnDrop?: boolean;
+    /** **UNSTABLE**: new API, yet to be vetted.
+     *
+     * The time in milliseconds after which the subprocess, and its process
+     * group if `detached`, is killed with `SIGKILL`. */
+    timeout?: number;
+    /** **UNSTABLE**: new API, yet to be vetted.
+     *
+     * Run the subprocess in a new pseudo-terminal, which its stdio streams
+     * that are `"inherit"` are connected to. The subprocess leads a new
+     * session, and the master side of the terminal is `Process.pty`. Only
+     * supported on Linux. */
+    pty?: boolean;
+  }
+
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * The master side of the pseudo-terminal of a subprocess run with
+   * `pty: true`. Reading it returns the output of the subprocess, and writing
+   * it is input for the subprocess. `Deno.setRaw()` and `Deno.consoleSize()`
+   * also work on its `rid`.
+   *
+   * ```ts
+   * const p = Deno.run({ cmd: ["vim"], pty: true });
+   * p.pty!.resize(120, 40);
+   * ```
+   */
+  export interface Pty extends Reader, Writer, Closer {
+    readonly rid: number;
+    /** Sets the size of the terminal, which the subprocess is notified of with
+     * `SIGWINCH`. */
+    resize(columns: number, rows: number): void;
+  }
+
+  export interface Process<T extends RunOptions = RunOptions> {
+    /** **UNSTABLE**: new API, yet to be vetted.
+     *
+     * The master side of the pseudo-terminal of the subprocess, if it was run
+     * with `pty: true`. */
+    readonly pty?: Pty;
+  }
+
+  /** **UNSTABLE**: new API, yet to be vetted. */
+  export interface ExecOptions {
+    /** Arguments to pass. Note, the first element needs to be a path to the
+     * binary */
+    cmd: string[] | [URL, ...string[]];
+    cwd?: string;
+    env?: {
+      [key: string]: string;
+    };
+    /** Don't inherit the environment variables of Deno, so that the
+     * subprocess only gets those in `env`. */
+    clearEnv?: boolean;
+    /** Written to the stdin of the subprocess, which is `"null"` otherwise. */
+    input?: string | Uint8Array;
+    /** The time in milliseconds after which the subprocess is killed, and
+     * `Deno.errors.TimedOut` is thrown. This also applies when the subprocess
+     * has exited, but processes it started keep its stdout or stderr open. */
+    timeout?: number;
+    /** The most bytes of stdout, and of stderr, that are captured. The
+     * subprocess is killed, and a `RangeError` is thrown, once it outputs
+     * more. */
+    maxOutput?: number;
+  }
+
+  /** **UNSTABLE**: new API, yet to be vetted. */
+  export interface ExecOutput {
+    status: ProcessStatus;
+    stdout: Uint8Array;
+    stderr: Uint8Array;
+  }
+
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * Synchronously runs a subprocess to completion, and returns its status and
+   * the output that it wrote to stdout and stderr.
+   *
+   * ```ts
+   * const { status, stdout } = Deno.execSync({
+   *   cmd: ["git", "rev-parse", "HEAD"],
+   * });
+   * ```
+   *
+   * Requires `allow-run` permission. */
+  export function execSync(options: ExecOptions): ExecOutput;
+
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * Runs a subprocess to completion, and resolves to its status and the
+   * output that it wrote to stdout and stderr.
+   *
+   * ```ts
+   * const { status, stdout } = await Deno.exec({
+   *   cmd: ["cat"],
+   *   input: "hello",
+   *   timeout: 1000,
+   * });
+   * ```
+   *
+   * Requires `allow-run` permission. */
+  export function exec(options: ExecOptions): Promise<ExecOutput>;
 
   /** **UNSTABLE**: The `signo` argument may change to require the Deno.Signal
    * enum.
@@ -1176,6 +1624,236 @@ declare namespace Deno {
    */
   export function ftruncate(rid: number, len?: number): Promise<void>;
 
+  /** **UNSTABLE**: new API, yet to be vetted. */
+  export interface FlockOptions {
+    /** Take an exclusive lock rather than a shared one. Defaults to
+     * `false`. */
+    exclusive?: boolean;
+    /** Lock a byte range with `fcntl()`, rather than the whole file with
+     * `flock()`. Such locks are held by the process instead of the file, so
+     * they don't conflict with other locks of the same process. A `length`
+     * of `0`, the default, locks up to the end of the file. */
+    range?: { start: number; length?: number };
+  }
+
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * Takes an advisory lock on a file, and waits while another lock conflicts.
+   * Closing the file rejects the wait with `Deno.errors.Interrupted`, and
+   * releases the locks of the file. A lock of the whole file that was waited
+   * for is still taken once granted, and released right away.
+   *
+   * On Unix, a lock of a byte `range` is waited for by trying to take it
+   * again every 100 milliseconds at most, so it isn't granted in turn with
+   * other waiters, and may wait for long while the file is contended.
+   *
+   * ```ts
+   * const file = await Deno.open("state.json", { read: true, write: true });
+   * await Deno.flock(file.rid, { exclusive: true });
+   * ```
+   */
+  export function flock(rid: number, options?: FlockOptions): Promise<void>;
+
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * Takes an advisory lock on a file without waiting, and returns whether
+   * that succeeded, which it doesn't while another lock conflicts.
+   *
+   * ```ts
+   * const file = Deno.openSync("state.json", { read: true, write: true });
+   * if (!Deno.tryFlock(file.rid, { exclusive: true })) {
+   *   console.log("state.json is in use");
+   * }
+   * ```
+   */
+  export function tryFlock(rid: number, options?: FlockOptions): boolean;
+
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * Releases an advisory lock on a file, which is the lock of the given
+   * `range`, if any.
+   *
+   * ```ts
+   * Deno.funlock(file.rid);
+   * ```
+   */
+  export function funlock(
+    rid: number,
+    options?: Pick<FlockOptions, "range">,
+  ): void;
+
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * Returns the value of an extended attribute of a file, which is given by
+   * its path or by the rid of an open file, or `null` if it has no such
+   * attribute. Only supported on Linux and macOS.
+   *
+   * ```ts
+   * const value = Deno.getXattrSync("backup.tar", "user.checksum");
+   * ```
+   *
+   * Requires `allow-read` permission for a path. */
+  export function getXattrSync(
+    file: string | URL | number,
+    name: string,
+  ): Uint8Array | null;
+
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * Returns the value of an extended attribute of a file, which is given by
+   * its path or by the rid of an open file, or `null` if it has no such
+   * attribute. Only supported on Linux and macOS.
+   *
+   * ```ts
+   * const value = await Deno.getXattr("backup.tar", "user.checksum");
+   * ```
+   *
+   * Requires `allow-read` permission for a path. */
+  export function getXattr(
+    file: string | URL | number,
+    name: string,
+  ): Promise<Uint8Array | null>;
+
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * Sets an extended attribute of a file, which is given by its path or by
+   * the rid of an open file. A string value is encoded as UTF-8. Only
+   * supported on Linux and macOS.
+   *
+   * ```ts
+   * Deno.setXattrSync("backup.tar", "user.checksum", "9f86d081");
+   * ```
+   *
+   * Requires `allow-write` permission for a path. */
+  export function setXattrSync(
+    file: string | URL | number,
+    name: string,
+    value: string | Uint8Array,
+  ): void;
+
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * Sets an extended attribute of a file, which is given by its path or by
+   * the rid of an open file. A string value is encoded as UTF-8. Only
+   * supported on Linux and macOS.
+   *
+   * ```ts
+   * await Deno.setXattr("backup.tar", "user.checksum", "9f86d081");
+   * ```
+   *
+   * Requires `allow-write` permission for a path. */
+  export function setXattr(
+    file: string | URL | number,
+    name: string,
+    value: string | Uint8Array,
+  ): Promise<void>;
+
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * Returns the names of the extended attributes of a file, which is given
+   * by its path or by the rid of an open file. Only supported on Linux and
+   * macOS.
+   *
+   * ```ts
+   * console.log(Deno.listXattrSync("backup.tar")); // [ "user.checksum" ]
+   * ```
+   *
+   * Requires `allow-read` permission for a path. */
+  export function listXattrSync(file: string | URL | number): string[];
+
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * Returns the names of the extended attributes of a file, which is given
+   * by its path or by the rid of an open file. Only supported on Linux and
+   * macOS.
+   *
+   * ```ts
+   * console.log(await Deno.listXattr("backup.tar")); // [ "user.checksum" ]
+   * ```
+   *
+   * Requires `allow-read` permission for a path. */
+  export function listXattr(file: string | URL | number): Promise<string[]>;
+
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * Removes an extended attribute of a file, which is given by its path or
+   * by the rid of an open file. Only supported on Linux and macOS.
+   *
+   * ```ts
+   * Deno.removeXattrSync("backup.tar", "user.checksum");
+   * ```
+   *
+   * Requires `allow-write` permission for a path. */
+  export function removeXattrSync(
+    file: string | URL | number,
+    name: string,
+  ): void;
+
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * Removes an extended attribute of a file, which is given by its path or
+   * by the rid of an open file. Only supported on Linux and macOS.
+   *
+   * ```ts
+   * await Deno.removeXattr("backup.tar", "user.checksum");
+   * ```
+   *
+   * Requires `allow-write` permission for a path. */
+  export function removeXattr(
+    file: string | URL | number,
+    name: string,
+  ): Promise<void>;
+
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * Information about a filesystem, as returned by `Deno.statfs()`. */
+  export interface StatfsInfo {
+    /** The type of the filesystem, like `"xfs"` or `"apfs"`. On Linux, ext2,
+     * ext3 and ext4 are all `"ext2/ext3/ext4"`, and an unknown type is given
+     * by its magic number in hex. */
+    type: string;
+    /** The size of a block in bytes. */
+    blockSize: number;
+    /** The number of blocks of the filesystem. */
+    blocks: number;
+    /** The number of free blocks. */
+    blocksFree: number;
+    /** The number of free blocks available to unprivileged users. */
+    blocksAvailable: number;
+    /** The number of inodes of the filesystem. */
+    files: number;
+    /** The number of free inodes. */
+    filesFree: number;
+    /** The number of free inodes available to unprivileged users. */
+    filesAvailable: number;
+  }
+
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * Synchronously returns information about the filesystem which contains
+   * the given path. Not supported on Windows.
+   *
+   * ```ts
+   * const info = Deno.statfsSync("/backups");
+   * console.log(info.blocksAvailable * info.blockSize); // free bytes
+   * ```
+   *
+   * Requires `allow-read` permission. */
+  export function statfsSync(path: string | URL): StatfsInfo;
+
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * Returns information about the filesystem which contains the given path.
+   * Not supported on Windows.
+   *
+   * ```ts
+   * const info = await Deno.statfs("/backups");
+   * console.log(info.blocksAvailable * info.blockSize); // free bytes
+   * ```
+   *
+   * Requires `allow-read` permission. */
+  export function statfs(path: string | URL): Promise<StatfsInfo>;
+
   /** **UNSTABLE**: New API, yet to be vetted.
    * Synchronously returns a `Deno.FileInfo` for the given file stream.
    *
@@ -1219,10 +1897,46 @@ declare namespace Deno {
   /** **UNSTABLE**: New API, yet to be vetted.
    * The options used when creating a [HttpClient].
    */
-  interface CreateHttpClientOptions {
+  interface CreateHttpClientOptions extends TlsClientCertOptions {
     /** A certificate authority to use when validating TLS certificates. Certificate data must be PEM encoded.
      */
     caData?: string;
+    /** The longest time in milliseconds to wait for a connection to be
+     * established. */
+    connectTimeout?: number;
+    /** The longest time in milliseconds to wait for the response headers, and
+     * for each chunk of the response body. A request that times out fails
+     * with `Deno.errors.TimedOut`. */
+    readTimeout?: number;
+    /** The maximum number of idle connections that are kept per host. `0`
+     * disables connection reuse. */
+    poolMaxIdlePerHost?: number;
+    /** The time in milliseconds after which idle connections are closed. */
+    poolIdleTimeout?: number;
+    /** Only use HTTP/1.1. */
+    http1Only?: boolean;
+    /** Use HTTP/2 without negotiating it, for servers that are known to
+     * support it. Can't be used with `http1Only`. */
+    http2PriorKnowledge?: boolean;
+    /** The `User-Agent` header of the requests. */
+    userAgent?: string;
+    /** Whether to decompress gzip and brotli encoded response bodies, which
+     * defaults to `true`. */
+    decompress?: boolean;
+    /** The path of a Unix socket to send the requests of `http:` URLs over,
+     * which requires read and write permission for the path. A single request
+     * can also be sent over a socket with a `http+unix:` URL, whose host is the
+     * percent-encoded socket path:
+     *
+     * ```ts
+     * const client = Deno.createHttpClient({
+     *   unixSocket: "/var/run/docker.sock",
+     * });
+     * await fetch("http://localhost/info", { client });
+     * await fetch("http+unix://%2Fvar%2Frun%2Fdocker.sock/info");
+     * ```
+     */
+    unixSocket?: string;
   }
 
   /** **UNSTABLE**: New API, yet to be vetted.
@@ -8,7 +8,7 @@ use crate::http_util::FetchOnceResult;
 use crate::media_type::MediaType;
 use crate::text_encoding;
 use crate::version::get_user_agent;
-use deno_runtime::permissions::Permissions;
+use deno_runtime::permissions::PermissionsContainer;
 
 use deno_core::error::custom_error;
 use deno_core::error::generic_error;
@@ -18,6 +18,7 @@ use deno_core::futures;
 use deno_core::futures::future::FutureExt;
 use deno_core::ModuleSpecifier;
 use deno_runtime::deno_fetch::reqwest;
+use deno_runtime::deno_web::BlobUrlStore;
 use std::collections::HashMap;
 use std::fs;
 use std::future::Future;
@@ -27,7 +28,8 @@ use std::pin::Pin;
 use std::sync::Arc;
 use std::sync::Mutex;
 
-pub const SUPPORTED_SCHEMES: [&str; 4] = ["data", "file", "http", "https"];
+pub const SUPPORTED_SCHEMES: [&str; 5] =
+  ["data", "blob", "file", "http", "https"];
 
 /// A structure representing a source file.
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -309,6 +311,7 @@ fn strip_shebang(mut value: String) -> String {
 #[derive(Clone)]
 pub struct FileFetcher {
   allow_remote: bool,
+  blob_url_store: BlobUrlStore,
   cache: FileCache,
   cache_setting: CacheSetting,
   http_cache: HttpCache,
@@ -321,9 +324,11 @@ impl FileFetcher {
     cache_setting: CacheSetting,
     allow_remote: bool,
     ca_data: Option<Vec<u8>>,
+    blob_url_store: BlobUrlStore,
   ) -> Result<Self, AnyError> {
     Ok(Self {
       allow_remote,
+      blob_url_store,
       cache: FileCache::default(),
       cache_setting,
       http_cache,
@@ -432,6 +437,40 @@ impl FileFetcher {
     })
   }
 
+  /// Convert a blob URL into a file, resulting in an error if the URL has not
+  /// been created with `URL.createObjectURL()` or has been revoked. Blob URLs
+  /// only live as long as the program, so they are never cached on disk.
+  fn fetch_blob_url(
+    &self,
+    specifier: &ModuleSpecifier,
+  ) -> Result<File, AnyError> {
+    debug!("FileFetcher::fetch_blob_url() - specifier: {}", specifier);
+    let blob =
+      self.blob_url_store.get(specifier.as_url()).ok_or_else(|| {
+        custom_error(
+          "NotFound",
+          format!("Blob URL not found: \"{}\".", specifier),
+        )
+      })?;
+    let maybe_content_type = if blob.media_type.is_empty() {
+      None
+    } else {
+      Some(blob.media_type)
+    };
+    let (media_type, maybe_charset) =
+      map_content_type(specifier, maybe_content_type);
+    let source =
+      strip_shebang(get_source_from_bytes(blob.data, maybe_charset)?);
+
+    Ok(File {
+      local: PathBuf::new(),
+      maybe_types: None,
+      media_type,
+      source,
+      specifier: specifier.clone(),
+    })
+  }
+
   /// Asynchronously fetch remote source file specified by the URL following
   /// redirects.
   ///
@@ -440,7 +479,7 @@ impl FileFetcher {
   fn fetch_remote(
     &self,
     specifier: &ModuleSpecifier,
-    permissions: &Permissions,
+    permissions: &PermissionsContainer,
     redirect_limit: i64,
   ) -> Pin<Box<dyn Future<Output = Result<File, AnyError>> + Send>> {
     debug!("FileFetcher::fetch_remote() - specifier: {}", specifier);
@@ -521,7 +560,7 @@ impl FileFetcher {
   pub async fn fetch(
     &self,
     specifier: &ModuleSpecifier,
-    permissions: &Permissions,
+    permissions: &PermissionsContainer,
   ) -> Result<File, AnyError> {
     debug!("FileFetcher::fetch() - specifier: {}", specifier);
     let scheme = get_validated_scheme(specifier)?;
@@ -538,6 +577,10 @@ impl FileFetcher {
         self.cache.insert(specifier.clone(), file.clone());
       }
       result
+    } else if scheme == "blob" {
+      // blob URLs are not in memory cached either, so that revoking one
+      // prevents it from being fetched again.
+      self.fetch_blob_url(specifier)
     } else if !self.allow_remote {
       Err(custom_error(
         "NoRemote",
@@ -585,6 +628,7 @@ impl FileFetcher {
 mod tests {
   use super::*;
   use deno_core::error::get_custom_error_class;
+  use deno_runtime::deno_web::Blob;
   use std::rc::Rc;
   use tempfile::TempDir;
 
@@ -596,9 +640,14 @@ mod tests {
       Rc::new(TempDir::new().expect("failed to create temp directory"))
     });
     let location = temp_dir.path().join("deps");
-    let file_fetcher =
-      FileFetcher::new(HttpCache::new(&location), cache_setting, true, None)
-        .expect("setup failed");
+    let file_fetcher = FileFetcher::new(
+      HttpCache::new(&location),
+      cache_setting,
+      true,
+      None,
+      BlobUrlStore::default(),
+    )
+    .expect("setup failed");
     (file_fetcher, temp_dir)
   }
 
@@ -615,7 +664,7 @@ mod tests {
   async fn test_fetch(specifier: &ModuleSpecifier) -> (File, FileFetcher) {
     let (file_fetcher, _) = setup(CacheSetting::ReloadAll, None);
     let result = file_fetcher
-      .fetch(specifier, &Permissions::allow_all())
+      .fetch(specifier, &PermissionsContainer::allow_all())
       .await;
     assert!(result.is_ok());
     (result.unwrap(), file_fetcher)
@@ -627,7 +676,7 @@ mod tests {
     let _http_server_guard = test_util::http_server();
     let (file_fetcher, _) = setup(CacheSetting::ReloadAll, None);
     let result: Result<File, AnyError> = file_fetcher
-      .fetch_remote(specifier, &Permissions::allow_all(), 1)
+      .fetch_remote(specifier, &PermissionsContainer::allow_all(), 1)
       .await;
     assert!(result.is_ok());
     let (_, headers) = file_fetcher.http_cache.get(specifier.as_url()).unwrap();
@@ -701,6 +750,11 @@ mod tests {
       ("file:///a/b/c.ts", true, "file"),
       ("file:///C:/a/b/c.ts", true, "file"),
       ("data:,some%20text", true, "data"),
+      (
+        "blob:null/a3d1c5c6-5b8c-4c57-8e2e-63b7b5a6f0a1",
+        true,
+        "blob",
+      ),
       ("ftp://a/b/c.ts", false, ""),
       ("mailto:dino@deno.land", false, ""),
     ];
@@ -910,7 +964,7 @@ mod tests {
     file_fetcher.insert_cached(file.clone());
 
     let result = file_fetcher
-      .fetch(&specifier, &Permissions::allow_all())
+      .fetch(&specifier, &PermissionsContainer::allow_all())
       .await;
     assert!(result.is_ok());
     let result_file = result.unwrap();
@@ -927,7 +981,7 @@ mod tests {
     .unwrap();
 
     let result = file_fetcher
-      .fetch(&specifier, &Permissions::allow_all())
+      .fetch(&specifier, &PermissionsContainer::allow_all())
       .await;
     assert!(result.is_ok());
 
@@ -958,7 +1012,7 @@ mod tests {
     let specifier = ModuleSpecifier::resolve_url("data:application/typescript;base64,ZXhwb3J0IGNvbnN0IGEgPSAiYSI7CgpleHBvcnQgZW51bSBBIHsKICBBLAogIEIsCiAgQywKfQo=").unwrap();
 
     let result = file_fetcher
-      .fetch(&specifier, &Permissions::allow_all())
+      .fetch(&specifier, &PermissionsContainer::allow_all())
       .await;
     assert!(result.is_ok());
     let file = result.unwrap();
@@ -971,6 +1025,52 @@ mod tests {
     assert_eq!(file.specifier, specifier);
   }
 
+  #[tokio::test]
+  async fn test_fetch_blob_url() {
+    let temp_dir = TempDir::new().expect("failed to create temp directory");
+    let blob_url_store = BlobUrlStore::default();
+    let file_fetcher = FileFetcher::new(
+      HttpCache::new(&temp_dir.path().join("deps")),
+      CacheSetting::Use,
+      true,
+      None,
+      blob_url_store.clone(),
+    )
+    .expect("setup failed");
+    let blob = Blob {
+      data: b"export const a: string = \"a\";\n".to_vec(),
+      media_type: "application/typescript".to_string(),
+    };
+    let url = blob_url_store.insert(blob, "null").unwrap();
+    let specifier = ModuleSpecifier::from(url.clone());
+
+    let result = file_fetcher
+      .fetch(&specifier, &PermissionsContainer::allow_all())
+      .await;
+    assert!(result.is_ok());
+    let file = result.unwrap();
+    assert_eq!(file.source, "export const a: string = \"a\";\n");
+    assert_eq!(file.media_type, MediaType::TypeScript);
+    assert_eq!(file.specifier, specifier);
+
+    let with_fragment =
+      ModuleSpecifier::resolve_url(&format!("{}#a", url)).unwrap();
+    let result = file_fetcher
+      .fetch(&with_fragment, &PermissionsContainer::allow_all())
+      .await;
+    assert!(result.is_ok());
+
+    blob_url_store.remove(&url);
+    let result = file_fetcher
+      .fetch(&specifier, &PermissionsContainer::allow_all())
+      .await;
+    assert!(result.is_err());
+    assert_eq!(
+      get_custom_error_class(&result.unwrap_err()),
+      Some("NotFound")
+    );
+  }
+
   #[tokio::test]
   async fn test_fetch_complex() {
     let _http_server_guard = test_util::http_server();
@@ -983,7 +1083,7 @@ mod tests {
     .unwrap();
 
     let result = file_fetcher
-      .fetch(&specifier, &Permissions::allow_all())
+      .fetch(&specifier, &PermissionsContainer::allow_all())
       .await;
     assert!(result.is_ok());
     let file = result.unwrap();
@@ -1005,7 +1105,7 @@ mod tests {
     metadata.write(&cache_filename).unwrap();
 
     let result = file_fetcher_01
-      .fetch(&specifier, &Permissions::allow_all())
+      .fetch(&specifier, &PermissionsContainer::allow_all())
       .await;
     assert!(result.is_ok());
     let file = result.unwrap();
@@ -1027,7 +1127,7 @@ mod tests {
     metadata.write(&cache_filename).unwrap();
 
     let result = file_fetcher_02
-      .fetch(&specifier, &Permissions::allow_all())
+      .fetch(&specifier, &PermissionsContainer::allow_all())
       .await;
     assert!(result.is_ok());
     let file = result.unwrap();
@@ -1045,10 +1145,11 @@ mod tests {
       CacheSetting::ReloadAll,
       true,
       None,
+      BlobUrlStore::default(),
     )
     .expect("setup failed");
     let result = file_fetcher
-      .fetch(&specifier, &Permissions::allow_all())
+      .fetch(&specifier, &PermissionsContainer::allow_all())
       .await;
     assert!(result.is_ok());
     let file = result.unwrap();
@@ -1071,6 +1172,7 @@ mod tests {
       CacheSetting::Use,
       true,
       None,
+      BlobUrlStore::default(),
     )
     .expect("could not create file fetcher");
     let specifier = ModuleSpecifier::resolve_url(
@@ -1082,7 +1184,7 @@ mod tests {
       .get_cache_filename(specifier.as_url());
 
     let result = file_fetcher_01
-      .fetch(&specifier, &Permissions::allow_all())
+      .fetch(&specifier, &PermissionsContainer::allow_all())
       .await;
     assert!(result.is_ok());
 
@@ -1098,10 +1200,11 @@ mod tests {
       CacheSetting::Use,
       true,
       None,
+      BlobUrlStore::default(),
     )
     .expect("could not create file fetcher");
     let result = file_fetcher_02
-      .fetch(&specifier, &Permissions::allow_all())
+      .fetch(&specifier, &PermissionsContainer::allow_all())
       .await;
     assert!(result.is_ok());
 
@@ -1138,7 +1241,7 @@ mod tests {
       .get_cache_filename(redirected_specifier.as_url());
 
     let result = file_fetcher
-      .fetch(&specifier, &Permissions::allow_all())
+      .fetch(&specifier, &PermissionsContainer::allow_all())
       .await;
     assert!(result.is_ok());
     let file = result.unwrap();
@@ -1196,7 +1299,7 @@ mod tests {
       .get_cache_filename(redirected_02_specifier.as_url());
 
     let result = file_fetcher
-      .fetch(&specifier, &Permissions::allow_all())
+      .fetch(&specifier, &PermissionsContainer::allow_all())
       .await;
     assert!(result.is_ok());
     let file = result.unwrap();
@@ -1253,6 +1356,7 @@ mod tests {
       CacheSetting::Use,
       true,
       None,
+      BlobUrlStore::default(),
     )
     .expect("could not create file fetcher");
     let specifier = ModuleSpecifier::resolve_url(
@@ -1268,7 +1372,7 @@ mod tests {
       .get_cache_filename(redirected_specifier.as_url());
 
     let result = file_fetcher_01
-      .fetch(&specifier, &Permissions::allow_all())
+      .fetch(&specifier, &PermissionsContainer::allow_all())
       .await;
     assert!(result.is_ok());
 
@@ -1284,10 +1388,11 @@ mod tests {
       CacheSetting::Use,
       true,
       None,
+      BlobUrlStore::default(),
     )
     .expect("could not create file fetcher");
     let result = file_fetcher_02
-      .fetch(&redirected_specifier, &Permissions::allow_all())
+      .fetch(&redirected_specifier, &PermissionsContainer::allow_all())
       .await;
     assert!(result.is_ok());
 
@@ -1314,12 +1419,12 @@ mod tests {
     .unwrap();
 
     let result = file_fetcher
-      .fetch_remote(&specifier, &Permissions::allow_all(), 2)
+      .fetch_remote(&specifier, &PermissionsContainer::allow_all(), 2)
       .await;
     assert!(result.is_ok());
 
     let result = file_fetcher
-      .fetch_remote(&specifier, &Permissions::allow_all(), 1)
+      .fetch_remote(&specifier, &PermissionsContainer::allow_all(), 1)
       .await;
     assert!(result.is_err());
 
@@ -1350,7 +1455,7 @@ mod tests {
       .get_cache_filename(redirected_specifier.as_url());
 
     let result = file_fetcher
-      .fetch(&specifier, &Permissions::allow_all())
+      .fetch(&specifier, &PermissionsContainer::allow_all())
       .await;
     assert!(result.is_ok());
     let file = result.unwrap();
@@ -1391,6 +1496,7 @@ mod tests {
       CacheSetting::Use,
       false,
       None,
+      BlobUrlStore::default(),
     )
     .expect("could not create file fetcher");
     let specifier = ModuleSpecifier::resolve_url(
@@ -1399,7 +1505,7 @@ mod tests {
     .unwrap();
 
     let result = file_fetcher
-      .fetch(&specifier, &Permissions::allow_all())
+      .fetch(&specifier, &PermissionsContainer::allow_all())
       .await;
     assert!(result.is_err());
     let err = result.unwrap_err();
@@ -1419,6 +1525,7 @@ mod tests {
       CacheSetting::Only,
       true,
       None,
+      BlobUrlStore::default(),
     )
     .expect("could not create file fetcher");
     let file_fetcher_02 = FileFetcher::new(
@@ -1426,6 +1533,7 @@ mod tests {
       CacheSetting::Use,
       true,
       None,
+      BlobUrlStore::default(),
     )
     .expect("could not create file fetcher");
     let specifier = ModuleSpecifier::resolve_url(
@@ -1434,7 +1542,7 @@ mod tests {
     .unwrap();
 
     let result = file_fetcher_01
-      .fetch(&specifier, &Permissions::allow_all())
+      .fetch(&specifier, &PermissionsContainer::allow_all())
       .await;
     assert!(result.is_err());
     let err = result.unwrap_err();
@@ -1442,12 +1550,12 @@ mod tests {
     assert_eq!(err.to_string(), "Specifier not found in cache: \"http://localhost:4545/cli/tests/002_hello.ts\", --cached-only is specified.");
 
     let result = file_fetcher_02
-      .fetch(&specifier, &Permissions::allow_all())
+      .fetch(&specifier, &PermissionsContainer::allow_all())
       .await;
     assert!(result.is_ok());
 
     let result = file_fetcher_01
-      .fetch(&specifier, &Permissions::allow_all())
+      .fetch(&specifier, &PermissionsContainer::allow_all())
       .await;
     assert!(result.is_ok());
 
@@ -1466,7 +1574,7 @@ mod tests {
     fs::write(fixture_path.clone(), r#"console.log("hello deno");"#)
       .expect("could not write file");
     let result = file_fetcher
-      .fetch(&specifier, &Permissions::allow_all())
+      .fetch(&specifier, &PermissionsContainer::allow_all())
       .await;
     assert!(result.is_ok());
     let file = result.unwrap();
@@ -1475,7 +1583,7 @@ mod tests {
     fs::write(fixture_path, r#"console.log("goodbye deno");"#)
       .expect("could not write file");
     let result = file_fetcher
-      .fetch(&specifier, &Permissions::allow_all())
+      .fetch(&specifier, &PermissionsContainer::allow_all())
       .await;
     assert!(result.is_ok());
     let file = result.unwrap();
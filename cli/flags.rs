@@ -116,6 +116,7 @@ pub struct Flags {
   pub cached_only: bool,
   pub config_path: Option<String>,
   pub coverage_dir: Option<String>,
+  pub env_file: Option<String>,
   pub ignore: Vec<PathBuf>,
   pub import_map_path: Option<String>,
   pub inspect: Option<SocketAddr>,
@@ -126,6 +127,7 @@ pub struct Flags {
   pub no_check: bool,
   pub no_prompts: bool,
   pub no_remote: bool,
+  pub permissions_manifest: Option<String>,
   pub reload: bool,
   pub repl: bool,
   pub seed: Option<u64>,
@@ -212,6 +214,14 @@ impl From<Flags> for PermissionsOptions {
       allow_read: flags.allow_read,
       allow_run: flags.allow_run,
       allow_write: flags.allow_write,
+      deny_env: false,
+      deny_hrtime: false,
+      deny_net: None,
+      deny_plugin: false,
+      deny_read: None,
+      deny_run: false,
+      deny_write: None,
+      scopes: Default::default(),
     }
   }
 }
@@ -407,6 +417,7 @@ fn fmt_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
 
 fn install_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   runtime_args_parse(flags, matches, true, true);
+  env_file_arg_parse(flags, matches);
 
   let root = if matches.is_present("root") {
     let install_root = matches.value_of("root").unwrap();
@@ -491,6 +502,7 @@ fn completions_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
 
 fn repl_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   runtime_args_parse(flags, matches, false, true);
+  env_file_arg_parse(flags, matches);
   flags.repl = true;
   flags.subcommand = DenoSubcommand::Repl;
   flags.allow_net = Some(vec![]);
@@ -504,6 +516,7 @@ fn repl_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
 
 fn eval_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   runtime_args_parse(flags, matches, false, true);
+  env_file_arg_parse(flags, matches);
   flags.allow_net = Some(vec![]);
   flags.allow_env = true;
   flags.allow_run = true;
@@ -629,6 +642,7 @@ fn runtime_args_parse(
 
 fn run_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   runtime_args_parse(flags, matches, true, true);
+  env_file_arg_parse(flags, matches);
 
   let mut script: Vec<String> = matches
     .values_of("script_arg")
@@ -648,6 +662,7 @@ fn run_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
 
 fn test_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   runtime_args_parse(flags, matches, true, true);
+  env_file_arg_parse(flags, matches);
 
   let no_run = matches.is_present("no-run");
   let fail_fast = matches.is_present("fail-fast");
@@ -822,11 +837,13 @@ Ignore formatting a file by adding an ignore comment at the top of the file:
 
 fn repl_subcommand<'a, 'b>() -> App<'a, 'b> {
   runtime_args(SubCommand::with_name("repl"), false, true)
+    .arg(env_file_arg())
     .about("Read Eval Print Loop")
 }
 
 fn install_subcommand<'a, 'b>() -> App<'a, 'b> {
   runtime_args(SubCommand::with_name("install"), true, true)
+        .arg(env_file_arg())
         .setting(AppSettings::TrailingVarArg)
         .arg(
           Arg::with_name("cmd")
@@ -952,6 +969,7 @@ fn completions_subcommand<'a, 'b>() -> App<'a, 'b> {
 
 fn eval_subcommand<'a, 'b>() -> App<'a, 'b> {
   runtime_args(SubCommand::with_name("eval"), false, true)
+    .arg(env_file_arg())
     .about("Eval script")
     .long_about(
       "Evaluate JavaScript from the command line.
@@ -1268,10 +1286,30 @@ fn permission_args<'a, 'b>(app: App<'a, 'b>) -> App<'a, 'b> {
         .long("allow-all")
         .help("Allow all permissions"),
     )
+    .arg(
+      Arg::with_name("permissions")
+        .long("permissions")
+        .value_name("FILE")
+        .takes_value(true)
+        .require_equals(true)
+        .help("Load allowed and denied permissions from a JSON manifest")
+        .long_help(
+          "Load allowed and denied permissions from a JSON manifest. The file
+has the same keys as the permission flags, plus deny lists, e.g.
+  {
+    \"allow_read\": [\"./data\"],
+    \"deny_read\": [\"./data/secrets\"],
+    \"allow_net\": [\"deno.land\"],
+    \"allow_env\": true
+  }
+Permissions given by flags are added to the ones in the manifest.",
+        ),
+    )
 }
 
 fn run_subcommand<'a, 'b>() -> App<'a, 'b> {
   runtime_args(SubCommand::with_name("run"), true, true)
+    .arg(env_file_arg())
     .arg(
       watch_arg()
         .conflicts_with("inspect")
@@ -1306,6 +1344,7 @@ Deno allows specifying the filename '-' to read the file from stdin.
 
 fn test_subcommand<'a, 'b>() -> App<'a, 'b> {
   runtime_args(SubCommand::with_name("test"), true, true)
+    .arg(env_file_arg())
     .setting(AppSettings::TrailingVarArg)
     .arg(
       Arg::with_name("no-run")
@@ -1606,6 +1645,29 @@ fn seed_arg_parse(flags: &mut Flags, matches: &ArgMatches) {
   }
 }
 
+fn env_file_arg<'a, 'b>() -> Arg<'a, 'b> {
+  Arg::with_name("env-file")
+    .long("env-file")
+    .value_name("FILE")
+    .help("Load environment variables from a dotenv file (default: .env)")
+    .long_help(
+      "Load environment variables from a dotenv file before running, without
+overriding variables that are already set. Defaults to '.env' in the current
+directory. Access from JavaScript still requires --allow-env.",
+    )
+    .min_values(0)
+    .max_values(1)
+    .require_equals(true)
+    .takes_value(true)
+}
+
+fn env_file_arg_parse(flags: &mut Flags, matches: &ArgMatches) {
+  if matches.is_present("env-file") {
+    let env_file = matches.value_of("env-file").unwrap_or(".env");
+    flags.env_file = Some(env_file.to_string());
+  }
+}
+
 fn cached_only_arg<'a, 'b>() -> Arg<'a, 'b> {
   Arg::with_name("cached-only")
     .long("cached-only")
@@ -1682,6 +1744,8 @@ fn permission_args_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
     flags.allow_plugin = true;
     flags.allow_hrtime = true;
   }
+  flags.permissions_manifest =
+    matches.value_of("permissions").map(ToOwned::to_owned);
 }
 
 // TODO(ry) move this to utility module and add test.
@@ -2477,6 +2541,63 @@ mod tests {
     );
   }
 
+  #[test]
+  fn permissions_manifest() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--permissions=permissions.json",
+      "--allow-read",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run {
+          script: "script.ts".to_string(),
+        },
+        permissions_manifest: Some("permissions.json".to_string()),
+        allow_read: Some(vec![]),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "eval",
+      "--permissions=permissions.json",
+      "42"
+    ]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn env_file() {
+    let r =
+      flags_from_vec(svec!["deno", "run", "--env-file", "script.ts", "arg"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run {
+          script: "script.ts".to_string(),
+        },
+        argv: svec!["arg"],
+        env_file: Some(".env".to_string()),
+        ..Flags::default()
+      }
+    );
+
+    let r =
+      flags_from_vec(svec!["deno", "eval", "--env-file=config/dev.env", "42"]);
+    assert_eq!(r.unwrap().env_file, Some("config/dev.env".to_string()));
+
+    let r = flags_from_vec(svec!["deno", "test", "--env-file=.env.test"]);
+    assert_eq!(r.unwrap().env_file, Some(".env.test".to_string()));
+
+    let r = flags_from_vec(svec!["deno", "compile", "--env-file", "script.ts"]);
+    assert!(r.is_err());
+  }
+
   #[test]
   fn allow_net_allowlist() {
     let r = flags_from_vec(svec![
@@ -1,6 +1,7 @@
 // Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
 
 use deno_core::url::Url;
+use ipnet::IpNet;
 use std::net::IpAddr;
 use std::str::FromStr;
 
@@ -26,9 +27,39 @@ impl FromStr for BarePort {
   }
 }
 
+/// Returns true if the entry is a CIDR block (eg. "10.0.0.0/8", "fd00::/8")
+/// or a leading-wildcard hostname with an optional port (eg. "*.deno.land",
+/// "*.deno.land:443").
+fn is_net_pattern(entry: &str) -> bool {
+  if entry.parse::<IpNet>().is_ok() {
+    return true;
+  }
+  match entry.strip_prefix("*.") {
+    Some(rest) if !rest.contains('*') && !rest.contains('/') => {
+      match Url::parse(&format!("deno://{}", rest)) {
+        Ok(url) => match url.host_str() {
+          Some(host) => !host.is_empty() && host.parse::<IpAddr>().is_err(),
+          None => false,
+        },
+        Err(_) => false,
+      }
+    }
+    _ => false,
+  }
+}
+
+/// Returns true if the entry is an exact host with an optional port.
+fn is_host_and_port(entry: &str) -> bool {
+  if entry.contains('*') || entry.contains('/') {
+    return false;
+  }
+  Url::parse(&format!("deno://{}", entry)).is_ok()
+    || entry.parse::<IpAddr>().is_ok()
+}
+
 pub fn validator(host_and_port: String) -> Result<(), String> {
-  if Url::parse(&format!("deno://{}", host_and_port)).is_ok()
-    || host_and_port.parse::<IpAddr>().is_ok()
+  if is_net_pattern(&host_and_port)
+    || is_host_and_port(&host_and_port)
     || host_and_port.parse::<BarePort>().is_ok()
   {
     Ok(())
@@ -39,13 +70,12 @@ pub fn validator(host_and_port: String) -> Result<(), String> {
 
 /// Expands "bare port" paths (eg. ":8080") into full paths with hosts. It
 /// expands to such paths into 3 paths with following hosts: `0.0.0.0:port`,
-/// `127.0.0.1:port` and `localhost:port`.
+/// `127.0.0.1:port` and `localhost:port`. CIDR blocks and wildcard hostnames
+/// are passed through unchanged.
 pub fn parse(paths: Vec<String>) -> clap::Result<Vec<String>> {
   let mut out: Vec<String> = vec![];
   for host_and_port in paths.iter() {
-    if Url::parse(&format!("deno://{}", host_and_port)).is_ok()
-      || host_and_port.parse::<IpAddr>().is_ok()
-    {
+    if is_net_pattern(host_and_port) || is_host_and_port(host_and_port) {
       out.push(host_and_port.to_owned())
     } else if let Ok(port) = host_and_port.parse::<BarePort>() {
       // we got bare port, let's add default hosts
@@ -181,6 +211,34 @@ mod tests {
     assert_eq!(actual, expected);
   }
 
+  #[test]
+  fn parse_net_args_patterns() {
+    let entries = svec![
+      "10.0.0.0/8",
+      "192.168.1.0/24",
+      "fd00::/8",
+      "*.corp.example",
+      "*.corp.example:8443"
+    ];
+    let expected = svec![
+      "10.0.0.0/8",
+      "192.168.1.0/24",
+      "fd00::/8",
+      "*.corp.example",
+      "*.corp.example:8443"
+    ];
+    let actual = parse(entries).unwrap();
+    assert_eq!(actual, expected);
+  }
+
+  #[test]
+  fn parse_net_args_patterns_error() {
+    assert!(parse(svec!["10.0.0.0/33"]).is_err());
+    assert!(parse(svec!["*.*.corp.example"]).is_err());
+    assert!(parse(svec!["*."]).is_err());
+    assert!(parse(svec!["*.10.0.0.1"]).is_err());
+  }
+
   #[test]
   fn parse_net_args_ipv6_error1() {
     let entries = svec![":::"];
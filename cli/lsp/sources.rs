@@ -14,7 +14,7 @@ use crate::module_graph::GraphBuilder;
 use crate::program_state::ProgramState;
 use crate::specifier_handler::FetchHandler;
 use crate::text_encoding;
-use deno_runtime::permissions::Permissions;
+use deno_runtime::permissions::PermissionsContainer;
 
 use deno_core::error::AnyError;
 use deno_core::serde_json;
@@ -34,7 +34,7 @@ pub async fn cache(
   let program_state = Arc::new(ProgramState::new(Default::default())?);
   let handler = Arc::new(Mutex::new(FetchHandler::new(
     &program_state,
-    Permissions::allow_all(),
+    PermissionsContainer::allow_all(),
   )?));
   let mut builder = GraphBuilder::new(handler, maybe_import_map, None);
   builder.add(&specifier, false).await
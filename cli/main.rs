@@ -31,6 +31,7 @@ mod media_type;
 mod module_graph;
 mod module_loader;
 mod ops;
+mod permissions_manifest;
 mod program_state;
 mod source_maps;
 mod specifier_handler;
@@ -68,6 +69,7 @@ use deno_doc as doc;
 use deno_doc::parser::DocFileLoader;
 use deno_runtime::ops::worker_host::CreateWebWorkerCb;
 use deno_runtime::permissions::Permissions;
+use deno_runtime::permissions::PermissionsContainer;
 use deno_runtime::web_worker::WebWorker;
 use deno_runtime::web_worker::WebWorkerOptions;
 use deno_runtime::worker::MainWorker;
@@ -127,6 +129,7 @@ fn create_web_worker_callback(
       ts_version: version::TYPESCRIPT.to_string(),
       no_color: !colors::use_color(),
       get_error_class_fn: Some(&crate::errors::get_error_class_name),
+      blob_url_store: program_state.blob_url_store.clone(),
     };
 
     let mut worker = WebWorker::from_options(
@@ -203,6 +206,7 @@ pub fn create_main_worker(
     ts_version: version::TYPESCRIPT.to_string(),
     no_color: !colors::use_color(),
     get_error_class_fn: Some(&crate::errors::get_error_class_name),
+    blob_url_store: program_state.blob_url_store.clone(),
     location: program_state.flags.location.clone(),
   };
 
@@ -278,11 +282,12 @@ fn print_cache_info(
 
 fn get_types(unstable: bool) -> String {
   let mut types = format!(
-    "{}\n{}\n{}\n{}\n{}\n{}",
+    "{}\n{}\n{}\n{}\n{}\n{}\n{}",
     crate::tsc::DENO_NS_LIB,
     crate::tsc::DENO_WEB_LIB,
     crate::tsc::DENO_FETCH_LIB,
     crate::tsc::DENO_WEBSOCKET_LIB,
+    crate::tsc::DENO_CRYPTO_LIB,
     crate::tsc::SHARED_GLOBALS_LIB,
     crate::tsc::WINDOW_LIB,
   );
@@ -364,7 +369,7 @@ async fn info_command(
       &program_state,
       // info accesses dynamically imported modules just for their information
       // so we allow access to all of them.
-      Permissions::allow_all(),
+      PermissionsContainer::allow_all(),
     )?));
     let mut builder = module_graph::GraphBuilder::new(
       handler,
@@ -398,7 +403,8 @@ async fn install_command(
   let mut preload_flags = flags.clone();
   preload_flags.inspect = None;
   preload_flags.inspect_brk = None;
-  let permissions = Permissions::from_options(&preload_flags.clone().into());
+  let permissions =
+    Permissions::from_options(&permissions_manifest::resolve(&preload_flags)?);
   let program_state = ProgramState::new(preload_flags)?;
   let main_module = ModuleSpecifier::resolve_url_or_path(&module_url)?;
   let mut worker =
@@ -448,7 +454,7 @@ async fn cache_command(
       .prepare_module_load(
         specifier,
         lib.clone(),
-        Permissions::allow_all(),
+        PermissionsContainer::allow_all(),
         false,
         program_state.maybe_import_map.clone(),
       )
@@ -458,16 +464,33 @@ async fn cache_command(
   Ok(())
 }
 
+/// Loads the dotenv file given by `--env-file` into the process environment,
+/// so that `Deno.env` and subprocesses see its variables. Variables that are
+/// already set keep their value.
+fn load_env_file(flags: &Flags) -> Result<(), AnyError> {
+  if let Some(env_file) = &flags.env_file {
+    dotenv::from_path(env_file).map_err(|err| {
+      generic_error(format!(
+        "Unable to load env file \"{}\": {}",
+        env_file, err
+      ))
+    })?;
+  }
+  Ok(())
+}
+
 async fn eval_command(
   flags: Flags,
   code: String,
   as_typescript: bool,
   print: bool,
 ) -> Result<(), AnyError> {
+  load_env_file(&flags)?;
   // Force TypeScript compile.
   let main_module =
     ModuleSpecifier::resolve_url_or_path("./$deno$eval.ts").unwrap();
-  let permissions = Permissions::from_options(&flags.clone().into());
+  let permissions =
+    Permissions::from_options(&permissions_manifest::resolve(&flags)?);
   let program_state = ProgramState::new(flags)?;
   let mut worker =
     create_main_worker(&program_state, main_module.clone(), permissions);
@@ -512,7 +535,7 @@ async fn create_module_graph_and_maybe_check(
     &program_state,
     // when bundling, dynamic imports are only access for their type safety,
     // therefore we will allow the graph to access any module.
-    Permissions::allow_all(),
+    PermissionsContainer::allow_all(),
   )?));
   let mut builder = module_graph::GraphBuilder::new(
     handler,
@@ -711,7 +734,7 @@ impl DocFileLoader for DocLoader {
       .expect("Expected valid specifier");
     async move {
       let source_file = fetcher
-        .fetch(&specifier, &Permissions::allow_all())
+        .fetch(&specifier, &PermissionsContainer::allow_all())
         .await
         .map_err(|e| {
           doc::DocError::Io(std::io::Error::new(
@@ -808,9 +831,11 @@ async fn format_command(
 }
 
 async fn run_repl(flags: Flags) -> Result<(), AnyError> {
+  load_env_file(&flags)?;
   let main_module =
     ModuleSpecifier::resolve_url_or_path("./$deno$repl.ts").unwrap();
-  let permissions = Permissions::from_options(&flags.clone().into());
+  let permissions =
+    Permissions::from_options(&permissions_manifest::resolve(&flags)?);
   let program_state = ProgramState::new(flags)?;
   let mut worker =
     create_main_worker(&program_state, main_module.clone(), permissions);
@@ -821,7 +846,8 @@ async fn run_repl(flags: Flags) -> Result<(), AnyError> {
 
 async fn run_from_stdin(flags: Flags) -> Result<(), AnyError> {
   let program_state = ProgramState::new(flags.clone())?;
-  let permissions = Permissions::from_options(&flags.clone().into());
+  let permissions =
+    Permissions::from_options(&permissions_manifest::resolve(&flags)?);
   let main_module =
     ModuleSpecifier::resolve_url_or_path("./$deno$stdin.ts").unwrap();
   let mut worker = create_main_worker(
@@ -863,7 +889,7 @@ async fn run_with_watch(flags: Flags, script: String) -> Result<(), AnyError> {
       let program_state = ProgramState::new(flags)?;
       let handler = Arc::new(Mutex::new(FetchHandler::new(
         &program_state,
-        Permissions::allow_all(),
+        PermissionsContainer::allow_all(),
       )?));
       let mut builder = module_graph::GraphBuilder::new(
         handler,
@@ -902,8 +928,9 @@ async fn run_with_watch(flags: Flags, script: String) -> Result<(), AnyError> {
 
   let operation = |main_module: ModuleSpecifier| {
     let flags = flags.clone();
-    let permissions = Permissions::from_options(&flags.clone().into());
     async move {
+      let permissions =
+        Permissions::from_options(&permissions_manifest::resolve(&flags)?);
       let main_module = main_module.clone();
       let program_state = ProgramState::new(flags)?;
       let mut worker =
@@ -927,6 +954,8 @@ async fn run_with_watch(flags: Flags, script: String) -> Result<(), AnyError> {
 }
 
 async fn run_command(flags: Flags, script: String) -> Result<(), AnyError> {
+  load_env_file(&flags)?;
+
   // Read script content from stdin
   if script == "-" {
     return run_from_stdin(flags).await;
@@ -938,7 +967,8 @@ async fn run_command(flags: Flags, script: String) -> Result<(), AnyError> {
 
   let main_module = ModuleSpecifier::resolve_url_or_path(&script)?;
   let program_state = ProgramState::new(flags.clone())?;
-  let permissions = Permissions::from_options(&flags.clone().into());
+  let permissions =
+    Permissions::from_options(&permissions_manifest::resolve(&flags)?);
   let mut worker =
     create_main_worker(&program_state, main_module.clone(), permissions);
 
@@ -978,8 +1008,10 @@ async fn test_command(
   allow_none: bool,
   filter: Option<String>,
 ) -> Result<(), AnyError> {
+  load_env_file(&flags)?;
   let program_state = ProgramState::new(flags.clone())?;
-  let permissions = Permissions::from_options(&flags.clone().into());
+  let permissions =
+    Permissions::from_options(&permissions_manifest::resolve(&flags)?);
   let cwd = std::env::current_dir().expect("No current directory");
   let include = include.unwrap_or_else(|| vec![".".to_string()]);
   let test_modules =
@@ -1020,7 +1052,7 @@ async fn test_command(
       .prepare_module_load(
         main_module.clone(),
         lib,
-        Permissions::allow_all(),
+        PermissionsContainer::allow_all(),
         false,
         program_state.maybe_import_map.clone(),
       )
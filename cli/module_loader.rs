@@ -10,7 +10,7 @@ use deno_core::ModuleLoadId;
 use deno_core::ModuleLoader;
 use deno_core::ModuleSpecifier;
 use deno_core::OpState;
-use deno_runtime::permissions::Permissions;
+use deno_runtime::permissions::PermissionsContainer;
 use std::cell::RefCell;
 use std::pin::Pin;
 use std::rc::Rc;
@@ -25,7 +25,7 @@ pub struct CliModuleLoader {
   /// The initial set of permissions used to resolve the imports in the worker.
   /// They are decoupled from the worker permissions since read access errors
   /// must be raised based on the parent thread permissions
-  pub initial_permissions: Rc<RefCell<Option<Permissions>>>,
+  pub initial_permissions: Rc<RefCell<Option<PermissionsContainer>>>,
   pub program_state: Arc<ProgramState>,
 }
 
@@ -49,7 +49,7 @@ impl CliModuleLoader {
 
   pub fn new_for_worker(
     program_state: Arc<ProgramState>,
-    permissions: Permissions,
+    permissions: PermissionsContainer,
   ) -> Rc<Self> {
     let lib = if program_state.flags.unstable {
       TypeLib::UnstableDenoWorker
@@ -130,11 +130,12 @@ impl ModuleLoader for CliModuleLoader {
     let dynamic_permissions =
       // If there are initial permissions assigned to the loader take them 
       // and use only once for top level module load.
-      // Otherwise use permissions assigned to the current worker.
+      // Otherwise use permissions assigned to the current worker, which are
+      // shared with it so that what is granted at a prompt is kept.
       if let Some(permissions) = self.initial_permissions.borrow_mut().take() {
         permissions
       } else {
-        state.borrow::<Permissions>().clone()
+        state.borrow::<PermissionsContainer>().clone()
       };
 
     let lib = self.lib.clone();
@@ -18,7 +18,7 @@ use deno_core::serde_json::Value;
 use deno_core::BufVec;
 use deno_core::ModuleSpecifier;
 use deno_core::OpState;
-use deno_runtime::permissions::Permissions;
+use deno_runtime::permissions::PermissionsContainer;
 use serde::Deserialize;
 use std::cell::RefCell;
 use std::collections::HashMap;
@@ -58,7 +58,7 @@ async fn op_emit(
   let program_state = state.borrow().borrow::<Arc<ProgramState>>().clone();
   let runtime_permissions = {
     let state = state.borrow();
-    state.borrow::<Permissions>().clone()
+    state.borrow::<PermissionsContainer>().clone()
   };
   // when we are actually resolving modules without provided sources, we should
   // treat the root module as a dynamic import so that runtime permissions are
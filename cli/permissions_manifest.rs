@@ -0,0 +1,366 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+//! Loading of the JSON permissions manifest given by `--permissions=<file>`.
+//!
+//! The manifest has the same structure as `PermissionsOptions`, e.g.
+//!
+//! ```json
+//! {
+//!   "allow_read": ["./data"],
+//!   "deny_read": ["./data/secrets"],
+//!   "allow_net": ["deno.land", "10.0.0.0/8"],
+//!   "allow_env": true
+//! }
+//! ```
+//!
+//! Missing keys keep their defaults. Relative paths are resolved against the
+//! directory of the manifest, so that it means the same wherever it is used
+//! from.
+//!
+//! `scopes` further restricts the code of the modules under a URL prefix,
+//! which can then only use what both its scope and the rest of the manifest
+//! allow:
+//!
+//! ```json
+//! {
+//!   "allow_read": ["."],
+//!   "scopes": {
+//!     "https://deno.land/x/sqlite/": { "allow_read": ["./db"] }
+//!   }
+//! }
+//! ```
+
+use crate::flags::Flags;
+use crate::flags_allow_net;
+use crate::fs_util::normalize_path;
+use crate::fs_util::resolve_from_cwd;
+use deno_core::error::generic_error;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_core::url::Url;
+use deno_runtime::permissions::PermissionsOptions;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+fn manifest_error(path: &Path, message: impl std::fmt::Display) -> AnyError {
+  generic_error(format!(
+    "Invalid permissions manifest \"{}\": {}",
+    path.display(),
+    message
+  ))
+}
+
+/// Reads and validates the permissions manifest at `path`.
+pub fn load(path: &Path) -> Result<PermissionsOptions, AnyError> {
+  debug!("Attempt to load permissions manifest: {}", path.display());
+  let json_string = fs::read_to_string(path).map_err(|err| {
+    generic_error(format!(
+      "Error retrieving permissions manifest at \"{}\": {}",
+      path.display(),
+      err
+    ))
+  })?;
+  let options: PermissionsOptions = serde_json::from_str(&json_string)
+    .map_err(|err| manifest_error(path, err))?;
+  let mut options = validate(path, options, "")?;
+
+  let scopes = std::mem::take(&mut options.scopes);
+  for (prefix, scope) in scopes {
+    if Url::parse(&prefix).is_err() {
+      return Err(manifest_error(
+        path,
+        format!("scope \"{}\" is not a URL prefix", prefix),
+      ));
+    }
+    if !scope.scopes.is_empty() {
+      return Err(manifest_error(
+        path,
+        format!("scope \"{}\" can't have scopes of its own", prefix),
+      ));
+    }
+    let context = format!("in scope \"{}\": ", prefix);
+    let scope = validate(path, scope, &context)?;
+    options.scopes.insert(prefix, scope);
+  }
+
+  Ok(options)
+}
+
+/// Resolves the relative paths of a list against the directory of the
+/// manifest at `path`.
+fn resolve_paths(
+  path: &Path,
+  list: Option<Vec<PathBuf>>,
+) -> Result<Option<Vec<PathBuf>>, AnyError> {
+  let manifest_path = resolve_from_cwd(path)?;
+  let dir = manifest_path.parent().unwrap();
+  Ok(list.map(|list| {
+    list
+      .into_iter()
+      .map(|entry| {
+        if entry.has_root() {
+          entry
+        } else {
+          normalize_path(&dir.join(entry))
+        }
+      })
+      .collect()
+  }))
+}
+
+/// Normalizes the path and net lists of `options` and checks for conflicting
+/// keys. `context` is prepended to error messages.
+fn validate(
+  path: &Path,
+  mut options: PermissionsOptions,
+  context: &str,
+) -> Result<PermissionsOptions, AnyError> {
+  options.allow_read = resolve_paths(path, options.allow_read)?;
+  options.allow_write = resolve_paths(path, options.allow_write)?;
+  options.deny_read = resolve_paths(path, options.deny_read)?;
+  options.deny_write = resolve_paths(path, options.deny_write)?;
+  if let Some(allow_net) = options.allow_net.take() {
+    options.allow_net =
+      Some(flags_allow_net::parse(allow_net).map_err(|err| {
+        manifest_error(path, format!("{}{}", context, err.message))
+      })?);
+  }
+  if let Some(deny_net) = options.deny_net.take() {
+    options.deny_net =
+      Some(flags_allow_net::parse(deny_net).map_err(|err| {
+        manifest_error(path, format!("{}{}", context, err.message))
+      })?);
+  }
+
+  let conflicts = [
+    ("env", options.allow_env && options.deny_env),
+    ("hrtime", options.allow_hrtime && options.deny_hrtime),
+    ("plugin", options.allow_plugin && options.deny_plugin),
+    ("run", options.allow_run && options.deny_run),
+  ];
+  for (name, conflict) in conflicts.iter() {
+    if *conflict {
+      return Err(manifest_error(
+        path,
+        format!(
+          "{}\"allow_{1}\" and \"deny_{1}\" are both set",
+          context, name
+        ),
+      ));
+    }
+  }
+
+  Ok(options)
+}
+
+/// Merges an allow list from the command line with one from the manifest.
+/// An empty list means "everything", so it wins over any other list.
+fn merge_list<T: PartialEq>(
+  a: Option<Vec<T>>,
+  b: Option<Vec<T>>,
+) -> Option<Vec<T>> {
+  match (a, b) {
+    (None, b) => b,
+    (a, None) => a,
+    (Some(a), Some(b)) if a.is_empty() || b.is_empty() => Some(vec![]),
+    (Some(mut a), Some(b)) => {
+      for item in b {
+        if !a.contains(&item) {
+          a.push(item);
+        }
+      }
+      Some(a)
+    }
+  }
+}
+
+/// Returns the permission options for the given flags, combining the
+/// `--allow-*` flags with the manifest given by `--permissions`, if any.
+pub fn resolve(flags: &Flags) -> Result<PermissionsOptions, AnyError> {
+  let options: PermissionsOptions = flags.clone().into();
+  let manifest = match &flags.permissions_manifest {
+    Some(path) => load(Path::new(path))?,
+    None => return Ok(options),
+  };
+  Ok(PermissionsOptions {
+    allow_env: options.allow_env || manifest.allow_env,
+    allow_hrtime: options.allow_hrtime || manifest.allow_hrtime,
+    allow_net: merge_list(options.allow_net, manifest.allow_net),
+    allow_plugin: options.allow_plugin || manifest.allow_plugin,
+    allow_read: merge_list(options.allow_read, manifest.allow_read),
+    allow_run: options.allow_run || manifest.allow_run,
+    allow_write: merge_list(options.allow_write, manifest.allow_write),
+    deny_env: manifest.deny_env,
+    deny_hrtime: manifest.deny_hrtime,
+    deny_net: manifest.deny_net,
+    deny_plugin: manifest.deny_plugin,
+    deny_read: manifest.deny_read,
+    deny_run: manifest.deny_run,
+    deny_write: manifest.deny_write,
+    scopes: manifest.scopes,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::path::PathBuf;
+  use tempfile::TempDir;
+
+  fn write_manifest(temp_dir: &TempDir, contents: &str) -> PathBuf {
+    let path = temp_dir.path().join("permissions.json");
+    fs::write(&path, contents).unwrap();
+    path
+  }
+
+  #[test]
+  fn load_manifest() {
+    let temp_dir = TempDir::new().expect("tempdir fail");
+    let path = write_manifest(
+      &temp_dir,
+      r#"{
+        "allow_read": ["/data"],
+        "deny_read": ["/data/secrets"],
+        "allow_net": [":8080", "deno.land"],
+        "allow_env": true
+      }"#,
+    );
+    let options = load(&path).unwrap();
+    assert_eq!(
+      options,
+      PermissionsOptions {
+        allow_read: Some(vec![PathBuf::from("/data")]),
+        deny_read: Some(vec![PathBuf::from("/data/secrets")]),
+        allow_net: Some(vec![
+          "0.0.0.0:8080".to_string(),
+          "127.0.0.1:8080".to_string(),
+          "localhost:8080".to_string(),
+          "deno.land".to_string(),
+        ]),
+        allow_env: true,
+        ..Default::default()
+      }
+    );
+  }
+
+  #[test]
+  fn load_manifest_errors() {
+    let temp_dir = TempDir::new().expect("tempdir fail");
+
+    let path = write_manifest(&temp_dir, r#"{ "allow_foo": true }"#);
+    let err = load(&path).unwrap_err().to_string();
+    assert!(err.starts_with("Invalid permissions manifest"));
+    assert!(err.contains("unknown field `allow_foo`"));
+
+    let path = write_manifest(&temp_dir, r#"{ "allow_read": "/data" }"#);
+    assert!(load(&path).is_err());
+
+    let path = write_manifest(&temp_dir, r#"{ "allow_net": ["[::q]:80"] }"#);
+    let err = load(&path).unwrap_err().to_string();
+    assert!(err.contains("Bad host:port pair"));
+
+    let path =
+      write_manifest(&temp_dir, r#"{ "allow_env": true, "deny_env": true }"#);
+    let err = load(&path).unwrap_err().to_string();
+    assert!(err.contains("\"allow_env\" and \"deny_env\" are both set"));
+
+    let path = write_manifest(
+      &temp_dir,
+      r#"{ "scopes": { "./vendor/": { "allow_env": true } } }"#,
+    );
+    let err = load(&path).unwrap_err().to_string();
+    assert!(err.contains("scope \"./vendor/\" is not a URL prefix"));
+
+    let path = write_manifest(
+      &temp_dir,
+      r#"{ "scopes": { "https://deno.land/x/": { "allow_net": ["a b"] } } }"#,
+    );
+    let err = load(&path).unwrap_err().to_string();
+    assert!(err.contains("in scope \"https://deno.land/x/\": "));
+
+    let err = load(&temp_dir.path().join("missing.json")).unwrap_err();
+    assert!(err
+      .to_string()
+      .starts_with("Error retrieving permissions manifest"));
+  }
+
+  #[test]
+  fn load_manifest_scopes() {
+    let temp_dir = TempDir::new().expect("tempdir fail");
+    let path = write_manifest(
+      &temp_dir,
+      r#"{
+        "allow_read": [],
+        "scopes": {
+          "https://deno.land/x/sqlite/": {
+            "allow_read": ["/db"],
+            "allow_net": [":8080"]
+          }
+        }
+      }"#,
+    );
+    let options = load(&path).unwrap();
+    let scope = &options.scopes["https://deno.land/x/sqlite/"];
+    assert_eq!(scope.allow_read, Some(vec![PathBuf::from("/db")]));
+    assert_eq!(
+      scope.allow_net,
+      Some(vec![
+        "0.0.0.0:8080".to_string(),
+        "127.0.0.1:8080".to_string(),
+        "localhost:8080".to_string(),
+      ])
+    );
+  }
+
+  #[test]
+  fn load_manifest_relative_paths() {
+    let temp_dir = TempDir::new().expect("tempdir fail");
+    let path = write_manifest(
+      &temp_dir,
+      r#"{
+        "allow_read": ["./data", "../shared"],
+        "allow_write": ["/tmp"],
+        "scopes": {
+          "https://deno.land/x/sqlite/": { "deny_write": ["db"] }
+        }
+      }"#,
+    );
+    let options = load(&path).unwrap();
+    let dir = temp_dir.path();
+    assert_eq!(
+      options.allow_read,
+      Some(vec![dir.join("data"), dir.parent().unwrap().join("shared")])
+    );
+    assert_eq!(options.allow_write, Some(vec![PathBuf::from("/tmp")]));
+    let scope = &options.scopes["https://deno.land/x/sqlite/"];
+    assert_eq!(scope.deny_write, Some(vec![dir.join("db")]));
+  }
+
+  #[test]
+  fn resolve_merges_flags() {
+    let temp_dir = TempDir::new().expect("tempdir fail");
+    let path = write_manifest(
+      &temp_dir,
+      r#"{ "allow_read": ["/a"], "allow_net": [], "deny_write": ["/b"] }"#,
+    );
+    let options = resolve(&Flags {
+      allow_read: Some(vec![PathBuf::from("/c")]),
+      allow_net: Some(vec!["deno.land".to_string()]),
+      allow_run: true,
+      permissions_manifest: Some(path.to_string_lossy().to_string()),
+      ..Flags::default()
+    })
+    .unwrap();
+    assert_eq!(
+      options,
+      PermissionsOptions {
+        allow_read: Some(vec![PathBuf::from("/c"), PathBuf::from("/a")]),
+        allow_net: Some(vec![]),
+        allow_run: true,
+        deny_write: Some(vec![PathBuf::from("/b")]),
+        ..Default::default()
+      }
+    );
+  }
+}
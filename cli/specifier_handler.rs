@@ -6,7 +6,7 @@ use crate::disk_cache::DiskCache;
 use crate::file_fetcher::FileFetcher;
 use crate::media_type::MediaType;
 use crate::program_state::ProgramState;
-use deno_runtime::permissions::Permissions;
+use deno_runtime::permissions::PermissionsContainer;
 
 use deno_core::error::custom_error;
 use deno_core::error::AnyError;
@@ -222,8 +222,9 @@ pub struct FetchHandler {
   /// An instance of disk where generated (emitted) files are stored.
   disk_cache: DiskCache,
   /// The set of current runtime permissions which need to be applied to
-  /// dynamic imports.
-  runtime_permissions: Permissions,
+  /// dynamic imports. They are shared with the worker, so that what is
+  /// granted at a prompt while fetching a module is kept.
+  runtime_permissions: PermissionsContainer,
   /// A clone of the `program_state` file fetcher.
   file_fetcher: FileFetcher,
 }
@@ -231,7 +232,7 @@ pub struct FetchHandler {
 impl FetchHandler {
   pub fn new(
     program_state: &Arc<ProgramState>,
-    runtime_permissions: Permissions,
+    runtime_permissions: PermissionsContainer,
   ) -> Result<Self, AnyError> {
     let custom_root = env::var("DENO_DIR").map(String::into).ok();
     let deno_dir = DenoDir::new(custom_root)?;
@@ -259,7 +260,7 @@ impl SpecifierHandler for FetchHandler {
     let permissions = if is_dynamic {
       self.runtime_permissions.clone()
     } else {
-      Permissions::allow_all()
+      PermissionsContainer::allow_all()
     };
     let file_fetcher = self.file_fetcher.clone();
     let disk_cache = self.disk_cache.clone();
@@ -305,7 +306,7 @@ impl SpecifierHandler for FetchHandler {
           }
         })?;
       let url = source_file.specifier.as_url();
-      let is_remote = !(url.scheme() == "file" || url.scheme() == "data");
+      let is_remote = !matches!(url.scheme(), "file" | "data" | "blob");
       let filename = disk_cache.get_cache_filename_with_extension(url, "meta");
       let maybe_version = if let Some(filename) = filename {
         if let Ok(bytes) = disk_cache.get(&filename) {
@@ -388,10 +389,15 @@ impl SpecifierHandler for FetchHandler {
     specifier: &ModuleSpecifier,
     tsbuildinfo: String,
   ) -> Result<(), AnyError> {
-    let filename = self
+    let filename = match self
       .disk_cache
       .get_cache_filename_with_extension(specifier.as_url(), "buildinfo")
-      .unwrap();
+    {
+      Some(filename) => filename,
+      // The build info of modules that the disk cache doesn't support, like
+      // blob URLs, isn't kept across runs.
+      None => return Ok(()),
+    };
     debug!("set_tsbuildinfo - filename {:?}", filename);
     self
       .disk_cache
@@ -407,10 +413,13 @@ impl SpecifierHandler for FetchHandler {
     match emit {
       Emit::Cli((code, maybe_map)) => {
         let url = specifier.as_url();
-        let filename = self
-          .disk_cache
-          .get_cache_filename_with_extension(url, "js")
-          .unwrap();
+        let filename =
+          match self.disk_cache.get_cache_filename_with_extension(url, "js") {
+            Some(filename) => filename,
+            // Modules that the disk cache doesn't support, like blob URLs,
+            // are only emitted in memory.
+            None => return Ok(()),
+          };
         self.disk_cache.set(&filename, code.as_bytes())?;
 
         if let Some(map) = maybe_map {
@@ -450,10 +459,13 @@ impl SpecifierHandler for FetchHandler {
     version_hash: String,
   ) -> Result<(), AnyError> {
     let compiled_file_metadata = CompiledFileMetadata { version_hash };
-    let filename = self
+    let filename = match self
       .disk_cache
       .get_cache_filename_with_extension(specifier.as_url(), "meta")
-      .unwrap();
+    {
+      Some(filename) => filename,
+      None => return Ok(()),
+    };
 
     self
       .disk_cache
@@ -568,6 +580,7 @@ pub mod tests {
   use super::*;
   use crate::file_fetcher::CacheSetting;
   use crate::http_cache::HttpCache;
+  use deno_runtime::deno_web::BlobUrlStore;
   use tempfile::TempDir;
 
   macro_rules! map (
@@ -592,13 +605,14 @@ pub mod tests {
       CacheSetting::Use,
       true,
       None,
+      BlobUrlStore::default(),
     )
     .expect("could not setup");
     let disk_cache = deno_dir.gen_cache;
 
     let fetch_handler = FetchHandler {
       disk_cache,
-      runtime_permissions: Permissions::default(),
+      runtime_permissions: PermissionsContainer::default(),
       file_fetcher,
     };
 
@@ -0,0 +1,3 @@
+console.log(Deno.env.get("GREETING"));
+console.log(Deno.env.get("NAME"));
+console.log(Deno.env.get("MESSAGE"));
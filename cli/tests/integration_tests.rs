@@ -3034,6 +3034,17 @@ itest!(lib_runtime_api {
   output: "lib_runtime_api.ts.out",
 });
 
+itest!(env_file {
+  args: "run --env-file=env_file.env --allow-env env_file.ts",
+  output: "env_file.ts.out",
+});
+
+itest!(env_file_no_allow_env {
+  args: "run --env-file=env_file.env env_file.ts",
+  output: "env_file_no_allow_env.ts.out",
+  exit_code: 1,
+});
+
 itest!(seed_random {
   args: "run --seed=100 seed_random.js",
 
@@ -0,0 +1,121 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+import {
+  assert,
+  assertEquals,
+  assertThrows,
+  assertThrowsAsync,
+  unitTest,
+} from "./test_util.ts";
+
+// "hello world" in each format, as compressed by Python's zlib.
+const helloWorld: Record<CompressionFormat, number[]> = {
+  "gzip": [
+    31, 139, 8, 0, 0, 0, 0, 0, 2, 3, 203, 72, 205, 201, 201, 87, 40, 207, 47,
+    202, 73, 1, 0, 133, 17, 74, 13, 11, 0, 0, 0,
+  ],
+  "deflate": [
+    120, 156, 203, 72, 205, 201, 201, 87, 40, 207, 47, 202, 73, 1, 0, 26, 11,
+    4, 93,
+  ],
+  "deflate-raw": [203, 72, 205, 201, 201, 87, 40, 207, 47, 202, 73, 1, 0],
+};
+
+/** Writes the chunks through the transform stream and collects the output. */
+async function transform(
+  stream: CompressionStream | DecompressionStream,
+  chunks: BufferSource[],
+): Promise<Uint8Array> {
+  const writer = stream.writable.getWriter();
+  const writing = (async () => {
+    for (const chunk of chunks) {
+      await writer.write(chunk);
+    }
+    await writer.close();
+  })();
+  const [output] = await Promise.all([
+    new Response(stream.readable).arrayBuffer(),
+    writing,
+  ]);
+  return new Uint8Array(output);
+}
+
+unitTest(async function decompressionStreamFormats(): Promise<void> {
+  for (const [format, bytes] of Object.entries(helloWorld)) {
+    const data = new Uint8Array(bytes);
+    const output = await transform(
+      new DecompressionStream(format as CompressionFormat),
+      [data.subarray(0, 5), data.buffer.slice(5)],
+    );
+    assertEquals(new TextDecoder().decode(output), "hello world");
+  }
+});
+
+unitTest(async function compressionStreamRoundTrip(): Promise<void> {
+  const data = new TextEncoder().encode("hello ".repeat(1000));
+  for (const format of Object.keys(helloWorld)) {
+    const compressed = await transform(
+      new CompressionStream(format as CompressionFormat),
+      [data.subarray(0, 100), data.subarray(100)],
+    );
+    assert(compressed.length < data.length);
+    const decompressed = await transform(
+      new DecompressionStream(format as CompressionFormat),
+      [compressed],
+    );
+    assertEquals(decompressed, data);
+  }
+});
+
+unitTest(async function decompressionStreamChunksOutput(): Promise<void> {
+  const data = new Uint8Array(1024 * 1024);
+  const compressed = await transform(new CompressionStream("gzip"), [data]);
+  const stream = new DecompressionStream("gzip");
+  const writer = stream.writable.getWriter();
+  const writing = Promise.all([writer.write(compressed), writer.close()]);
+  let chunks = 0;
+  let length = 0;
+  for await (const chunk of stream.readable) {
+    assert(chunk.length <= 128 * 1024);
+    chunks++;
+    length += chunk.length;
+  }
+  await writing;
+  assert(chunks > 1);
+  assertEquals(length, data.length);
+});
+
+unitTest(async function compressionStreamPipeThrough(): Promise<void> {
+  const compressed = new Blob(["hello world"])
+    .stream()
+    .pipeThrough(new CompressionStream("gzip"));
+  const decompressed = compressed.pipeThrough(new DecompressionStream("gzip"));
+  assertEquals(await new Response(decompressed).text(), "hello world");
+});
+
+unitTest(async function decompressionStreamErrors(): Promise<void> {
+  await assertThrowsAsync(
+    () =>
+      transform(new DecompressionStream("gzip"), [new Uint8Array([1, 2, 3])]),
+    TypeError,
+    "Corrupt compressed data",
+  );
+  const truncated = new Uint8Array(helloWorld.gzip.slice(0, 20));
+  await assertThrowsAsync(
+    () => transform(new DecompressionStream("gzip"), [truncated]),
+    TypeError,
+    "Corrupt compressed data",
+  );
+  await assertThrowsAsync(
+    () =>
+      // deno-lint-ignore no-explicit-any
+      transform(new CompressionStream("gzip"), ["hello" as any]),
+    TypeError,
+    "Chunk is not a BufferSource",
+  );
+  assertThrows(
+    // deno-lint-ignore no-explicit-any
+    () => new CompressionStream("brotli" as any),
+    TypeError,
+    'Unsupported compression format "brotli"',
+  );
+});
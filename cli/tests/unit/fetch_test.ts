@@ -1081,3 +1081,121 @@ unitTest(
     assertEquals(actual, expected);
   },
 );
+
+unitTest(
+  { perms: { net: true } },
+  async function fetchCustomHttpClientReadTimeout(): Promise<void> {
+    // A server that reads the request, and never responds.
+    const listener = Deno.listen({ hostname: "127.0.0.1", port: 4502 });
+    const request = (async () => {
+      const conn = await listener.accept();
+      const buf = new Uint8Array(1024);
+      const n = await conn.read(buf);
+      return { conn, head: new TextDecoder().decode(buf.subarray(0, n!)) };
+    })();
+
+    const client = Deno.createHttpClient({
+      readTimeout: 100,
+      userAgent: "my-service/1.0",
+      http1Only: true,
+    });
+    await assertThrowsAsync(
+      () => fetch("http://127.0.0.1:4502/", { client }),
+      Deno.errors.TimedOut,
+    );
+    const { conn, head } = await request;
+    assert(head.toLowerCase().includes("user-agent: my-service/1.0\r\n"));
+    conn.close();
+    listener.close();
+    client.close();
+  },
+);
+
+unitTest(
+  { perms: { net: true } },
+  async function fetchCustomHttpClientNoDecompress(): Promise<void> {
+    const client = Deno.createHttpClient({ decompress: false });
+    const response = await fetch(
+      "http://localhost:4545/cli/tests/053_import_compression/gziped",
+      { client },
+    );
+    assertEquals(response.headers.get("content-encoding"), "gzip");
+    const body = new Uint8Array(await response.arrayBuffer());
+    assertEquals([...body.subarray(0, 2)], [0x1f, 0x8b]);
+    client.close();
+  },
+);
+
+unitTest(function createHttpClientInvalidOptions(): void {
+  assertThrows(() => Deno.createHttpClient({ readTimeout: -1 }), TypeError);
+  assertThrows(
+    () => Deno.createHttpClient({ connectTimeout: 0 }),
+    TypeError,
+  );
+  assertThrows(
+    () => Deno.createHttpClient({ poolMaxIdlePerHost: 1.5 }),
+    TypeError,
+  );
+  assertThrows(
+    () => Deno.createHttpClient({ http1Only: true, http2PriorKnowledge: true }),
+    TypeError,
+  );
+  assertThrows(
+    () => Deno.createHttpClient({ userAgent: "line\nbreak" }),
+    TypeError,
+  );
+});
+
+// Answers each request on a Unix socket with its request line.
+async function serveUnixSocket(listener: Deno.Listener): Promise<void> {
+  for await (const conn of listener) {
+    const buf = new Uint8Array(1024);
+    const n = await conn.read(buf);
+    const head = new TextDecoder().decode(buf.subarray(0, n!));
+    const body = head.split("\r\n")[0];
+    const response = `HTTP/1.1 200 OK\r\nContent-Length: ${body.length}\r\n` +
+      `Connection: close\r\n\r\n${body}`;
+    await conn.write(new TextEncoder().encode(response));
+    conn.close();
+  }
+}
+
+unitTest(
+  { ignore: Deno.build.os === "windows", perms: { read: true, write: true } },
+  async function fetchUnixSocket(): Promise<void> {
+    const path = Deno.makeTempDirSync() + "/fetch.sock";
+    const listener = Deno.listen({ path, transport: "unix" });
+    const server = serveUnixSocket(listener);
+
+    const client = Deno.createHttpClient({ unixSocket: path });
+    const response = await fetch("http://localhost/info?a=1", { client });
+    assertEquals(response.status, 200);
+    assertEquals(await response.text(), "GET /info?a=1 HTTP/1.1");
+    client.close();
+
+    const url = `http+unix://${encodeURIComponent(path)}/version`;
+    const post = await fetch(url, { method: "POST", body: "hi" });
+    assertEquals(await post.text(), "POST /version HTTP/1.1");
+
+    listener.close();
+    await server;
+  },
+);
+
+unitTest(
+  { ignore: Deno.build.os === "windows", perms: { read: true } },
+  async function fetchUnixSocketNoWritePermission(): Promise<void> {
+    const url = `http+unix://${encodeURIComponent("/tmp/fetch.sock")}/`;
+    await assertThrowsAsync(
+      () => fetch(url),
+      Deno.errors.PermissionDenied,
+    );
+    const client = Deno.createHttpClient({ unixSocket: "/tmp/fetch.sock" });
+    await assertThrowsAsync(
+      () => fetch("https://localhost/", { client }),
+      TypeError,
+      "HTTPS is not supported over Unix sockets",
+    );
+    client.close();
+  },
+);
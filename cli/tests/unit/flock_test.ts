@@ -0,0 +1,99 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+import { assertEquals, assertThrowsAsync, unitTest } from "./test_util.ts";
+
+function openTwice(): [Deno.File, Deno.File] {
+  const path = Deno.makeTempFileSync();
+  const options = { read: true, write: true };
+  return [Deno.openSync(path, options), Deno.openSync(path, options)];
+}
+
+unitTest(
+  { perms: { read: true, write: true } },
+  function tryFlockConflicts(): void {
+    const [a, b] = openTwice();
+    assertEquals(Deno.tryFlock(a.rid, { exclusive: true }), true);
+    assertEquals(Deno.tryFlock(b.rid), false);
+    assertEquals(Deno.tryFlock(b.rid, { exclusive: true }), false);
+    Deno.funlock(a.rid);
+    assertEquals(Deno.tryFlock(b.rid, { exclusive: true }), true);
+    a.close();
+    b.close();
+  },
+);
+
+unitTest(
+  { perms: { read: true, write: true } },
+  function tryFlockShared(): void {
+    const [a, b] = openTwice();
+    assertEquals(Deno.tryFlock(a.rid), true);
+    assertEquals(Deno.tryFlock(b.rid), true);
+    assertEquals(Deno.tryFlock(b.rid, { exclusive: true }), false);
+    a.close();
+    b.close();
+  },
+);
+
+unitTest(
+  { perms: { read: true, write: true } },
+  function flockReleasedOnClose(): void {
+    const [a, b] = openTwice();
+    assertEquals(Deno.tryFlock(a.rid, { exclusive: true }), true);
+    a.close();
+    assertEquals(Deno.tryFlock(b.rid, { exclusive: true }), true);
+    b.close();
+  },
+);
+
+unitTest(
+  { perms: { read: true, write: true } },
+  async function flockWaits(): Promise<void> {
+    const [a, b] = openTwice();
+    await Deno.flock(a.rid, { exclusive: true });
+    setTimeout(() => Deno.funlock(a.rid), 50);
+    await Deno.flock(b.rid, { exclusive: true });
+    assertEquals(Deno.tryFlock(a.rid), false);
+    a.close();
+    b.close();
+  },
+);
+
+unitTest(
+  { perms: { read: true, write: true } },
+  async function flockCanceledByClose(): Promise<void> {
+    const [a, b] = openTwice();
+    await Deno.flock(a.rid, { exclusive: true });
+    const lock = Deno.flock(b.rid, { exclusive: true });
+    b.close();
+    await assertThrowsAsync(() => lock, Deno.errors.Interrupted);
+    a.close();
+  },
+);
+
+unitTest(
+  { perms: { read: true, write: true, run: true } },
+  async function flockRange(): Promise<void> {
+    const path = Deno.makeTempFileSync();
+    const file = Deno.openSync(path, { read: true, write: true });
+    const range = { start: 0, length: 10 };
+    assertEquals(Deno.tryFlock(file.rid, { exclusive: true, range }), true);
+
+    // Byte-range locks only conflict with those of other processes.
+    const code = [
+      `const file = Deno.openSync(${JSON.stringify(path)}, { write: true });`,
+      "const overlapping = { start: 5, length: 10 };",
+      "console.log(Deno.tryFlock(file.rid, { range: overlapping }));",
+      "console.log(Deno.tryFlock(file.rid, { range: { start: 10 } }));",
+    ].join("\n");
+    const p = Deno.run({
+      cmd: [Deno.execPath(), "eval", "--unstable", code],
+      stdout: "piped",
+    });
+    const output = new TextDecoder().decode(await p.output());
+    assertEquals(output, "false\ntrue\n");
+    await p.status();
+    p.close();
+
+    Deno.funlock(file.rid, { range });
+    file.close();
+  },
+);
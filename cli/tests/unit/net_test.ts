@@ -626,3 +626,263 @@ unitTest(
     listener.close();
   },
 );
+
+const nameServer = { ipAddr: "127.0.0.1", port: 4553 };
+
+unitTest(
+  { perms: { net: true } },
+  async function netResolveDns(): Promise<void> {
+    const a = await Deno.resolveDns("www.deno.test", "A", { nameServer });
+    assertEquals(a, ["127.0.0.1"]);
+    const aaaa = await Deno.resolveDns("www.deno.test", "AAAA", {
+      nameServer,
+    });
+    assertEquals(aaaa, ["::1"]);
+    const cname = await Deno.resolveDns("alias.deno.test", "CNAME", {
+      nameServer,
+    });
+    assertEquals(cname, ["www.deno.test."]);
+    const mx = await Deno.resolveDns("deno.test", "MX", { nameServer });
+    assertEquals(mx, [{ preference: 10, exchange: "mail.deno.test." }]);
+    const txt = await Deno.resolveDns("deno.test", "TXT", { nameServer });
+    assertEquals(txt, [["v=spf1 -all", "hello world"]]);
+    const srv = await Deno.resolveDns("_http._tcp.deno.test", "SRV", {
+      nameServer,
+    });
+    assertEquals(srv, [
+      { priority: 0, weight: 5, port: 4545, target: "www.deno.test." },
+    ]);
+  },
+);
+
+unitTest(
+  { perms: { net: true } },
+  async function netResolveDnsNotFound(): Promise<void> {
+    await assertThrowsAsync(
+      () => Deno.resolveDns("missing.deno.test", "A", { nameServer }),
+      Deno.errors.NotFound,
+    );
+    await assertThrowsAsync(
+      () =>
+        // @ts-expect-error
+        Deno.resolveDns("deno.test", "NS", { nameServer }),
+      TypeError,
+    );
+  },
+);
+
+unitTest(async function netResolveDnsNoPerm(): Promise<void> {
+  await assertThrowsAsync(
+    () => Deno.resolveDns("www.deno.test", "A", { nameServer }),
+    Deno.errors.PermissionDenied,
+  );
+});
+
+unitTest(
+  { perms: { net: true } },
+  async function netTcpSetNoDelayAndKeepAlive(): Promise<void> {
+    const listener = Deno.listen({ hostname: "127.0.0.1", port: 3500 });
+    const accepted = listener.accept();
+    const conn = await Deno.connect({ hostname: "127.0.0.1", port: 3500 });
+    const serverConn = await accepted;
+
+    await conn.setNoDelay();
+    await conn.setNoDelay(false);
+    await conn.setKeepAlive(true, 1000);
+    await conn.setKeepAlive(false);
+    await serverConn.setNoDelay(true);
+    await serverConn.setKeepAlive();
+    await assertThrowsAsync(() => conn.setKeepAlive(true, -1), TypeError);
+
+    await conn.write(new Uint8Array([1, 2, 3]));
+    const buf = new Uint8Array(3);
+    assertEquals(await serverConn.read(buf), 3);
+    assertEquals(buf, new Uint8Array([1, 2, 3]));
+    conn.close();
+    serverConn.close();
+    listener.close();
+  },
+);
+
+unitTest(
+  { ignore: Deno.build.os === "windows", perms: { net: true } },
+  function netTcpListenReusePort(): void {
+    const options = { hostname: "127.0.0.1", port: 3500, reusePort: true };
+    const listener1 = Deno.listen(options);
+    const listener2 = Deno.listen(options);
+    assertThrows(() => {
+      Deno.listen({ hostname: "127.0.0.1", port: 3500 });
+    }, Deno.errors.AddrInUse);
+    listener1.close();
+    listener2.close();
+  },
+);
+
+unitTest(
+  { perms: { net: true } },
+  async function netTcpListenBacklog(): Promise<void> {
+    const listener = Deno.listen({
+      hostname: "127.0.0.1",
+      port: 3500,
+      backlog: 1,
+    });
+    const accepted = listener.accept();
+    const conn = await Deno.connect({ hostname: "127.0.0.1", port: 3500 });
+    (await accepted).close();
+    conn.close();
+    listener.close();
+  },
+);
+
+unitTest(
+  { perms: { net: true } },
+  function netUdpSocketOptions(): void {
+    const socket = Deno.listenDatagram({
+      hostname: "0.0.0.0",
+      port: 3500,
+      transport: "udp",
+    });
+    socket.setBroadcast();
+    socket.setTTL(32);
+    socket.setMulticastTTL(1);
+    socket.setMulticastLoopback(false);
+    socket.joinMulticast("239.255.0.1");
+    socket.leaveMulticast("239.255.0.1");
+    socket.joinMulticast("239.255.0.2", "0.0.0.0");
+    socket.leaveMulticast("239.255.0.2", "0.0.0.0");
+    assertThrows(() => socket.joinMulticast("239.255.0.3", 1), TypeError);
+    assertThrows(() => socket.joinMulticast("not an address"), TypeError);
+    socket.close();
+  },
+);
+
+unitTest(
+  { ignore: Deno.build.os !== "linux", perms: { net: true } },
+  async function netUnixAbstractNamespace(): Promise<void> {
+    const path = "\0deno-net-test-abstract";
+    const listener = Deno.listen({ path, transport: "unix" });
+    assertEquals((listener.addr as Deno.UnixAddr).path, path);
+    const accepted = listener.accept();
+    const conn = await Deno.connect({ path, transport: "unix" });
+    assertEquals((conn.remoteAddr as Deno.UnixAddr).path, path);
+    const serverConn = await accepted;
+
+    await conn.write(new Uint8Array([1, 2, 3]));
+    const buf = new Uint8Array(3);
+    assertEquals(await serverConn.read(buf), 3);
+    assertEquals(buf, new Uint8Array([1, 2, 3]));
+    conn.close();
+    serverConn.close();
+    listener.close();
+  },
+);
+
+unitTest(
+  { ignore: Deno.build.os !== "linux" },
+  async function netUnixAbstractNamespaceNoPerm(): Promise<void> {
+    await assertThrowsAsync(
+      () => Deno.connect({ path: "\0deno-net-test", transport: "unix" }),
+      Deno.errors.PermissionDenied,
+    );
+  },
+);
+
+unitTest(
+  {
+    ignore: Deno.build.os === "windows",
+    perms: { read: true, write: true },
+  },
+  async function netUnixPeerCredentials(): Promise<void> {
+    const filePath = await Deno.makeTempFile();
+    const listener = Deno.listen({ path: filePath, transport: "unix" });
+    const accepted = listener.accept() as Promise<Deno.UnixConn>;
+    const conn = await Deno.connect({
+      path: filePath,
+      transport: "unix",
+    }) as Deno.UnixConn;
+    const serverConn = await accepted;
+    const peers = [conn.peerCredentials, serverConn.peerCredentials];
+    for (const credentials of peers) {
+      assert(credentials !== null);
+      assertEquals(typeof credentials.uid, "number");
+      assertEquals(typeof credentials.gid, "number");
+      if (Deno.build.os === "linux") {
+        assertEquals(credentials.pid, Deno.pid);
+      }
+    }
+    conn.close();
+    serverConn.close();
+    listener.close();
+  },
+);
+
+unitTest(
+  {
+    ignore: Deno.build.os === "windows",
+    perms: { read: true, write: true, net: true },
+  },
+  async function netUnixSendFds(): Promise<void> {
+    const socketPath = await Deno.makeTempFile();
+    const listener = Deno.listen({ path: socketPath, transport: "unix" });
+    const accepted = listener.accept() as Promise<Deno.UnixConn>;
+    const conn = await Deno.connect({
+      path: socketPath,
+      transport: "unix",
+    }) as Deno.UnixConn;
+    const serverConn = await accepted;
+
+    const filePath = await Deno.makeTempFile();
+    await Deno.writeTextFile(filePath, "hello");
+    const file = await Deno.open(filePath);
+    const tcpListener = Deno.listen({ hostname: "127.0.0.1", port: 3500 });
+    assertEquals(await conn.sendFds([file, tcpListener]), 1);
+    file.close();
+    tcpListener.close();
+
+    const buf = new Uint8Array(16);
+    const { nread, resources } = await serverConn.receiveFds(buf);
+    assertEquals(nread, 1);
+    assertEquals(resources.length, 2);
+    const [receivedFile, receivedListener] = resources;
+    assert(receivedFile instanceof Deno.File);
+    assertEquals(await receivedFile.read(buf), 5);
+    assertEquals(new TextDecoder().decode(buf.subarray(0, 5)), "hello");
+    receivedFile.close();
+
+    const tcpConn = Deno.connect({ hostname: "127.0.0.1", port: 3500 });
+    const handedOff = receivedListener as Deno.Listener;
+    assertEquals((handedOff.addr as Deno.NetAddr).port, 3500);
+    const tcpServerConn = await handedOff.accept();
+    (await tcpConn).close();
+    tcpServerConn.close();
+    handedOff.close();
+
+    conn.close();
+    assertEquals((await serverConn.receiveFds(buf)).nread, null);
+    serverConn.close();
+    listener.close();
+  },
+);
+
+unitTest(
+  {
+    ignore: Deno.build.os === "windows",
+    perms: { read: true, write: true },
+  },
+  async function netUnixSendFdsWithoutData(): Promise<void> {
+    const socketPath = await Deno.makeTempFile();
+    const listener = Deno.listen({ path: socketPath, transport: "unix" });
+    const accepted = listener.accept();
+    const conn = await Deno.connect({
+      path: socketPath,
+      transport: "unix",
+    }) as Deno.UnixConn;
+    await assertThrowsAsync(
+      () => conn.sendFds([], new Uint8Array()),
+      TypeError,
+    );
+    conn.close();
+    (await accepted).close();
+    listener.close();
+  },
+);
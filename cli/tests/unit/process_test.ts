@@ -4,6 +4,7 @@ import {
   assertEquals,
   assertStringIncludes,
   assertThrows,
+  assertThrowsAsync,
   unitTest,
 } from "./test_util.ts";
 
@@ -506,3 +507,207 @@ unitTest({ perms: { run: true, read: true } }, function killFailed(): void {
 
   p.close();
 });
+
+unitTest(
+  { ignore: Deno.build.os === "windows", perms: { run: true, env: true } },
+  async function runClearEnv(): Promise<void> {
+    Deno.env.set("RUN_CLEAR_ENV_SECRET", "secret");
+    const p = Deno.run({
+      cmd: ["env"],
+      env: { FOO: "bar" },
+      clearEnv: true,
+      stdout: "piped",
+    });
+    const output = new TextDecoder().decode(await p.output());
+    assertEquals(output, "FOO=bar\n");
+    await p.status();
+    p.close();
+    Deno.env.delete("RUN_CLEAR_ENV_SECRET");
+  },
+);
+
+unitTest(
+  { ignore: Deno.build.os === "windows", perms: { run: true } },
+  async function runDetachedKillGroup(): Promise<void> {
+    // A shell whose child shares its process group.
+    const p = Deno.run({
+      cmd: ["sh", "-c", "sleep 10000 & wait"],
+      detached: true,
+    });
+    Deno.kill(-p.pid, Deno.Signal.SIGTERM);
+    const status = await p.status();
+    assertEquals(status.success, false);
+    assertEquals(status.signal, Deno.Signal.SIGTERM);
+    p.close();
+  },
+);
+
+unitTest(
+  { ignore: Deno.build.os === "windows", perms: { run: true } },
+  async function runTimeout(): Promise<void> {
+    const p = Deno.run({ cmd: ["sleep", "10000"], timeout: 100 });
+    const status = await p.status();
+    assertEquals(status.success, false);
+    assertEquals(status.signal, Deno.Signal.SIGKILL);
+    p.close();
+  },
+);
+
+unitTest(
+  { ignore: Deno.build.os === "windows", perms: { run: true } },
+  function runKillOnDrop(): void {
+    const p = Deno.run({
+      cmd: ["sleep", "10000"],
+      detached: "session",
+      killOnDrop: false,
+    });
+    p.close();
+    // The subprocess outlives its resource.
+    Deno.kill(p.pid, Deno.Signal.SIGKILL);
+  },
+);
+
+unitTest({ perms: { run: true } }, function runInvalidTimeout(): void {
+  assertThrows(
+    () => Deno.run({ cmd: ["sleep", "1"], timeout: -1 }),
+    TypeError,
+  );
+});
+
+unitTest(
+  { ignore: Deno.build.os !== "linux", perms: { run: true } },
+  async function runPty(): Promise<void> {
+    const p = Deno.run({
+      cmd: ["sh", "-c", "test -t 0 && test -t 1 && stty size"],
+      stderr: "null",
+      pty: true,
+    });
+    assert(p.pty);
+    assert(!p.stdout);
+    assertEquals(Deno.consoleSize(p.pty.rid), { columns: 80, rows: 24 });
+
+    const output = new TextDecoder().decode(await Deno.readAll(p.pty));
+    assertEquals(output, "24 80\r\n");
+    const status = await p.status();
+    assertEquals(status.success, true);
+    p.pty.close();
+    p.close();
+  },
+);
+
+unitTest(
+  { ignore: Deno.build.os !== "linux", perms: { run: true } },
+  async function runPtyNullStdin(): Promise<void> {
+    const p = Deno.run({
+      cmd: ["sh", "-c", "test ! -t 0 && test -t 1 && echo ok"],
+      stdin: "null",
+      pty: true,
+    });
+    const output = new TextDecoder().decode(await Deno.readAll(p.pty!));
+    assertEquals(output, "ok\r\n");
+    const status = await p.status();
+    assertEquals(status.success, true);
+    p.pty!.close();
+    p.close();
+  },
+);
+
+unitTest(
+  { ignore: Deno.build.os !== "linux", perms: { run: true } },
+  async function runPtyResizeAndSetRaw(): Promise<void> {
+    const p = Deno.run({ cmd: ["sh", "-c", "read line"], pty: true });
+    p.pty!.resize(120, 40);
+    assertEquals(Deno.consoleSize(p.pty!.rid), { columns: 120, rows: 40 });
+
+    Deno.setRaw(p.pty!.rid, true);
+    Deno.setRaw(p.pty!.rid, false);
+
+    await p.pty!.write(new TextEncoder().encode("done\n"));
+    const status = await p.status();
+    assertEquals(status.success, true);
+    p.pty!.close();
+    p.close();
+  },
+);
+
+unitTest({ perms: { read: true } }, function execPermissions(): void {
+  assertThrows(() => {
+    Deno.execSync({ cmd: [Deno.execPath(), "eval", "1"] });
+  }, Deno.errors.PermissionDenied);
+});
+
+unitTest(
+  { perms: { run: true, read: true } },
+  function execSyncOutput(): void {
+    const { status, stdout, stderr } = Deno.execSync({
+      cmd: [
+        Deno.execPath(),
+        "eval",
+        "console.log('out'); console.error('err'); Deno.exit(3)",
+      ],
+    });
+    assertEquals(status, { success: false, code: 3 });
+    assertEquals(new TextDecoder().decode(stdout), "out\n");
+    assertEquals(new TextDecoder().decode(stderr), "err\n");
+  },
+);
+
+unitTest(
+  { perms: { run: true, read: true } },
+  async function execInput(): Promise<void> {
+    const { status, stdout } = await Deno.exec({
+      cmd: [
+        Deno.execPath(),
+        "eval",
+        "Deno.copy(Deno.stdin, Deno.stdout)",
+      ],
+      input: "hello",
+    });
+    assertEquals(status, { success: true, code: 0 });
+    assertEquals(new TextDecoder().decode(stdout), "hello");
+  },
+);
+
+unitTest(
+  { perms: { run: true, read: true } },
+  async function execTimeout(): Promise<void> {
+    await assertThrowsAsync(
+      () =>
+        Deno.exec({
+          cmd: [Deno.execPath(), "eval", "setTimeout(() => {}, 10000)"],
+          timeout: 100,
+        }),
+      Deno.errors.TimedOut,
+    );
+  },
+);
+
+unitTest(
+  { perms: { run: true, read: true } },
+  function execMaxOutput(): void {
+    assertThrows(
+      () =>
+        Deno.execSync({
+          cmd: [
+            Deno.execPath(),
+            "eval",
+            "while (true) Deno.stdout.writeSync(new Uint8Array(1024))",
+          ],
+          maxOutput: 4096,
+        }),
+      RangeError,
+      "maxOutput",
+    );
+  },
+);
+
+unitTest(
+  { ignore: Deno.build.os === "windows", perms: { run: true } },
+  async function execTimeoutOpenPipe(): Promise<void> {
+    // The child exits right away, but its own child keeps stdout open.
+    await assertThrowsAsync(
+      () => Deno.exec({ cmd: ["sh", "-c", "sleep 10 &"], timeout: 500 }),
+      Deno.errors.TimedOut,
+    );
+  },
+);
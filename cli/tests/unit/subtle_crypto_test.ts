@@ -0,0 +1,618 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+import {
+  assert,
+  assertEquals,
+  assertThrows,
+  assertThrowsAsync,
+  unitTest,
+} from "./test_util.ts";
+
+function toHex(buffer: ArrayBuffer): string {
+  return Array.from(new Uint8Array(buffer))
+    .map((byte) => byte.toString(16).padStart(2, "0"))
+    .join("");
+}
+
+unitTest(async function subtleCryptoDigest(): Promise<void> {
+  const data = new TextEncoder().encode("abc");
+  const expected: Record<string, string> = {
+    "SHA-1": "a9993e364706816aba3e25717850c26c9cd0d89d",
+    "SHA-256":
+      "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+    "SHA-384":
+      "cb00753f45a35e8bb5a03d699ac65007272c32ab0eded1631a8b605a43ff5bed" +
+      "8086072ba1e7cc2358baeca134c825a7",
+    "SHA-512":
+      "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a" +
+      "2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f",
+  };
+  for (const [algorithm, hex] of Object.entries(expected)) {
+    const digest = await crypto.subtle.digest(algorithm, data);
+    assert(digest instanceof ArrayBuffer);
+    assertEquals(toHex(digest), hex);
+  }
+});
+
+unitTest(async function subtleCryptoDigestInputs(): Promise<void> {
+  const bytes = new TextEncoder().encode("xabcx");
+  const expected =
+    "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+  // Algorithm names are case-insensitive and can be given as an object.
+  const fromView = await crypto.subtle.digest(
+    { name: "sha-256" },
+    bytes.subarray(1, 4),
+  );
+  assertEquals(toHex(fromView), expected);
+  const fromBuffer = await crypto.subtle.digest(
+    "SHA-256",
+    bytes.slice(1, 4).buffer,
+  );
+  assertEquals(toHex(fromBuffer), expected);
+  const empty = await crypto.subtle.digest("SHA-1", new Uint8Array());
+  assertEquals(toHex(empty), "da39a3ee5e6b4b0d3255bfef95601890afd80709");
+});
+
+unitTest(async function subtleCryptoDigestErrors(): Promise<void> {
+  const data = new Uint8Array(1);
+  await assertThrowsAsync(
+    () => crypto.subtle.digest("MD5", data),
+    DOMException,
+    'Unrecognized algorithm name "MD5"',
+  );
+  await assertThrowsAsync(
+    // deno-lint-ignore no-explicit-any
+    () => crypto.subtle.digest("SHA-256", "abc" as any),
+    TypeError,
+  );
+  assertThrows(() => {
+    new SubtleCrypto();
+  }, TypeError);
+});
+
+unitTest(async function subtleCryptoHmac(): Promise<void> {
+  const key = await crypto.subtle.generateKey(
+    { name: "HMAC", hash: "SHA-256" },
+    false,
+    ["sign", "verify"],
+  );
+  assert(key instanceof CryptoKey);
+  assertEquals(key.type, "secret");
+  assertEquals(key.extractable, false);
+  assertEquals(key.algorithm, {
+    name: "HMAC",
+    hash: { name: "SHA-256" },
+    length: 512,
+  });
+  assertEquals(key.usages, ["sign", "verify"]);
+
+  const data = new TextEncoder().encode("hello");
+  const signature = await crypto.subtle.sign("HMAC", key, data);
+  assertEquals(signature.byteLength, 32);
+  assert(await crypto.subtle.verify("HMAC", key, signature, data));
+  const other = new TextEncoder().encode("hellO");
+  assert(!await crypto.subtle.verify("HMAC", key, signature, other));
+});
+
+unitTest(async function subtleCryptoKeyImmutable(): Promise<void> {
+  const key = await crypto.subtle.generateKey(
+    { name: "HMAC", hash: "SHA-256" },
+    false,
+    ["verify"],
+  );
+  assertThrows(() => {
+    (key.usages as KeyUsage[]).push("sign");
+  }, TypeError);
+  assertThrows(() => {
+    const algorithm = key.algorithm as unknown as { hash: { name: string } };
+    algorithm.hash.name = "SHA-1";
+  }, TypeError);
+  assertEquals(key.usages, ["verify"]);
+  const data = new TextEncoder().encode("hello");
+  await assertThrowsAsync(
+    () => crypto.subtle.sign("HMAC", key, data),
+    DOMException,
+    'Key usages don\'t include "sign"',
+  );
+});
+
+unitTest(async function subtleCryptoEcdsa(): Promise<void> {
+  const { privateKey, publicKey } = await crypto.subtle.generateKey(
+    { name: "ECDSA", namedCurve: "P-384" },
+    true,
+    ["sign", "verify"],
+  );
+  assertEquals(privateKey.type, "private");
+  assertEquals(privateKey.usages, ["sign"]);
+  assertEquals(publicKey.type, "public");
+  assertEquals(publicKey.usages, ["verify"]);
+  assertEquals(publicKey.algorithm, { name: "ECDSA", namedCurve: "P-384" });
+
+  const algorithm = { name: "ECDSA", hash: "SHA-384" };
+  const data = new TextEncoder().encode("hello");
+  const signature = await crypto.subtle.sign(algorithm, privateKey, data);
+  assertEquals(signature.byteLength, 96);
+  assert(await crypto.subtle.verify(algorithm, publicKey, signature, data));
+  const other = new TextEncoder().encode("hellO");
+  assert(!await crypto.subtle.verify(algorithm, publicKey, signature, other));
+  await assertThrowsAsync(
+    () => crypto.subtle.sign(algorithm, publicKey, data),
+    DOMException,
+    'Key usages don\'t include "sign"',
+  );
+});
+
+unitTest(async function subtleCryptoRsa(): Promise<void> {
+  const data = new TextEncoder().encode("hello");
+  const other = new TextEncoder().encode("hellO");
+  const publicExponent = new Uint8Array([1, 0, 1]);
+
+  const pkcs1 = await crypto.subtle.generateKey(
+    {
+      name: "RSASSA-PKCS1-v1_5",
+      modulusLength: 1024,
+      publicExponent,
+      hash: "SHA-256",
+    },
+    false,
+    ["sign", "verify"],
+  );
+  assertEquals(pkcs1.privateKey.algorithm.name, "RSASSA-PKCS1-v1_5");
+  const signature = await crypto.subtle.sign(
+    "RSASSA-PKCS1-v1_5",
+    pkcs1.privateKey,
+    data,
+  );
+  assertEquals(signature.byteLength, 128);
+  assert(
+    await crypto.subtle.verify(
+      "RSASSA-PKCS1-v1_5",
+      pkcs1.publicKey,
+      signature,
+      data,
+    ),
+  );
+  assert(
+    !await crypto.subtle.verify(
+      "RSASSA-PKCS1-v1_5",
+      pkcs1.publicKey,
+      signature,
+      other,
+    ),
+  );
+
+  const pss = await crypto.subtle.generateKey(
+    { name: "RSA-PSS", modulusLength: 1024, publicExponent, hash: "SHA-1" },
+    false,
+    ["sign", "verify"],
+  );
+  const algorithm = { name: "RSA-PSS", saltLength: 20 };
+  const pssSignature = await crypto.subtle.sign(
+    algorithm,
+    pss.privateKey,
+    data,
+  );
+  assert(
+    await crypto.subtle.verify(algorithm, pss.publicKey, pssSignature, data),
+  );
+  await assertThrowsAsync(
+    () => crypto.subtle.verify(algorithm, pkcs1.publicKey, signature, data),
+    DOMException,
+    'Key algorithm "RSASSA-PKCS1-v1_5" doesn\'t match "RSA-PSS"',
+  );
+});
+
+unitTest(async function subtleCryptoGenerateKeyErrors(): Promise<void> {
+  await assertThrowsAsync(
+    () =>
+      crypto.subtle.generateKey({ name: "HMAC", hash: "SHA-256" }, false, [
+        "encrypt" as KeyUsage,
+      ]),
+    DOMException,
+    'Invalid key usage "encrypt"',
+  );
+  await assertThrowsAsync(
+    () =>
+      crypto.subtle.generateKey(
+        { name: "ECDSA", namedCurve: "P-521" },
+        false,
+        ["sign"],
+      ),
+    DOMException,
+    'Unsupported named curve "P-521"',
+  );
+  await assertThrowsAsync(
+    () =>
+      crypto.subtle.generateKey(
+        {
+          name: "RSA-PSS",
+          modulusLength: 1024,
+          publicExponent: new Uint8Array([3]),
+          hash: "SHA-256",
+        },
+        false,
+        ["sign"],
+      ),
+    DOMException,
+    "Only the public exponent 65537 is supported",
+  );
+  await assertThrowsAsync(
+    () =>
+      crypto.subtle.generateKey(
+        { name: "HMAC", hash: "SHA-1", length: 12 },
+        false,
+        ["sign"],
+      ),
+    DOMException,
+    "HMAC key length must be a non-zero multiple of 8",
+  );
+  assertThrows(() => {
+    new CryptoKey();
+  }, TypeError);
+});
+
+function fromHex(hex: string): Uint8Array {
+  return new Uint8Array(
+    hex.match(/../g)!.map((byte) => parseInt(byte, 16)),
+  );
+}
+
+unitTest(async function subtleCryptoAesCbc(): Promise<void> {
+  // NIST SP 800-38A, F.2.1.
+  const key = await crypto.subtle.importKey(
+    "raw",
+    fromHex("2b7e151628aed2a6abf7158809cf4f3c"),
+    "AES-CBC",
+    true,
+    ["encrypt", "decrypt"],
+  );
+  assertEquals(key.algorithm, { name: "AES-CBC", length: 128 });
+  const iv = fromHex("000102030405060708090a0b0c0d0e0f");
+  const plaintext = fromHex("6bc1bee22e409f96e93d7e117393172a");
+  const ciphertext = await crypto.subtle.encrypt(
+    { name: "AES-CBC", iv },
+    key,
+    plaintext,
+  );
+  // The ciphertext ends with a block of padding.
+  assertEquals(ciphertext.byteLength, 32);
+  assertEquals(
+    toHex(ciphertext.slice(0, 16)),
+    "7649abac8119b246cee98e9b12e9197d",
+  );
+  const decrypted = await crypto.subtle.decrypt(
+    { name: "AES-CBC", iv },
+    key,
+    ciphertext,
+  );
+  assertEquals(new Uint8Array(decrypted), plaintext);
+  assertEquals(
+    toHex(await crypto.subtle.exportKey("raw", key)),
+    "2b7e151628aed2a6abf7158809cf4f3c",
+  );
+});
+
+unitTest(async function subtleCryptoAesCtr(): Promise<void> {
+  // NIST SP 800-38A, F.5.1.
+  const key = await crypto.subtle.importKey(
+    "raw",
+    fromHex("2b7e151628aed2a6abf7158809cf4f3c"),
+    "AES-CTR",
+    false,
+    ["encrypt"],
+  );
+  const ciphertext = await crypto.subtle.encrypt(
+    {
+      name: "AES-CTR",
+      counter: fromHex("f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff"),
+      length: 64,
+    },
+    key,
+    fromHex("6bc1bee22e409f96e93d7e117393172a"),
+  );
+  assertEquals(toHex(ciphertext), "874d6191b620e3261bef6864990db6ce");
+});
+
+unitTest(async function subtleCryptoAesGcm(): Promise<void> {
+  const key = await crypto.subtle.generateKey(
+    { name: "AES-GCM", length: 256 },
+    true,
+    ["encrypt", "decrypt"],
+  ) as CryptoKey;
+  assertEquals(key.algorithm, { name: "AES-GCM", length: 256 });
+  const iv = crypto.getRandomValues(new Uint8Array(12));
+  const additionalData = new TextEncoder().encode("header");
+  const data = new TextEncoder().encode("hello");
+  const ciphertext = await crypto.subtle.encrypt(
+    { name: "AES-GCM", iv, additionalData },
+    key,
+    data,
+  );
+  assertEquals(ciphertext.byteLength, data.length + 16);
+  const decrypted = await crypto.subtle.decrypt(
+    { name: "AES-GCM", iv, additionalData },
+    key,
+    ciphertext,
+  );
+  assertEquals(new Uint8Array(decrypted), data);
+  await assertThrowsAsync(
+    () => crypto.subtle.decrypt({ name: "AES-GCM", iv }, key, ciphertext),
+    DOMException,
+    "Decryption failed",
+  );
+
+  const jwk = await crypto.subtle.exportKey("jwk", key);
+  assertEquals(jwk.kty, "oct");
+  assertEquals(jwk.alg, "A256GCM");
+  assertEquals(jwk.ext, true);
+  assertEquals(jwk.key_ops, ["encrypt", "decrypt"]);
+  const imported = await crypto.subtle.importKey(
+    "jwk",
+    jwk,
+    "AES-GCM",
+    false,
+    ["decrypt"],
+  );
+  const decryptedWithImported = await crypto.subtle.decrypt(
+    { name: "AES-GCM", iv, additionalData },
+    imported,
+    ciphertext,
+  );
+  assertEquals(new Uint8Array(decryptedWithImported), data);
+  await assertThrowsAsync(
+    () => crypto.subtle.exportKey("raw", imported),
+    DOMException,
+    "Key is not extractable",
+  );
+});
+
+unitTest(async function subtleCryptoRsaOaep(): Promise<void> {
+  const { privateKey, publicKey } = await crypto.subtle.generateKey(
+    {
+      name: "RSA-OAEP",
+      modulusLength: 1024,
+      publicExponent: new Uint8Array([1, 0, 1]),
+      hash: "SHA-256",
+    },
+    true,
+    ["encrypt", "decrypt"],
+  ) as CryptoKeyPair;
+  assertEquals(privateKey.usages, ["decrypt"]);
+  assertEquals(publicKey.usages, ["encrypt"]);
+  const label = new TextEncoder().encode("label");
+  const data = new TextEncoder().encode("hello");
+  const ciphertext = await crypto.subtle.encrypt(
+    { name: "RSA-OAEP", label },
+    publicKey,
+    data,
+  );
+  assertEquals(ciphertext.byteLength, 128);
+  const decrypted = await crypto.subtle.decrypt(
+    { name: "RSA-OAEP", label },
+    privateKey,
+    ciphertext,
+  );
+  assertEquals(new Uint8Array(decrypted), data);
+
+  // The exported keys import back to the same key material.
+  const spki = await crypto.subtle.exportKey("spki", publicKey);
+  const pkcs8 = await crypto.subtle.exportKey("pkcs8", privateKey);
+  const importedPublic = await crypto.subtle.importKey(
+    "spki",
+    spki,
+    { name: "RSA-OAEP", hash: "SHA-256" },
+    true,
+    ["encrypt"],
+  );
+  assertEquals(importedPublic.algorithm, publicKey.algorithm);
+  const importedPrivate = await crypto.subtle.importKey(
+    "pkcs8",
+    pkcs8,
+    { name: "RSA-OAEP", hash: "SHA-256" },
+    true,
+    ["decrypt"],
+  );
+  const jwk = await crypto.subtle.exportKey("jwk", importedPrivate);
+  assertEquals(jwk.alg, "RSA-OAEP-256");
+  assertEquals(
+    toHex(await crypto.subtle.exportKey("pkcs8", importedPrivate)),
+    toHex(pkcs8),
+  );
+  const reencrypted = await crypto.subtle.encrypt(
+    { name: "RSA-OAEP" },
+    importedPublic,
+    data,
+  );
+  const redecrypted = await crypto.subtle.decrypt(
+    { name: "RSA-OAEP" },
+    importedPrivate,
+    reencrypted,
+  );
+  assertEquals(new Uint8Array(redecrypted), data);
+});
+
+unitTest(async function subtleCryptoEcImport(): Promise<void> {
+  const { privateKey, publicKey } = await crypto.subtle.generateKey(
+    { name: "ECDSA", namedCurve: "P-256" },
+    true,
+    ["sign", "verify"],
+  ) as CryptoKeyPair;
+  const raw = new Uint8Array(await crypto.subtle.exportKey("raw", publicKey));
+  assertEquals(raw.length, 65);
+  assertEquals(raw[0], 4);
+  const jwk = await crypto.subtle.exportKey("jwk", privateKey);
+  assertEquals(jwk.kty, "EC");
+  assertEquals(jwk.crv, "P-256");
+  const imported = await crypto.subtle.importKey(
+    "jwk",
+    jwk,
+    { name: "ECDSA", namedCurve: "P-256" },
+    false,
+    ["sign"],
+  );
+  const data = new TextEncoder().encode("hello");
+  const signature = await crypto.subtle.sign(
+    { name: "ECDSA", hash: "SHA-256" },
+    imported,
+    data,
+  );
+  assert(
+    await crypto.subtle.verify(
+      { name: "ECDSA", hash: "SHA-256" },
+      publicKey,
+      signature,
+      data,
+    ),
+  );
+  await assertThrowsAsync(
+    () =>
+      crypto.subtle.importKey(
+        "raw",
+        raw.subarray(1),
+        { name: "ECDSA", namedCurve: "P-256" },
+        true,
+        ["verify"],
+      ),
+    DOMException,
+  );
+  await assertThrowsAsync(
+    () =>
+      crypto.subtle.importKey(
+        "jwk",
+        { ...jwk, ext: false },
+        { name: "ECDSA", namedCurve: "P-256" },
+        true,
+        ["sign"],
+      ),
+    DOMException,
+    "JWK is not extractable",
+  );
+});
+
+unitTest(async function subtleCryptoPbkdf2(): Promise<void> {
+  // RFC 6070, test case 2.
+  const key = await crypto.subtle.importKey(
+    "raw",
+    new TextEncoder().encode("password"),
+    "PBKDF2",
+    false,
+    ["deriveBits", "deriveKey"],
+  );
+  const bits = await crypto.subtle.deriveBits(
+    {
+      name: "PBKDF2",
+      hash: "SHA-1",
+      salt: new TextEncoder().encode("salt"),
+      iterations: 2,
+    },
+    key,
+    160,
+  );
+  assertEquals(toHex(bits), "ea6c014dc72d6f8ccd1ed92ace1d41f0d8de8957");
+
+  const derived = await crypto.subtle.deriveKey(
+    {
+      name: "PBKDF2",
+      hash: "SHA-1",
+      salt: new TextEncoder().encode("salt"),
+      iterations: 2,
+    },
+    key,
+    { name: "HMAC", hash: "SHA-1", length: 160 },
+    true,
+    ["sign"],
+  );
+  assertEquals(derived.algorithm, {
+    name: "HMAC",
+    hash: { name: "SHA-1" },
+    length: 160,
+  });
+  const exported = await crypto.subtle.exportKey("raw", derived);
+  assertEquals(toHex(exported), toHex(bits));
+  await assertThrowsAsync(
+    () =>
+      crypto.subtle.importKey(
+        "raw",
+        new Uint8Array(8),
+        "PBKDF2",
+        true,
+        ["deriveBits"],
+      ),
+    DOMException,
+    "PBKDF2 keys can't be extractable",
+  );
+});
+
+unitTest(async function subtleCryptoHkdf(): Promise<void> {
+  // RFC 5869, test case 1.
+  const key = await crypto.subtle.importKey(
+    "raw",
+    new Uint8Array(22).fill(0x0b),
+    "HKDF",
+    false,
+    ["deriveBits"],
+  );
+  const bits = await crypto.subtle.deriveBits(
+    {
+      name: "HKDF",
+      hash: "SHA-256",
+      salt: fromHex("000102030405060708090a0b0c"),
+      info: fromHex("f0f1f2f3f4f5f6f7f8f9"),
+    },
+    key,
+    336,
+  );
+  assertEquals(
+    toHex(bits),
+    "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf" +
+      "34007208d5b887185865",
+  );
+  await assertThrowsAsync(
+    () =>
+      crypto.subtle.deriveBits(
+        {
+          name: "HKDF",
+          hash: "SHA-256",
+          salt: new Uint8Array(),
+          info: new Uint8Array(),
+        },
+        key,
+        7,
+      ),
+    DOMException,
+    "Length must be a multiple of 8",
+  );
+});
+
+unitTest(async function subtleCryptoEcdh(): Promise<void> {
+  const generate = () =>
+    crypto.subtle.generateKey(
+      { name: "ECDH", namedCurve: "P-256" },
+      true,
+      ["deriveBits"],
+    ) as Promise<CryptoKeyPair>;
+  const alice = await generate();
+  const bob = await generate();
+  assertEquals(alice.publicKey.usages, []);
+  const aliceBits = await crypto.subtle.deriveBits(
+    { name: "ECDH", public: bob.publicKey },
+    alice.privateKey,
+    null,
+  );
+  const bobBits = await crypto.subtle.deriveBits(
+    { name: "ECDH", public: alice.publicKey },
+    bob.privateKey,
+    256,
+  );
+  assertEquals(aliceBits.byteLength, 32);
+  assertEquals(toHex(aliceBits), toHex(bobBits));
+  await assertThrowsAsync(
+    () =>
+      crypto.subtle.deriveBits(
+        { name: "ECDH", public: bob.privateKey },
+        alice.privateKey,
+        256,
+      ),
+    DOMException,
+    "Algorithm public must be an ECDH public key",
+  );
+});
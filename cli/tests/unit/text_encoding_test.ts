@@ -103,15 +103,48 @@ unitTest(function textDecoderErrorEncoding(): void {
   assert(didThrow);
 });
 
-unitTest(function textDecoderHandlesNotFoundInternalDecoder() {
-  let didThrow = false;
-  try {
-    new TextDecoder("gbk");
-  } catch (e) {
-    didThrow = true;
-    assert(e instanceof RangeError);
+unitTest(function textDecoderReplacementEncoding(): void {
+  assertThrows(() => new TextDecoder("iso-2022-kr"), RangeError);
+});
+
+unitTest(function textDecoderLegacyEncodings(): void {
+  const shiftJis = new TextDecoder(" Shift_JIS ");
+  assertEquals(shiftJis.encoding, "shift_jis");
+  const fixture = new Uint8Array([0x93, 0xfa, 0x96, 0x7b]);
+  assertEquals(shiftJis.decode(fixture), "日本");
+
+  const windows1252 = new TextDecoder("latin1", { fatal: true });
+  assertEquals(windows1252.encoding, "windows-1252");
+  assertEquals(windows1252.decode(new Uint8Array([0x80])), "€");
+
+  const gbk = new TextDecoder("gbk", { fatal: true });
+  assertEquals(gbk.decode(new Uint8Array([0xd6, 0xd0, 0xce, 0xc4])), "中文");
+  assertThrows(() => gbk.decode(new Uint8Array([0xd6])), TypeError);
+});
+
+unitTest(function textDecoderUtf16(): void {
+  const fixture = new Uint8Array([0xff, 0xfe, 0x61, 0x00, 0x3d, 0xd8]);
+  assertEquals(new TextDecoder("utf-16").decode(fixture), "a\ufffd");
+  const ignoreBOM = new TextDecoder("utf-16le", { ignoreBOM: true });
+  assertEquals(ignoreBOM.ignoreBOM, true);
+  assertEquals(ignoreBOM.decode(fixture), "\ufeffa\ufffd");
+});
+
+unitTest(function textDecoderStream(): void {
+  const decoder = new TextDecoder("shift_jis");
+  const fixture = new Uint8Array([0x93, 0xfa, 0x96, 0x7b]);
+  let decoded = "";
+  for (const byte of fixture) {
+    decoded += decoder.decode(new Uint8Array([byte]), { stream: true });
   }
-  assert(didThrow);
+  decoded += decoder.decode();
+  assertEquals(decoded, "日本");
+
+  // An incomplete sequence at the end of a stream is replaced, and the next
+  // call starts a new stream.
+  assertEquals(decoder.decode(fixture.subarray(0, 1), { stream: true }), "");
+  assertEquals(decoder.decode(), "\ufffd");
+  assertEquals(decoder.decode(fixture.subarray(2)), "本");
 });
 
 unitTest(function textEncoder(): void {
@@ -11,6 +11,7 @@ import "./buffer_test.ts";
 import "./build_test.ts";
 import "./chmod_test.ts";
 import "./chown_test.ts";
+import "./compression_stream_test.ts";
 import "./console_test.ts";
 import "./copy_file_test.ts";
 import "./custom_event_test.ts";
@@ -26,6 +27,7 @@ import "./file_test.ts";
 import "./filereader_test.ts";
 import "./files_test.ts";
 import "./filter_function_test.ts";
+import "./flock_test.ts";
 import "./form_data_test.ts";
 import "./format_error_test.ts";
 import "./fs_events_test.ts";
@@ -62,6 +64,7 @@ import "./streams_internal_test.ts";
 import "./streams_piping_test.ts";
 import "./streams_transform_test.ts";
 import "./streams_writable_test.ts";
+import "./subtle_crypto_test.ts";
 import "./symlink_test.ts";
 import "./sync_test.ts";
 import "./text_encoding_test.ts";
@@ -77,6 +80,7 @@ import "./utime_test.ts";
 import "./worker_types.ts";
 import "./write_file_test.ts";
 import "./write_text_file_test.ts";
+import "./xattr_test.ts";
 import "./performance_test.ts";
 import "./version_test.ts";
 import "./websocket_test.ts";
@@ -1,5 +1,11 @@
 // Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
-import { assert, assertEquals, assertThrows, unitTest } from "./test_util.ts";
+import {
+  assert,
+  assertEquals,
+  assertThrows,
+  assertThrowsAsync,
+  unitTest,
+} from "./test_util.ts";
 
 unitTest(function urlParsing(): void {
   const url = new URL(
@@ -500,3 +506,43 @@ unitTest(function emptyPortForSchemeDefaultPort(): void {
     assertEquals(url.port, "");
   }
 });
+
+unitTest(async function urlCreateObjectURL(): Promise<void> {
+  const blob = new Blob(["export default 42;"], {
+    type: "application/javascript",
+  });
+  const url = URL.createObjectURL(blob);
+  assert(url.startsWith(`blob:${location.origin}/`));
+
+  const response = await fetch(url);
+  assertEquals(response.status, 200);
+  assertEquals(response.headers.get("content-type"), "application/javascript");
+  assertEquals(response.headers.get("content-length"), "18");
+  assertEquals(await response.text(), "export default 42;");
+
+  const { default: answer } = await import(url + "#fragment");
+  assertEquals(answer, 42);
+
+  await assertThrowsAsync(
+    () => fetch(url, { method: "POST" }),
+    TypeError,
+    "not allowed",
+  );
+
+  URL.revokeObjectURL(url);
+  await assertThrowsAsync(() => fetch(url), TypeError, "Blob URL not found");
+  // Revoking twice, or revoking other URLs, does nothing.
+  URL.revokeObjectURL(url);
+  URL.revokeObjectURL("https://deno.land/");
+});
+
+unitTest(function urlCreateObjectURLNotBlob(): void {
+  assertThrows(
+    () => {
+      // @ts-expect-error testing invalid input
+      URL.createObjectURL("not a blob");
+    },
+    TypeError,
+    "Argument is not a Blob",
+  );
+});
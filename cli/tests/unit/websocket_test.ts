@@ -1,5 +1,11 @@
 // Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
-import { assertThrows, unitTest } from "./test_util.ts";
+import {
+  assertEquals,
+  assertThrows,
+  assertThrowsAsync,
+  deferred,
+  unitTest,
+} from "./test_util.ts";
 
 unitTest(function websocketPermissionless() {
   assertThrows(
@@ -7,3 +13,79 @@ unitTest(function websocketPermissionless() {
     Deno.errors.PermissionDenied,
   );
 });
+
+unitTest({ perms: { net: true } }, async function websocketUpgrade() {
+  const listener = Deno.listen({ hostname: "127.0.0.1", port: 4248 });
+  const echoed = deferred<string>();
+  const client = new WebSocket("ws://127.0.0.1:4248/echo", ["a", "b"]);
+  client.onopen = () => client.send("hello");
+  client.onmessage = (e) => {
+    echoed.resolve(e.data);
+    client.close();
+  };
+
+  const conn = await listener.accept();
+  const server = await Deno.upgradeWebSocket(conn, { protocols: ["b"] });
+  assertEquals(server.readyState, WebSocket.OPEN);
+  assertEquals(server.protocol, "b");
+  assertEquals(server.url, "/echo");
+  const closed = deferred();
+  server.onmessage = (e) => server.send(e.data);
+  server.onclose = () => closed.resolve();
+
+  assertEquals(await echoed, "hello");
+  await closed;
+  listener.close();
+});
+
+unitTest({ perms: { net: true } }, async function websocketUpgradeBusy() {
+  const listener = Deno.listen({ hostname: "127.0.0.1", port: 4250 });
+  const client = await Deno.connect({ hostname: "127.0.0.1", port: 4250 });
+  const conn = await listener.accept();
+  const read = conn.read(new Uint8Array(1));
+  await assertThrowsAsync(
+    () => Deno.upgradeWebSocket(conn),
+    Deno.errors.Busy,
+  );
+  await client.write(new Uint8Array([1]));
+  assertEquals(await read, 1);
+  await conn.write(new Uint8Array([2]));
+  const buf = new Uint8Array(1);
+  assertEquals(await client.read(buf), 1);
+  assertEquals(buf, new Uint8Array([2]));
+  client.close();
+  conn.close();
+  listener.close();
+});
+
+unitTest({ perms: { net: true } }, async function websocketStream() {
+  const listener = Deno.listen({ hostname: "127.0.0.1", port: 4249 });
+  const wss = new WebSocketStream("ws://127.0.0.1:4249/", {
+    headers: { authorization: "Bearer token" },
+  });
+
+  const server = await Deno.upgradeWebSocket(await listener.accept());
+  server.onmessage = (e) => server.send(`echo: ${e.data}`);
+  server.send("welcome");
+
+  const { readable, writable, extensions } = await wss.connection;
+  assertEquals(extensions, "permessage-deflate");
+  const reader = readable.getReader();
+  assertEquals((await reader.read()).value, "welcome");
+  const writer = writable.getWriter();
+  await writer.write("hello");
+  assertEquals((await reader.read()).value, "echo: hello");
+
+  wss.close({ code: 1000, reason: "done" });
+  assertEquals(await wss.closed, { code: 1000, reason: "done" });
+  assertEquals((await reader.read()).done, true);
+  listener.close();
+});
+
+unitTest({ perms: { net: true } }, async function websocketStreamHeaders() {
+  const wss = new WebSocketStream("ws://127.0.0.1:4249/", {
+    headers: { "sec-websocket-key": "dGhlIHNhbXBsZSBub25jZQ==" },
+  });
+  await assertThrowsAsync(() => wss.connection, TypeError);
+  await assertThrowsAsync(() => wss.closed, TypeError);
+});
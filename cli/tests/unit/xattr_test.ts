@@ -0,0 +1,127 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+import {
+  assert,
+  assertEquals,
+  assertThrows,
+  assertThrowsAsync,
+  unitTest,
+} from "./test_util.ts";
+
+const xattrSupported = Deno.build.os === "linux" ||
+  Deno.build.os === "darwin";
+
+unitTest(
+  { ignore: !xattrSupported, perms: { read: true, write: true } },
+  function xattrSyncSuccess(): void {
+    const path = Deno.makeTempFileSync();
+    assertEquals(Deno.getXattrSync(path, "user.deno"), null);
+    Deno.setXattrSync(path, "user.deno", "hello");
+    assertEquals(
+      Deno.getXattrSync(path, "user.deno"),
+      new TextEncoder().encode("hello"),
+    );
+    assert(Deno.listXattrSync(path).includes("user.deno"));
+    Deno.removeXattrSync(path, "user.deno");
+    assertEquals(Deno.getXattrSync(path, "user.deno"), null);
+    assert(!Deno.listXattrSync(path).includes("user.deno"));
+  },
+);
+
+unitTest(
+  { ignore: !xattrSupported, perms: { read: true, write: true } },
+  async function xattrSuccess(): Promise<void> {
+    const path = await Deno.makeTempFile();
+    const value = new Uint8Array([0, 1, 2, 255]);
+    await Deno.setXattr(path, "user.deno", value);
+    assertEquals(await Deno.getXattr(path, "user.deno"), value);
+    assert((await Deno.listXattr(path)).includes("user.deno"));
+    await Deno.removeXattr(path, "user.deno");
+    assertEquals(await Deno.getXattr(path, "user.deno"), null);
+  },
+);
+
+unitTest(
+  { ignore: !xattrSupported, perms: { read: true, write: true } },
+  async function xattrRid(): Promise<void> {
+    const path = Deno.makeTempFileSync();
+    const file = await Deno.open(path, { read: true, write: true });
+    Deno.setXattrSync(file.rid, "user.deno", "sync");
+    assertEquals(
+      Deno.getXattrSync(path, "user.deno"),
+      new TextEncoder().encode("sync"),
+    );
+    await Deno.setXattr(file.rid, "user.deno", "async");
+    assertEquals(
+      await Deno.getXattr(file.rid, "user.deno"),
+      new TextEncoder().encode("async"),
+    );
+    assert((await Deno.listXattr(file.rid)).includes("user.deno"));
+    Deno.removeXattrSync(file.rid, "user.deno");
+    assertEquals(Deno.getXattrSync(file.rid, "user.deno"), null);
+    file.close();
+  },
+);
+
+unitTest(
+  { ignore: !xattrSupported, perms: { read: true, write: true } },
+  function xattrNotFound(): void {
+    const path = Deno.makeTempFileSync();
+    assertThrows(() => {
+      Deno.removeXattrSync(path, "user.deno");
+    });
+    assertThrows(() => {
+      Deno.getXattrSync(path + ".missing", "user.deno");
+    }, Deno.errors.NotFound);
+  },
+);
+
+unitTest(
+  { perms: { read: false } },
+  async function xattrReadPerm(): Promise<void> {
+    assertThrows(() => {
+      Deno.getXattrSync("README.md", "user.deno");
+    }, Deno.errors.PermissionDenied);
+    await assertThrowsAsync(async () => {
+      await Deno.listXattr("README.md");
+    }, Deno.errors.PermissionDenied);
+  },
+);
+
+unitTest(
+  { perms: { read: true, write: false } },
+  async function xattrWritePerm(): Promise<void> {
+    assertThrows(() => {
+      Deno.setXattrSync("README.md", "user.deno", "hello");
+    }, Deno.errors.PermissionDenied);
+    await assertThrowsAsync(async () => {
+      await Deno.removeXattr("README.md", "user.deno");
+    }, Deno.errors.PermissionDenied);
+  },
+);
+
+unitTest(
+  { ignore: Deno.build.os === "windows", perms: { read: true } },
+  async function statfsSuccess(): Promise<void> {
+    const info = Deno.statfsSync(".");
+    assertEquals(typeof info.type, "string");
+    assert(info.blockSize > 0);
+    assert(info.blocks > 0);
+    assert(info.blocksFree <= info.blocks);
+    assert(info.blocksAvailable <= info.blocksFree);
+    assert(info.filesFree <= info.files);
+    assert(info.filesAvailable <= info.filesFree);
+    assertEquals((await Deno.statfs(".")).blocks, info.blocks);
+  },
+);
+
+unitTest(
+  { perms: { read: false } },
+  async function statfsPerm(): Promise<void> {
+    assertThrows(() => {
+      Deno.statfsSync(".");
+    }, Deno.errors.PermissionDenied);
+    await assertThrowsAsync(async () => {
+      await Deno.statfs(".");
+    }, Deno.errors.PermissionDenied);
+  },
+);
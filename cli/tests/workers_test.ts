@@ -67,6 +67,31 @@ Deno.test({
   },
 });
 
+Deno.test({
+  name: "worker from blob url",
+  async fn() {
+    const promise = deferred();
+    const blob = new Blob([
+      "onmessage = (e: MessageEvent): void => {\n",
+      "  postMessage(e.data + ' from ' + self.name);\n",
+      "};\n",
+    ], { type: "application/typescript" });
+    const url = URL.createObjectURL(blob);
+    const tsWorker = new Worker(url, { type: "module", name: "tsWorker" });
+
+    tsWorker.onmessage = (e): void => {
+      assertEquals(e.data, "Hello World from tsWorker");
+      promise.resolve();
+    };
+
+    tsWorker.postMessage("Hello World");
+
+    await promise;
+    tsWorker.terminate();
+    URL.revokeObjectURL(url);
+  },
+});
+
 Deno.test({
   name: "worker nested",
   fn: async function (): Promise<void> {
@@ -9,7 +9,7 @@ use deno_core::serde_json::json;
 use deno_core::url::Url;
 use deno_core::ModuleSpecifier;
 use deno_runtime::inspector::InspectorSession;
-use deno_runtime::permissions::Permissions;
+use deno_runtime::permissions::PermissionsContainer;
 use serde::Deserialize;
 use serde::Serialize;
 use std::fs;
@@ -323,7 +323,7 @@ pub async fn report_coverages(
       .prepare_module_load(
         module_specifier.clone(),
         TypeLib::UnstableDenoWindow,
-        Permissions::allow_all(),
+        PermissionsContainer::allow_all(),
         false,
         program_state.maybe_import_map.clone(),
       )
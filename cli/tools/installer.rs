@@ -2,8 +2,10 @@
 
 use crate::flags::Flags;
 use crate::fs_util::canonicalize_path;
+use crate::permissions_manifest;
 use deno_core::error::generic_error;
 use deno_core::error::AnyError;
+use deno_core::serde_json;
 use deno_core::url::Url;
 use log::Level;
 use regex::{Regex, RegexBuilder};
@@ -14,6 +16,7 @@ use std::io;
 use std::io::Write;
 #[cfg(not(windows))]
 use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
 use std::path::PathBuf;
 
 lazy_static! {
@@ -278,6 +281,24 @@ pub fn install(
     extra_files.push((copy_path, fs::read_to_string(config_path)?));
   }
 
+  if let Some(permissions_manifest) = flags.permissions_manifest {
+    let mut copy_path = file_path.clone();
+    copy_path.set_extension("permissions.json");
+    executable_args.push(format!("--permissions={}", copy_path.display()));
+    // The copy has its relative paths resolved, as they are relative to the
+    // original manifest.
+    let manifest =
+      permissions_manifest::load(Path::new(&permissions_manifest))?;
+    extra_files.push((copy_path, serde_json::to_string_pretty(&manifest)?));
+  }
+
+  if let Some(env_file) = flags.env_file {
+    let mut copy_path = file_path.clone();
+    copy_path.set_extension("env");
+    executable_args.push(format!("--env-file={}", copy_path.display()));
+    extra_files.push((copy_path, fs::read_to_string(env_file)?));
+  }
+
   if let Some(lock_path) = flags.lock {
     let mut copy_path = file_path.clone();
     copy_path.set_extension("lock.json");
@@ -798,6 +819,72 @@ mod tests {
     assert!(content == "{}");
   }
 
+  #[test]
+  fn install_with_permissions_manifest() {
+    let temp_dir = TempDir::new().expect("tempdir fail");
+    let bin_dir = temp_dir.path().join("bin");
+    let manifest_path = temp_dir.path().join("test_permissions.json");
+    let manifest = r#"{ "allow_net": ["localhost"], "allow_read": ["data"] }"#;
+    fs::write(&manifest_path, manifest).unwrap();
+
+    let result = install(
+      Flags {
+        permissions_manifest: Some(manifest_path.to_string_lossy().to_string()),
+        ..Flags::default()
+      },
+      "http://localhost:4545/cli/tests/cat.ts",
+      vec![],
+      Some("echo_test".to_string()),
+      Some(temp_dir.path().to_path_buf()),
+      true,
+    );
+    assert!(result.is_ok());
+
+    let copy_path = bin_dir.join("echo_test.permissions.json");
+    assert!(copy_path.exists());
+    let copy = permissions_manifest::load(&copy_path).unwrap();
+    assert_eq!(copy.allow_net, Some(vec!["localhost".to_string()]));
+    assert_eq!(copy.allow_read, Some(vec![temp_dir.path().join("data")]));
+
+    let mut file_path = bin_dir.join("echo_test");
+    if cfg!(windows) {
+      file_path = file_path.with_extension("cmd");
+    }
+    let content = fs::read_to_string(file_path).unwrap();
+    assert!(content.contains(&format!("--permissions={}", copy_path.display())));
+  }
+
+  #[test]
+  fn install_with_env_file() {
+    let temp_dir = TempDir::new().expect("tempdir fail");
+    let bin_dir = temp_dir.path().join("bin");
+    let env_file_path = temp_dir.path().join(".env");
+    fs::write(&env_file_path, "PORT=8080\n").unwrap();
+
+    let result = install(
+      Flags {
+        env_file: Some(env_file_path.to_string_lossy().to_string()),
+        ..Flags::default()
+      },
+      "http://localhost:4545/cli/tests/cat.ts",
+      vec![],
+      Some("echo_test".to_string()),
+      Some(temp_dir.path().to_path_buf()),
+      true,
+    );
+    assert!(result.is_ok());
+
+    let copy_path = bin_dir.join("echo_test.env");
+    assert_eq!(fs::read_to_string(&copy_path).unwrap(), "PORT=8080\n");
+
+    let mut file_path = bin_dir.join("echo_test");
+    if cfg!(windows) {
+      file_path = file_path.with_extension("cmd");
+    }
+    let content = fs::read_to_string(file_path).unwrap();
+    assert!(content.contains(&format!("--env-file={}", copy_path.display())));
+  }
+
   // TODO: enable on Windows after fixing batch escaping
   #[cfg(not(windows))]
   #[test]
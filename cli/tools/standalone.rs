@@ -2,6 +2,7 @@
 
 use crate::flags::DenoSubcommand;
 use crate::flags::Flags;
+use crate::permissions_manifest;
 use deno_core::error::bail;
 use deno_core::error::AnyError;
 use deno_core::serde_json;
@@ -33,7 +34,7 @@ pub async fn create_standalone_binary(
     unstable: flags.unstable,
     seed: flags.seed,
     location: flags.location.clone(),
-    permissions: flags.clone().into(),
+    permissions: permissions_manifest::resolve(&flags)?,
     v8_flags: flags.v8_flags.clone(),
     log_level: flags.log_level,
     ca_data,
@@ -124,6 +125,7 @@ pub fn compile_to_runtime_flags(
     cached_only: false,
     config_path: None,
     coverage_dir: flags.coverage_dir,
+    env_file: None,
     ignore: vec![],
     import_map_path: None,
     inspect: None,
@@ -135,6 +137,8 @@ pub fn compile_to_runtime_flags(
     no_check: false,
     no_prompts: flags.no_prompts,
     no_remote: false,
+    // The resolved manifest is embedded as `Metadata::permissions`.
+    permissions_manifest: None,
     reload: false,
     repl: false,
     seed: flags.seed,
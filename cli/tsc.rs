@@ -32,6 +32,7 @@ pub static DENO_WEB_LIB: &str = include_str!(env!("DENO_WEB_LIB_PATH"));
 pub static DENO_FETCH_LIB: &str = include_str!(env!("DENO_FETCH_LIB_PATH"));
 pub static DENO_WEBSOCKET_LIB: &str =
   include_str!(env!("DENO_WEBSOCKET_LIB_PATH"));
+pub static DENO_CRYPTO_LIB: &str = include_str!(env!("DENO_CRYPTO_LIB_PATH"));
 pub static SHARED_GLOBALS_LIB: &str =
   include_str!("dts/lib.deno.shared_globals.d.ts");
 pub static WINDOW_LIB: &str = include_str!("dts/lib.deno.window.d.ts");
@@ -84,14 +85,19 @@ fn get_maybe_hash(
   }
 }
 
+/// Returns whether a specifier has no file name that tsc could take the media
+/// type from, so that it has to be remapped with `hash_data_url()`.
+fn is_data_url(specifier: &ModuleSpecifier) -> bool {
+  matches!(specifier.as_url().scheme(), "data" | "blob")
+}
+
 fn hash_data_url(
   specifier: &ModuleSpecifier,
   media_type: &MediaType,
 ) -> String {
-  assert_eq!(
-    specifier.as_url().scheme(),
-    "data",
-    "Specifier must be a data: specifier."
+  assert!(
+    is_data_url(specifier),
+    "Specifier must be a data: or blob: specifier."
   );
   let hash = crate::checksum::gen(&[specifier.as_url().path().as_bytes()]);
   format!("data:///{}{}", hash, media_type.as_ts_extension())
@@ -366,9 +372,7 @@ fn resolve(state: &mut State, args: Value) -> Result<Value, AnyError> {
               resolved_specifier
             )
           };
-          let resolved_specifier_str = if resolved_specifier.as_url().scheme()
-            == "data"
-          {
+          let resolved_specifier_str = if is_data_url(&resolved_specifier) {
             let specifier_str = hash_data_url(&resolved_specifier, &media_type);
             state
               .data_url_map
@@ -423,7 +427,7 @@ pub fn exec(request: Request) -> Result<Response, AnyError> {
     .root_names
     .iter()
     .map(|(s, mt)| {
-      if s.as_url().scheme() == "data" {
+      if is_data_url(s) {
         let specifier_str = hash_data_url(s, mt);
         data_url_map.insert(specifier_str.clone(), s.clone());
         specifier_str
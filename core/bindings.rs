@@ -1,6 +1,8 @@
 // Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
 
 use crate::error::AnyError;
+use crate::error::JsStackFrame;
+use crate::ops::with_op_callers;
 use crate::runtime::JsRuntimeState;
 use crate::JsRuntime;
 use crate::Op;
@@ -13,6 +15,7 @@ use std::cell::Cell;
 use std::convert::TryFrom;
 use std::io::{stdout, Write};
 use std::option::Option;
+use std::rc::Rc;
 use url::Url;
 use v8::MapFnTo;
 
@@ -422,7 +425,15 @@ fn send<'s>(
     }
   };
 
-  let op = OpTable::route_op(op_id, state.op_state.clone(), bufs);
+  let op = if state.capture_op_callers {
+    let callers = Rc::new(capture_op_callers(scope));
+    with_op_callers(&callers, || {
+      OpTable::route_op(op_id, state.op_state.clone(), bufs)
+    })
+    .with_callers(callers)
+  } else {
+    OpTable::route_op(op_id, state.op_state.clone(), bufs)
+  };
   assert_eq!(state.shared.size(), 0);
   match op {
     Op::Sync(buf) if !buf.is_empty() => {
@@ -448,6 +459,23 @@ fn send<'s>(
   }
 }
 
+/// Maximum number of frames looked at by `capture_op_callers()`.
+const OP_CALLERS_FRAME_LIMIT: usize = 64;
+
+/// Returns the frames on the JS stack that don't belong to the runtime's own
+/// `deno:` scripts.
+fn capture_op_callers(scope: &mut v8::HandleScope) -> Vec<JsStackFrame> {
+  JsStackFrame::capture_current(scope, OP_CALLERS_FRAME_LIMIT)
+    .into_iter()
+    .filter(|frame| match &frame.file_name {
+      Some(file_name) => {
+        !file_name.is_empty() && !file_name.starts_with("deno:")
+      }
+      None => false,
+    })
+    .collect()
+}
+
 fn set_macrotask_callback(
   scope: &mut v8::HandleScope,
   args: v8::FunctionCallbackArguments,
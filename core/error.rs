@@ -139,6 +139,36 @@ impl JsStackFrame {
       promise_index: None,
     }
   }
+
+  /// Captures the frames currently on the JS stack, innermost first. Unlike
+  /// `JsError::from_v8_exception()` this doesn't need an exception to be
+  /// thrown, but only the location of each frame is filled in.
+  pub fn capture_current(
+    scope: &mut v8::HandleScope,
+    frame_limit: usize,
+  ) -> Vec<Self> {
+    let scope = &mut v8::HandleScope::new(scope);
+    let stack = match v8::StackTrace::current_stack_trace(scope, frame_limit) {
+      Some(stack) => stack,
+      None => return vec![],
+    };
+    let mut frames = vec![];
+    for i in 0..stack.get_frame_count() {
+      let frame = match stack.get_frame(scope, i) {
+        Some(frame) => frame,
+        None => continue,
+      };
+      let file_name = frame
+        .get_script_name(scope)
+        .map(|s| s.to_rust_string_lossy(scope));
+      frames.push(Self::from_location(
+        file_name,
+        Some(frame.get_line_number() as i64),
+        Some(frame.get_column() as i64),
+      ));
+    }
+    frames
+  }
 }
 
 fn get_property<'a>(
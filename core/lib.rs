@@ -55,6 +55,7 @@ pub use crate::modules::RecursiveModuleLoad;
 pub use crate::normalize_path::normalize_path;
 pub use crate::ops::json_op_async;
 pub use crate::ops::json_op_sync;
+pub use crate::ops::op_callers;
 pub use crate::ops::op_close;
 pub use crate::ops::op_resources;
 pub use crate::ops::Op;
@@ -3,12 +3,15 @@
 use crate::error::bad_resource_id;
 use crate::error::type_error;
 use crate::error::AnyError;
+use crate::error::JsStackFrame;
 use crate::gotham_state::GothamState;
 use crate::resources::ResourceTable;
 use crate::runtime::GetErrorClassFn;
 use crate::BufVec;
 use crate::ZeroCopyBuf;
+use futures::future::poll_fn;
 use futures::Future;
+use futures::FutureExt;
 use indexmap::IndexMap;
 use serde_json::json;
 use serde_json::Value;
@@ -33,6 +36,51 @@ pub enum Op {
   NotFound,
 }
 
+impl Op {
+  /// Makes `op_callers()` return `callers` while the op's future is polled.
+  pub(crate) fn with_callers(self, callers: Rc<Vec<JsStackFrame>>) -> Self {
+    let wrap = |mut fut: OpAsyncFuture| -> OpAsyncFuture {
+      Box::pin(poll_fn(move |cx| {
+        with_op_callers(&callers, || fut.poll_unpin(cx))
+      }))
+    };
+    match self {
+      Op::Async(fut) => Op::Async(wrap(fut)),
+      Op::AsyncUnref(fut) => Op::AsyncUnref(wrap(fut)),
+      op => op,
+    }
+  }
+}
+
+thread_local! {
+  static OP_CALLERS: RefCell<Option<Rc<Vec<JsStackFrame>>>> =
+    Default::default();
+}
+
+/// Returns the frames of user code that were on the JS stack when the op
+/// that is currently running was called, innermost first. Frames of the
+/// runtime's own `deno:` scripts are left out, so they are empty when the
+/// op was called by the runtime on its own, e.g. by `setTimeout()` calling
+/// an op's JS binding directly.
+///
+/// This is `None` outside of ops, and unless the runtime was created with
+/// `RuntimeOptions::capture_op_callers` set.
+pub fn op_callers() -> Option<Rc<Vec<JsStackFrame>>> {
+  OP_CALLERS.with(|op_callers| op_callers.borrow().clone())
+}
+
+/// Runs `f` with `op_callers()` returning `callers`.
+pub(crate) fn with_op_callers<R>(
+  callers: &Rc<Vec<JsStackFrame>>,
+  f: impl FnOnce() -> R,
+) -> R {
+  let prev =
+    OP_CALLERS.with(|op_callers| op_callers.replace(Some(callers.clone())));
+  let result = f();
+  OP_CALLERS.with(|op_callers| op_callers.replace(prev));
+  result
+}
+
 /// Maintains the resources and ops inside a JS runtime.
 pub struct OpState {
   pub resource_table: ResourceTable,
@@ -114,6 +162,30 @@ impl Default for OpTable {
   }
 }
 
+#[test]
+fn op_with_callers() {
+  let callers = Rc::new(vec![JsStackFrame::from_location(
+    Some("file:///a.js".to_string()),
+    Some(1),
+    Some(1),
+  )]);
+  let expected = callers.clone();
+  let op = Op::Async(
+    futures::future::lazy(move |_| {
+      assert_eq!(op_callers(), Some(expected));
+      Vec::new().into_boxed_slice()
+    })
+    .boxed_local(),
+  )
+  .with_callers(callers);
+  assert!(op_callers().is_none());
+  match op {
+    Op::Async(fut) => futures::executor::block_on(fut),
+    _ => unreachable!(),
+  };
+  assert!(op_callers().is_none());
+}
+
 #[test]
 fn op_table() {
   let state = Rc::new(RefCell::new(OpState::new()));
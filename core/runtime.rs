@@ -113,6 +113,7 @@ pub(crate) struct JsRuntimeState {
   pub(crate) have_unpolled_ops: Cell<bool>,
   //pub(crate) op_table: OpTable,
   pub(crate) op_state: Rc<RefCell<OpState>>,
+  pub(crate) capture_op_callers: bool,
   pub loader: Rc<dyn ModuleLoader>,
   pub modules: Modules,
   pub(crate) dyn_import_map:
@@ -193,6 +194,12 @@ pub struct RuntimeOptions {
 
   /// Isolate creation parameters.
   pub create_params: Option<v8::CreateParams>,
+
+  /// Record the JS stack every time an op is called, so that ops can find
+  /// out which code called them with `op_callers()`.
+  ///
+  /// Off by default since walking the stack slows down every op call.
+  pub capture_op_callers: bool,
 }
 
 impl JsRuntime {
@@ -279,6 +286,7 @@ impl JsRuntime {
       pending_ops: FuturesUnordered::new(),
       pending_unref_ops: FuturesUnordered::new(),
       op_state: Rc::new(RefCell::new(op_state)),
+      capture_op_callers: options.capture_op_callers,
       have_unpolled_ops: Cell::new(false),
       modules: Modules::new(),
       loader,
@@ -1985,6 +1993,30 @@ pub mod tests {
     });
   }
 
+  #[test]
+  fn test_capture_op_callers() {
+    let mut runtime = JsRuntime::new(RuntimeOptions {
+      capture_op_callers: true,
+      ..Default::default()
+    });
+    let file_names = Rc::new(RefCell::new(vec![]));
+    let file_names_ = file_names.clone();
+    runtime.register_op("op_callers", move |_state, _bufs| {
+      *file_names_.borrow_mut() = op_callers()
+        .unwrap()
+        .iter()
+        .map(|frame| frame.file_name.clone().unwrap())
+        .collect::<Vec<_>>();
+      Op::Sync(Box::new([]))
+    });
+    runtime
+      .execute("a.js", "function a() { Deno.core.dispatch('op_callers'); }")
+      .unwrap();
+    runtime.execute("b.js", "Deno.core.ops(); a();").unwrap();
+    assert_eq!(*file_names.borrow(), vec!["a.js", "b.js"]);
+    assert!(op_callers().is_none());
+  }
+
   #[test]
   fn core_test_js() {
     run_in_task(|mut cx| {
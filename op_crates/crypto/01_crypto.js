@@ -40,11 +40,789 @@
     return arrayBufferView;
   }
 
-  window.crypto = {
+  const illegalConstructorKey = Symbol("illegalConstructorKey");
+
+  // Output lengths in bytes of the supported digest algorithms.
+  const digestLengths = {
+    "SHA-1": 20,
+    "SHA-256": 32,
+    "SHA-384": 48,
+    "SHA-512": 64,
+  };
+
+  /**
+   * Returns the registered name of `algorithm` (a string or an object with a
+   * `name`), matched case-insensitively against `supportedNames`.
+   */
+  function normalizeAlgorithm(algorithm, supportedNames) {
+    const name = typeof algorithm === "object" && algorithm !== null
+      ? algorithm.name
+      : algorithm;
+    if (typeof name !== "string") {
+      throw new TypeError("Algorithm name is missing");
+    }
+    const upperName = name.toUpperCase();
+    for (const supportedName of supportedNames) {
+      if (supportedName.toUpperCase() === upperName) {
+        return supportedName;
+      }
+    }
+    throw new DOMException(
+      `Unrecognized algorithm name "${name}"`,
+      "NotSupportedError",
+    );
+  }
+
+  /** Returns a Uint8Array over the bytes of an ArrayBuffer or a view. */
+  function bufferSourceBytes(data, argument) {
+    if (ArrayBuffer.isView(data)) {
+      return new Uint8Array(data.buffer, data.byteOffset, data.byteLength);
+    }
+    if (data instanceof ArrayBuffer) {
+      return new Uint8Array(data);
+    }
+    throw new TypeError(
+      `Argument ${argument} is not an ArrayBuffer or ArrayBufferView`,
+    );
+  }
+
+  // Block sizes in bits of the digest algorithms, the default length of HMAC
+  // keys.
+  const hmacDefaultLengths = {
+    "SHA-1": 512,
+    "SHA-256": 512,
+    "SHA-384": 1024,
+    "SHA-512": 1024,
+  };
+
+  // Lengths in bytes of ECDSA signatures, which hold r and s of the size of
+  // the curve's order.
+  const ecdsaSignatureLengths = {
+    "P-256": 64,
+    "P-384": 96,
+  };
+
+  // The usages a key of each type can have, by algorithm.
+  const supportedUsages = {
+    "HMAC": { secret: ["sign", "verify"] },
+    "ECDSA": { private: ["sign"], public: ["verify"] },
+    "RSASSA-PKCS1-v1_5": { private: ["sign"], public: ["verify"] },
+    "RSA-PSS": { private: ["sign"], public: ["verify"] },
+    "AES-GCM": { secret: ["encrypt", "decrypt"] },
+    "AES-CBC": { secret: ["encrypt", "decrypt"] },
+    "AES-CTR": { secret: ["encrypt", "decrypt"] },
+    "RSA-OAEP": { private: ["decrypt"], public: ["encrypt"] },
+    "ECDH": { private: ["deriveKey", "deriveBits"], public: [] },
+    "PBKDF2": { secret: ["deriveKey", "deriveBits"] },
+    "HKDF": { secret: ["deriveKey", "deriveBits"] },
+  };
+
+  // PBKDF2 and HKDF keys are passwords and key material that can only be
+  // imported.
+  const generateKeyAlgorithms = Object.keys(supportedUsages).filter(
+    (name) => name !== "PBKDF2" && name !== "HKDF",
+  );
+  const encryptAlgorithms = ["AES-GCM", "AES-CBC", "AES-CTR", "RSA-OAEP"];
+  const deriveAlgorithms = ["PBKDF2", "HKDF", "ECDH"];
+  const aesLengths = [128, 192, 256];
+
+  // The kind of key material of each algorithm, as imported by Rust.
+  function keyKind(name) {
+    switch (name) {
+      case "ECDSA":
+      case "ECDH":
+        return "ec";
+      case "RSASSA-PKCS1-v1_5":
+      case "RSA-PSS":
+      case "RSA-OAEP":
+        return "rsa";
+      default:
+        return "secret";
+    }
+  }
+
+  // The JWK "alg" of a key algorithm, if it has one.
+  function jwkAlg(algorithm) {
+    const hashSuffix = algorithm.hash?.name.slice(4);
+    switch (algorithm.name) {
+      case "HMAC":
+        return `HS${hashSuffix}`;
+      case "RSASSA-PKCS1-v1_5":
+        return `RS${hashSuffix}`;
+      case "RSA-PSS":
+        return `PS${hashSuffix}`;
+      case "RSA-OAEP":
+        return hashSuffix === "1" ? "RSA-OAEP" : `RSA-OAEP-${hashSuffix}`;
+      case "AES-GCM":
+      case "AES-CBC":
+      case "AES-CTR":
+        return `A${algorithm.length}${algorithm.name.slice(4)}`;
+    }
+  }
+
+  function normalizeHash(hash) {
+    if (hash === undefined) {
+      throw new TypeError("Algorithm hash is missing");
+    }
+    return normalizeAlgorithm(hash, Object.keys(digestLengths));
+  }
+
+  // Drops the key material in Rust once a CryptoKey is garbage collected.
+  const keyRegistry = new FinalizationRegistry((id) => {
+    core.jsonOpSync("op_crypto_drop_key", { key: id });
+  });
+
+  const _type = Symbol("[[type]]");
+  const _extractable = Symbol("[[extractable]]");
+  const _algorithm = Symbol("[[algorithm]]");
+  const _usages = Symbol("[[usages]]");
+  const _id = Symbol("[[id]]");
+  const _algorithmView = Symbol("algorithmView");
+  const _usagesView = Symbol("usagesView");
+
+  /** Returns a frozen copy of a key algorithm, which can be handed out
+   * without the key's own algorithm being changed through it. */
+  function frozenAlgorithm(algorithm) {
+    const copy = { ...algorithm };
+    if (copy.hash !== undefined) {
+      copy.hash = Object.freeze({ ...copy.hash });
+    }
+    if (copy.publicExponent !== undefined) {
+      copy.publicExponent = new Uint8Array(copy.publicExponent);
+    }
+    return Object.freeze(copy);
+  }
+
+  class CryptoKey {
+    constructor(key, type, extractable, algorithm, usages, id) {
+      if (key !== illegalConstructorKey) {
+        throw new TypeError("Illegal constructor.");
+      }
+      this[_type] = type;
+      this[_extractable] = extractable;
+      this[_algorithm] = frozenAlgorithm(algorithm);
+      this[_usages] = Object.freeze([...usages]);
+      this[_algorithmView] = frozenAlgorithm(algorithm);
+      this[_usagesView] = Object.freeze([...usages]);
+      this[_id] = id;
+      keyRegistry.register(this, id);
+    }
+
+    get type() {
+      return this[_type];
+    }
+
+    get extractable() {
+      return this[_extractable];
+    }
+
+    get algorithm() {
+      return this[_algorithmView];
+    }
+
+    get usages() {
+      return this[_usagesView];
+    }
+
+    get [Symbol.toStringTag]() {
+      return "CryptoKey";
+    }
+  }
+
+  function checkUsages(usages, allowed) {
+    for (const usage of usages) {
+      if (!allowed.includes(usage)) {
+        throw new DOMException(`Invalid key usage "${usage}"`, "SyntaxError");
+      }
+    }
+  }
+
+  /** Returns the key's algorithm, or throws if it can't be used for `name`
+   * and `usage`. */
+  function checkKey(key, name, usage) {
+    if (!(key instanceof CryptoKey)) {
+      throw new TypeError("Key is not a CryptoKey");
+    }
+    const algorithm = key[_algorithm];
+    if (algorithm.name !== name) {
+      throw new DOMException(
+        `Key algorithm "${algorithm.name}" doesn't match "${name}"`,
+        "InvalidAccessError",
+      );
+    }
+    if (!key[_usages].includes(usage)) {
+      throw new DOMException(
+        `Key usages don't include "${usage}"`,
+        "InvalidAccessError",
+      );
+    }
+    return algorithm;
+  }
+
+  /** Returns the arguments of the sign and verify ops and the signature
+   * length for `algorithm`. */
+  function signatureParams(algorithm, name, key, usage) {
+    const keyAlgorithm = checkKey(key, name, usage);
+    switch (name) {
+      case "HMAC":
+        return {
+          args: { key: key[_id], name, hash: keyAlgorithm.hash.name },
+          length: digestLengths[keyAlgorithm.hash.name],
+        };
+      case "ECDSA":
+        return {
+          args: { key: key[_id], name, hash: normalizeHash(algorithm.hash) },
+          length: ecdsaSignatureLengths[keyAlgorithm.namedCurve],
+        };
+      case "RSASSA-PKCS1-v1_5":
+      case "RSA-PSS": {
+        const args = { key: key[_id], name, hash: keyAlgorithm.hash.name };
+        if (name === "RSA-PSS") {
+          if (typeof algorithm.saltLength !== "number") {
+            throw new TypeError("Algorithm saltLength is missing");
+          }
+          args.saltLength = algorithm.saltLength;
+        }
+        return { args, length: Math.ceil(keyAlgorithm.modulusLength / 8) };
+      }
+    }
+  }
+
+  function checkNamedCurve(namedCurve) {
+    if (!(namedCurve in ecdsaSignatureLengths)) {
+      throw new DOMException(
+        `Unsupported named curve "${namedCurve}"`,
+        "NotSupportedError",
+      );
+    }
+    return namedCurve;
+  }
+
+  /** Returns the arguments and buffers of the encrypt and decrypt ops for
+   * `algorithm`, along with the largest possible output length. */
+  function encryptParams(algorithm, name, key, usage, input) {
+    const keyAlgorithm = checkKey(key, name, usage);
+    const args = { key: key[_id], name };
+    const empty = new Uint8Array();
+    const encrypt = usage === "encrypt";
+    switch (name) {
+      case "AES-GCM": {
+        args.tagLength = algorithm.tagLength;
+        const additionalData = algorithm.additionalData === undefined
+          ? empty
+          : bufferSourceBytes(algorithm.additionalData, "additionalData");
+        return {
+          args,
+          buffers: [bufferSourceBytes(algorithm.iv, "iv"), additionalData],
+          length: encrypt
+            ? input.length + 16
+            : Math.max(input.length - 16, 0),
+        };
+      }
+      case "AES-CBC":
+        return {
+          args,
+          buffers: [bufferSourceBytes(algorithm.iv, "iv"), empty],
+          length: encrypt
+            ? input.length - input.length % 16 + 16
+            : input.length,
+        };
+      case "AES-CTR":
+        if (typeof algorithm.length !== "number") {
+          throw new TypeError("Algorithm length is missing");
+        }
+        args.length = algorithm.length;
+        return {
+          args,
+          buffers: [bufferSourceBytes(algorithm.counter, "counter"), empty],
+          length: input.length,
+        };
+      case "RSA-OAEP": {
+        args.hash = keyAlgorithm.hash.name;
+        const label = algorithm.label === undefined
+          ? empty
+          : bufferSourceBytes(algorithm.label, "label");
+        return {
+          args,
+          buffers: [label, empty],
+          length: Math.ceil(keyAlgorithm.modulusLength / 8),
+        };
+      }
+    }
+  }
+
+  async function crypt(algorithm, key, data, usage) {
+    const name = normalizeAlgorithm(algorithm, encryptAlgorithms);
+    const input = bufferSourceBytes(data, 3);
+    const { args, buffers, length } = encryptParams(
+      algorithm,
+      name,
+      key,
+      usage,
+      input,
+    );
+    const output = new Uint8Array(length);
+    const result = await core.jsonOpAsync(
+      `op_crypto_${usage}`,
+      args,
+      input,
+      output,
+      ...buffers,
+    );
+    return output.slice(0, result.length).buffer;
+  }
+
+  async function deriveBits(algorithm, baseKey, length, usage) {
+    const name = normalizeAlgorithm(algorithm, deriveAlgorithms);
+    const keyAlgorithm = checkKey(baseKey, name, usage);
+    if (name === "ECDH" && length === null) {
+      length = ecdsaSignatureLengths[keyAlgorithm.namedCurve] * 4;
+    }
+    if (typeof length !== "number" || length % 8 !== 0) {
+      throw new DOMException(
+        "Length must be a multiple of 8",
+        "OperationError",
+      );
+    }
+    const args = { key: baseKey[_id], name };
+    const buffers = [];
+    switch (name) {
+      case "PBKDF2":
+        args.hash = normalizeHash(algorithm.hash);
+        args.iterations = algorithm.iterations;
+        buffers.push(bufferSourceBytes(algorithm.salt, "salt"));
+        break;
+      case "HKDF":
+        args.hash = normalizeHash(algorithm.hash);
+        buffers.push(
+          bufferSourceBytes(algorithm.salt, "salt"),
+          bufferSourceBytes(algorithm.info, "info"),
+        );
+        break;
+      case "ECDH": {
+        const publicKey = algorithm.public;
+        if (!(publicKey instanceof CryptoKey)) {
+          throw new TypeError("Algorithm public is not a CryptoKey");
+        }
+        if (
+          publicKey[_type] !== "public" ||
+          publicKey[_algorithm].name !== "ECDH"
+        ) {
+          throw new DOMException(
+            "Algorithm public must be an ECDH public key",
+            "InvalidAccessError",
+          );
+        }
+        args.publicKey = publicKey[_id];
+        break;
+      }
+    }
+    const output = new Uint8Array(length / 8);
+    await core.jsonOpAsync("op_crypto_derive_bits", args, ...buffers, output);
+    return output.buffer;
+  }
+
+  /** Returns the type of the key that importing `keyData` creates. */
+  function importedKeyType(format, keyData, name) {
+    switch (format) {
+      case "raw":
+        return keyKind(name) === "secret" ? "secret" : "public";
+      case "pkcs8":
+        return "private";
+      case "spki":
+        return "public";
+      case "jwk":
+        if (keyData.kty === "oct") {
+          return "secret";
+        }
+        return keyData.d === undefined ? "public" : "private";
+      default:
+        throw new TypeError(`Unsupported key format "${format}"`);
+    }
+  }
+
+  /** Throws if the members of a JWK other than the key material don't allow
+   * importing it with `algorithm`, `extractable` and `usages`. */
+  function checkJwk(jwk, algorithm, extractable, usages) {
+    if (jwk.ext === false && extractable) {
+      throw new DOMException("JWK is not extractable", "DataError");
+    }
+    if (jwk.key_ops !== undefined) {
+      for (const usage of usages) {
+        if (!jwk.key_ops.includes(usage)) {
+          throw new DOMException(
+            `JWK key_ops don't include "${usage}"`,
+            "DataError",
+          );
+        }
+      }
+    }
+    const alg = jwkAlg(algorithm);
+    if (jwk.alg !== undefined && alg !== undefined && jwk.alg !== alg) {
+      throw new DOMException(
+        `JWK alg "${jwk.alg}" doesn't match "${alg}"`,
+        "DataError",
+      );
+    }
+  }
+
+  // Throws a DataError and drops an imported key that turned out invalid.
+  function rejectImportedKey(id, message) {
+    core.jsonOpSync("op_crypto_drop_key", { key: id });
+    throw new DOMException(message, "DataError");
+  }
+
+  class SubtleCrypto {
+    constructor(key) {
+      if (key !== illegalConstructorKey) {
+        throw new TypeError("Illegal constructor.");
+      }
+    }
+
+    async digest(algorithm, data) {
+      const name = normalizeAlgorithm(algorithm, Object.keys(digestLengths));
+      const input = bufferSourceBytes(data, 2);
+      const output = new Uint8Array(digestLengths[name]);
+      await core.jsonOpAsync(
+        "op_crypto_subtle_digest",
+        { algorithm: name },
+        input,
+        output,
+      );
+      return output.buffer;
+    }
+
+    async generateKey(algorithm, extractable, keyUsages) {
+      const name = normalizeAlgorithm(algorithm, generateKeyAlgorithms);
+      const usages = supportedUsages[name];
+      keyUsages = Array.from(keyUsages);
+      checkUsages(keyUsages, [].concat(...Object.values(usages)));
+
+      let keyAlgorithm;
+      const args = { name };
+      switch (name) {
+        case "HMAC": {
+          const hash = normalizeHash(algorithm.hash);
+          const length = algorithm.length ?? hmacDefaultLengths[hash];
+          keyAlgorithm = { name, hash: { name: hash }, length };
+          args.length = length;
+          break;
+        }
+        case "AES-GCM":
+        case "AES-CBC":
+        case "AES-CTR": {
+          keyAlgorithm = { name, length: algorithm.length };
+          args.length = algorithm.length;
+          break;
+        }
+        case "ECDSA":
+        case "ECDH": {
+          const namedCurve = checkNamedCurve(algorithm.namedCurve);
+          keyAlgorithm = { name, namedCurve };
+          args.namedCurve = namedCurve;
+          break;
+        }
+        case "RSASSA-PKCS1-v1_5":
+        case "RSA-PSS":
+        case "RSA-OAEP": {
+          const hash = normalizeHash(algorithm.hash);
+          const modulusLength = algorithm.modulusLength;
+          if (typeof modulusLength !== "number") {
+            throw new TypeError("Algorithm modulusLength is missing");
+          }
+          const publicExponent = bufferSourceBytes(
+            algorithm.publicExponent,
+            "publicExponent",
+          );
+          // Only the usual exponent 65537 is supported.
+          const exponent = publicExponent.slice(
+            publicExponent.findIndex((byte) => byte !== 0),
+          );
+          if (
+            exponent.length !== 3 || exponent[0] !== 1 || exponent[1] !== 0 ||
+            exponent[2] !== 1
+          ) {
+            throw new DOMException(
+              "Only the public exponent 65537 is supported",
+              "NotSupportedError",
+            );
+          }
+          keyAlgorithm = {
+            name,
+            modulusLength,
+            publicExponent: new Uint8Array(publicExponent),
+            hash: { name: hash },
+          };
+          args.modulusLength = modulusLength;
+          break;
+        }
+      }
+
+      // The usages are checked before generating the key, which would
+      // otherwise be stored without a CryptoKey to drop it.
+      if (usages.secret) {
+        if (keyUsages.length === 0) {
+          throw new DOMException("Key usages can't be empty", "SyntaxError");
+        }
+        const ids = await core.jsonOpAsync("op_crypto_generate_key", args);
+        return new CryptoKey(
+          illegalConstructorKey,
+          "secret",
+          extractable,
+          keyAlgorithm,
+          keyUsages,
+          ids.secretKey,
+        );
+      }
+      const privateUsages = keyUsages.filter((usage) =>
+        usages.private.includes(usage)
+      );
+      const publicUsages = keyUsages.filter((usage) =>
+        usages.public.includes(usage)
+      );
+      if (privateUsages.length === 0) {
+        throw new DOMException(
+          "Private key usages can't be empty",
+          "SyntaxError",
+        );
+      }
+      const ids = await core.jsonOpAsync("op_crypto_generate_key", args);
+      return {
+        privateKey: new CryptoKey(
+          illegalConstructorKey,
+          "private",
+          extractable,
+          keyAlgorithm,
+          privateUsages,
+          ids.privateKey,
+        ),
+        // Public keys are always extractable.
+        publicKey: new CryptoKey(
+          illegalConstructorKey,
+          "public",
+          true,
+          keyAlgorithm,
+          publicUsages,
+          ids.publicKey,
+        ),
+      };
+    }
+
+    async sign(algorithm, key, data) {
+      const name = normalizeAlgorithm(
+        algorithm,
+        Object.keys(supportedUsages),
+      );
+      const input = bufferSourceBytes(data, 3);
+      const { args, length } = signatureParams(algorithm, name, key, "sign");
+      const output = new Uint8Array(length);
+      await core.jsonOpAsync("op_crypto_sign", args, input, output);
+      return output.buffer;
+    }
+
+    async verify(algorithm, key, signature, data) {
+      const name = normalizeAlgorithm(
+        algorithm,
+        Object.keys(supportedUsages),
+      );
+      const signatureBytes = bufferSourceBytes(signature, 3);
+      const input = bufferSourceBytes(data, 4);
+      const { args } = signatureParams(algorithm, name, key, "verify");
+      const { valid } = await core.jsonOpAsync(
+        "op_crypto_verify",
+        args,
+        input,
+        signatureBytes,
+      );
+      return valid;
+    }
+
+    encrypt(algorithm, key, data) {
+      return crypt(algorithm, key, data, "encrypt");
+    }
+
+    decrypt(algorithm, key, data) {
+      return crypt(algorithm, key, data, "decrypt");
+    }
+
+    async importKey(format, keyData, algorithm, extractable, keyUsages) {
+      const name = normalizeAlgorithm(
+        algorithm,
+        Object.keys(supportedUsages),
+      );
+      keyUsages = Array.from(keyUsages);
+      const type = importedKeyType(format, keyData, name);
+      const allowed = supportedUsages[name][type];
+      if (allowed === undefined) {
+        throw new DOMException(
+          `Can't import a ${type} ${name} key`,
+          "DataError",
+        );
+      }
+      checkUsages(keyUsages, allowed);
+      if (type !== "public" && keyUsages.length === 0) {
+        throw new DOMException("Key usages can't be empty", "SyntaxError");
+      }
+      if (name === "PBKDF2" || name === "HKDF") {
+        if (format !== "raw") {
+          throw new DOMException(
+            `${name} keys can only be imported in the raw format`,
+            "NotSupportedError",
+          );
+        }
+        if (extractable) {
+          throw new DOMException(
+            `${name} keys can't be extractable`,
+            "SyntaxError",
+          );
+        }
+      }
+
+      const args = { format, kind: keyKind(name) };
+      let keyAlgorithm;
+      switch (args.kind) {
+        case "secret":
+          keyAlgorithm = { name };
+          break;
+        case "ec":
+          args.namedCurve = checkNamedCurve(algorithm.namedCurve);
+          keyAlgorithm = { name, namedCurve: args.namedCurve };
+          break;
+        case "rsa":
+          keyAlgorithm = {
+            name,
+            hash: { name: normalizeHash(algorithm.hash) },
+          };
+          break;
+      }
+      if (name === "HMAC") {
+        keyAlgorithm.hash = { name: normalizeHash(algorithm.hash) };
+      }
+      let data;
+      if (format === "jwk") {
+        args.jwk = keyData;
+        data = new Uint8Array();
+      } else {
+        data = bufferSourceBytes(keyData, 2);
+      }
+
+      const info = core.jsonOpSync("op_crypto_import_key", args, data);
+      switch (name) {
+        case "HMAC":
+          if (
+            algorithm.length !== undefined && algorithm.length !== info.length
+          ) {
+            rejectImportedKey(info.key, "Key length doesn't match");
+          }
+          keyAlgorithm.length = info.length;
+          break;
+        case "AES-GCM":
+        case "AES-CBC":
+        case "AES-CTR":
+          if (!aesLengths.includes(info.length)) {
+            rejectImportedKey(
+              info.key,
+              "AES key length must be 128, 192 or 256 bits",
+            );
+          }
+          keyAlgorithm.length = info.length;
+          break;
+        case "RSASSA-PKCS1-v1_5":
+        case "RSA-PSS":
+        case "RSA-OAEP":
+          keyAlgorithm.modulusLength = info.modulusLength;
+          keyAlgorithm.publicExponent = new Uint8Array(info.publicExponent);
+          break;
+      }
+      if (format === "jwk") {
+        try {
+          checkJwk(keyData, keyAlgorithm, extractable, keyUsages);
+        } catch (err) {
+          core.jsonOpSync("op_crypto_drop_key", { key: info.key });
+          throw err;
+        }
+      }
+      return new CryptoKey(
+        illegalConstructorKey,
+        type,
+        // Public keys are always extractable.
+        type === "public" ? true : extractable,
+        keyAlgorithm,
+        keyUsages,
+        info.key,
+      );
+    }
+
+    async exportKey(format, key) {
+      if (!(key instanceof CryptoKey)) {
+        throw new TypeError("Key is not a CryptoKey");
+      }
+      if (!key[_extractable]) {
+        throw new DOMException("Key is not extractable", "InvalidAccessError");
+      }
+      const result = core.jsonOpSync("op_crypto_export_key", {
+        key: key[_id],
+        format,
+      });
+      if (result.data) {
+        return new Uint8Array(result.data).buffer;
+      }
+      const jwk = result.jwk;
+      const alg = jwkAlg(key[_algorithm]);
+      if (alg !== undefined) {
+        jwk.alg = alg;
+      }
+      jwk.key_ops = [...key[_usages]];
+      jwk.ext = true;
+      return jwk;
+    }
+
+    deriveBits(algorithm, baseKey, length) {
+      return deriveBits(algorithm, baseKey, length, "deriveBits");
+    }
+
+    async deriveKey(
+      algorithm,
+      baseKey,
+      derivedKeyType,
+      extractable,
+      keyUsages,
+    ) {
+      const derivedName = normalizeAlgorithm(
+        derivedKeyType,
+        ["HMAC", "AES-GCM", "AES-CBC", "AES-CTR"],
+      );
+      let length = derivedKeyType.length;
+      if (derivedName === "HMAC" && length === undefined) {
+        length = hmacDefaultLengths[normalizeHash(derivedKeyType.hash)];
+      }
+      const bits = await deriveBits(algorithm, baseKey, length, "deriveKey");
+      return this.importKey(
+        "raw",
+        bits,
+        derivedKeyType,
+        extractable,
+        keyUsages,
+      );
+    }
+
+    get [Symbol.toStringTag]() {
+      return "SubtleCrypto";
+    }
+  }
+
+  const subtle = new SubtleCrypto(illegalConstructorKey);
+
+  const crypto = {
     getRandomValues,
+    subtle,
   };
+
+  window.crypto = crypto;
   window.__bootstrap = window.__bootstrap || {};
   window.__bootstrap.crypto = {
-    getRandomValues,
+    crypto,
+    CryptoKey,
+    SubtleCrypto,
   };
 })(this);
@@ -0,0 +1,367 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+//! Encryption and decryption with AES-GCM, AES-CBC, AES-CTR and RSA-OAEP.
+
+use crate::key::not_supported_error;
+use crate::key::operation_error;
+use crate::key::HashAlgorithm;
+use crate::key::KeyMaterial;
+use aes::cipher::generic_array::typenum::U12;
+use aes::cipher::generic_array::typenum::U16;
+use aes::cipher::generic_array::GenericArray;
+use aes::Aes128;
+use aes::Aes192;
+use aes::Aes256;
+use aes::BlockCipher;
+use aes::NewBlockCipher;
+use aes_gcm::aead::Aead;
+use aes_gcm::aead::NewAead;
+use aes_gcm::aead::Payload;
+use aes_gcm::AesGcm;
+use block_modes::block_padding::Pkcs7;
+use block_modes::BlockMode;
+use block_modes::Cbc;
+use deno_core::error::type_error;
+use deno_core::error::AnyError;
+use rand::rngs::OsRng;
+use rsa::PublicKey;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncryptArgs {
+  pub key: u32,
+  pub name: String,
+  /// The hash of RSA-OAEP keys.
+  pub hash: Option<HashAlgorithm>,
+  /// The number of bits of an AES-CTR counter block that are incremented.
+  pub length: Option<u32>,
+  /// The length in bits of AES-GCM authentication tags.
+  pub tag_length: Option<usize>,
+}
+
+/// The buffers that go along with the data to encrypt or decrypt.
+pub struct EncryptParams {
+  /// The AES-GCM and AES-CBC iv, the AES-CTR counter block or the RSA-OAEP
+  /// label.
+  pub iv: Vec<u8>,
+  /// The AES-GCM additional data.
+  pub additional_data: Vec<u8>,
+}
+
+fn aes_key_length_error() -> AnyError {
+  operation_error("AES key length must be 128, 192 or 256 bits")
+}
+
+fn gcm<A: NewAead + Aead>(
+  key: &[u8],
+  params: &EncryptParams,
+  data: &[u8],
+  encrypt: bool,
+) -> Result<Vec<u8>, AnyError> {
+  let cipher = A::new(GenericArray::from_slice(key));
+  let nonce = GenericArray::from_slice(&params.iv);
+  let payload = Payload {
+    msg: data,
+    aad: &params.additional_data,
+  };
+  if encrypt {
+    cipher
+      .encrypt(nonce, payload)
+      .map_err(|_| operation_error("Encryption failed"))
+  } else {
+    cipher
+      .decrypt(nonce, payload)
+      .map_err(|_| operation_error("Decryption failed"))
+  }
+}
+
+fn aes_gcm(
+  key: &[u8],
+  args: &EncryptArgs,
+  params: &EncryptParams,
+  data: &[u8],
+  encrypt: bool,
+) -> Result<Vec<u8>, AnyError> {
+  if args.tag_length.unwrap_or(128) != 128 {
+    return Err(not_supported_error(
+      "Only 128 bit AES-GCM tags are supported",
+    ));
+  }
+  if params.iv.len() != 12 {
+    return Err(not_supported_error("Only 96 bit AES-GCM ivs are supported"));
+  }
+  match key.len() {
+    16 => gcm::<AesGcm<Aes128, U12>>(key, params, data, encrypt),
+    24 => gcm::<AesGcm<Aes192, U12>>(key, params, data, encrypt),
+    32 => gcm::<AesGcm<Aes256, U12>>(key, params, data, encrypt),
+    _ => Err(aes_key_length_error()),
+  }
+}
+
+fn cbc<C, M>(
+  key: &[u8],
+  params: &EncryptParams,
+  data: &[u8],
+  encrypt: bool,
+) -> Result<Vec<u8>, AnyError>
+where
+  C: BlockCipher + NewBlockCipher,
+  M: BlockMode<C, Pkcs7>,
+{
+  let cipher = M::new_var(key, &params.iv)
+    .map_err(|_| operation_error("AES-CBC iv must be 16 bytes"))?;
+  if encrypt {
+    Ok(cipher.encrypt_vec(data))
+  } else {
+    cipher
+      .decrypt_vec(data)
+      .map_err(|_| operation_error("Decryption failed"))
+  }
+}
+
+fn aes_cbc(
+  key: &[u8],
+  params: &EncryptParams,
+  data: &[u8],
+  encrypt: bool,
+) -> Result<Vec<u8>, AnyError> {
+  match key.len() {
+    16 => cbc::<Aes128, Cbc<Aes128, Pkcs7>>(key, params, data, encrypt),
+    24 => cbc::<Aes192, Cbc<Aes192, Pkcs7>>(key, params, data, encrypt),
+    32 => cbc::<Aes256, Cbc<Aes256, Pkcs7>>(key, params, data, encrypt),
+    _ => Err(aes_key_length_error()),
+  }
+}
+
+/// Encrypts or decrypts in counter mode, where only the rightmost `length`
+/// bits of the counter block are incremented, wrapping around.
+fn ctr<C>(
+  key: &[u8],
+  counter: &[u8],
+  length: u32,
+  data: &[u8],
+) -> Result<Vec<u8>, AnyError>
+where
+  C: BlockCipher<BlockSize = U16> + NewBlockCipher,
+{
+  let cipher = C::new_varkey(key).map_err(|_| aes_key_length_error())?;
+  let mask = if length == 128 {
+    u128::MAX
+  } else {
+    (1 << length) - 1
+  };
+  // Counter blocks must not repeat.
+  let blocks = (data.len() as u128 + 15) / 16;
+  if length < 128 && blocks > 1 << length {
+    return Err(operation_error("AES-CTR counter length is too small"));
+  }
+
+  let mut counter_block = [0; 16];
+  counter_block.copy_from_slice(counter);
+  let mut counter = u128::from_be_bytes(counter_block);
+  let mut output = Vec::with_capacity(data.len());
+  for chunk in data.chunks(16) {
+    let mut block = GenericArray::clone_from_slice(&counter.to_be_bytes());
+    cipher.encrypt_block(&mut block);
+    output.extend(chunk.iter().zip(block.iter()).map(|(a, b)| a ^ b));
+    counter = (counter & !mask) | (counter.wrapping_add(1) & mask);
+  }
+  Ok(output)
+}
+
+fn aes_ctr(
+  key: &[u8],
+  args: &EncryptArgs,
+  params: &EncryptParams,
+  data: &[u8],
+) -> Result<Vec<u8>, AnyError> {
+  let length = args.length.ok_or_else(|| type_error("Missing length"))?;
+  if params.iv.len() != 16 {
+    return Err(operation_error("AES-CTR counter must be 16 bytes"));
+  }
+  if length == 0 || length > 128 {
+    return Err(operation_error(
+      "AES-CTR length must be between 1 and 128 bits",
+    ));
+  }
+  match key.len() {
+    16 => ctr::<Aes128>(key, &params.iv, length, data),
+    24 => ctr::<Aes192>(key, &params.iv, length, data),
+    32 => ctr::<Aes256>(key, &params.iv, length, data),
+    _ => Err(aes_key_length_error()),
+  }
+}
+
+fn oaep_label(params: &EncryptParams) -> Result<Option<String>, AnyError> {
+  if params.iv.is_empty() {
+    return Ok(None);
+  }
+  String::from_utf8(params.iv.clone()).map(Some).map_err(|_| {
+    not_supported_error("Only UTF-8 RSA-OAEP labels are supported")
+  })
+}
+
+fn wrong_key_type(name: &str) -> AnyError {
+  type_error(format!("Key can't be used with {}", name))
+}
+
+pub fn encrypt(
+  key: &KeyMaterial,
+  args: &EncryptArgs,
+  params: &EncryptParams,
+  data: &[u8],
+) -> Result<Vec<u8>, AnyError> {
+  match (args.name.as_str(), key) {
+    ("AES-GCM", KeyMaterial::Secret(key)) => {
+      aes_gcm(key, args, params, data, true)
+    }
+    ("AES-CBC", KeyMaterial::Secret(key)) => aes_cbc(key, params, data, true),
+    ("AES-CTR", KeyMaterial::Secret(key)) => aes_ctr(key, args, params, data),
+    ("RSA-OAEP", KeyMaterial::RsaPublic(key)) => {
+      let hash = args.hash.ok_or_else(|| type_error("Missing hash"))?;
+      let padding = hash.oaep_padding(oaep_label(params)?);
+      key
+        .encrypt(&mut OsRng, padding, data)
+        .map_err(|err| operation_error(err.to_string()))
+    }
+    (name, _) => Err(wrong_key_type(name)),
+  }
+}
+
+pub fn decrypt(
+  key: &KeyMaterial,
+  args: &EncryptArgs,
+  params: &EncryptParams,
+  data: &[u8],
+) -> Result<Vec<u8>, AnyError> {
+  match (args.name.as_str(), key) {
+    ("AES-GCM", KeyMaterial::Secret(key)) => {
+      aes_gcm(key, args, params, data, false)
+    }
+    ("AES-CBC", KeyMaterial::Secret(key)) => aes_cbc(key, params, data, false),
+    ("AES-CTR", KeyMaterial::Secret(key)) => aes_ctr(key, args, params, data),
+    ("RSA-OAEP", KeyMaterial::RsaPrivate(key)) => {
+      let hash = args.hash.ok_or_else(|| type_error("Missing hash"))?;
+      let padding = hash.oaep_padding(oaep_label(params)?);
+      key
+        .decrypt_blinded(&mut OsRng, padding, data)
+        .map_err(|_| operation_error("Decryption failed"))
+    }
+    (name, _) => Err(wrong_key_type(name)),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+  }
+
+  fn unhex(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+      .step_by(2)
+      .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+      .collect()
+  }
+
+  fn args(name: &str, length: Option<u32>) -> EncryptArgs {
+    EncryptArgs {
+      key: 0,
+      name: name.to_string(),
+      hash: None,
+      length,
+      tag_length: None,
+    }
+  }
+
+  // The vectors are from NIST SP 800-38A, F.2.1 and F.5.1.
+  const KEY: &str = "2b7e151628aed2a6abf7158809cf4f3c";
+  const PLAINTEXT: &str =
+    "6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e51";
+
+  #[test]
+  fn aes_cbc_vector() {
+    let key = KeyMaterial::Secret(unhex(KEY));
+    let params = EncryptParams {
+      iv: unhex("000102030405060708090a0b0c0d0e0f"),
+      additional_data: vec![],
+    };
+    let ciphertext =
+      encrypt(&key, &args("AES-CBC", None), &params, &unhex(PLAINTEXT))
+        .unwrap();
+    // Followed by a block of padding.
+    assert_eq!(ciphertext.len(), 48);
+    assert_eq!(
+      hex(&ciphertext[..32]),
+      "7649abac8119b246cee98e9b12e9197d5086cb9b507219ee95db113a917678b2"
+    );
+    let plaintext =
+      decrypt(&key, &args("AES-CBC", None), &params, &ciphertext).unwrap();
+    assert_eq!(hex(&plaintext), PLAINTEXT);
+  }
+
+  #[test]
+  fn aes_ctr_vector() {
+    let key = KeyMaterial::Secret(unhex(KEY));
+    let params = EncryptParams {
+      iv: unhex("f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff"),
+      additional_data: vec![],
+    };
+    let ciphertext =
+      encrypt(&key, &args("AES-CTR", Some(64)), &params, &unhex(PLAINTEXT))
+        .unwrap();
+    assert_eq!(
+      hex(&ciphertext),
+      "874d6191b620e3261bef6864990db6ce9806f66b7970fdff8617187bb9fffdff"
+    );
+  }
+
+  #[test]
+  fn aes_ctr_counter_wraps() {
+    let key = KeyMaterial::Secret(unhex(KEY));
+    let wrapping = EncryptParams {
+      iv: unhex("000000000000000000000000000000ff"),
+      additional_data: vec![],
+    };
+    let restarted = EncryptParams {
+      iv: unhex("00000000000000000000000000000000"),
+      additional_data: vec![],
+    };
+    let data = [0; 32];
+    let ciphertext =
+      encrypt(&key, &args("AES-CTR", Some(8)), &wrapping, &data).unwrap();
+    // The second block uses the counter 0, as only the last byte counts.
+    let expected =
+      encrypt(&key, &args("AES-CTR", Some(8)), &restarted, &data[..16])
+        .unwrap();
+    assert_eq!(&ciphertext[16..], &expected[..]);
+
+    let too_long = [0; 16 * 257];
+    assert!(
+      encrypt(&key, &args("AES-CTR", Some(8)), &restarted, &too_long).is_err()
+    );
+  }
+
+  #[test]
+  fn aes_gcm_tamper() {
+    let key = KeyMaterial::Secret(unhex(KEY));
+    let params = EncryptParams {
+      iv: vec![0; 12],
+      additional_data: b"header".to_vec(),
+    };
+    let mut ciphertext =
+      encrypt(&key, &args("AES-GCM", None), &params, b"hello").unwrap();
+    assert_eq!(ciphertext.len(), 5 + 16);
+    assert_eq!(
+      decrypt(&key, &args("AES-GCM", None), &params, &ciphertext).unwrap(),
+      b"hello"
+    );
+    ciphertext[0] ^= 1;
+    assert!(
+      decrypt(&key, &args("AES-GCM", None), &params, &ciphertext).is_err()
+    );
+  }
+}
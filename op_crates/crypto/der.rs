@@ -0,0 +1,163 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+//! Just enough DER to read and write the PKCS #8 and SubjectPublicKeyInfo
+//! documents of the supported key types. The key material read from them is
+//! still validated by `ring` or `rsa`.
+
+use crate::key::data_error;
+use deno_core::error::AnyError;
+
+pub const INTEGER: u8 = 0x02;
+pub const BIT_STRING: u8 = 0x03;
+pub const OCTET_STRING: u8 = 0x04;
+pub const NULL: u8 = 0x05;
+pub const OBJECT_IDENTIFIER: u8 = 0x06;
+pub const SEQUENCE: u8 = 0x30;
+pub const CONTEXT_SPECIFIC_0: u8 = 0xa0;
+pub const CONTEXT_SPECIFIC_1: u8 = 0xa1;
+
+/// 1.2.840.113549.1.1.1
+pub const RSA_ENCRYPTION: &[u8] =
+  &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x01];
+/// 1.2.840.10045.2.1
+pub const EC_PUBLIC_KEY: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01];
+/// 1.2.840.10045.3.1.7
+pub const SECP256R1: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07];
+/// 1.3.132.0.34
+pub const SECP384R1: &[u8] = &[0x2b, 0x81, 0x04, 0x00, 0x22];
+
+/// Encodes a value with the given tag.
+pub fn encode(tag: u8, contents: &[u8]) -> Vec<u8> {
+  let mut output = vec![tag];
+  let length = contents.len();
+  if length < 0x80 {
+    output.push(length as u8);
+  } else {
+    let bytes = length.to_be_bytes();
+    let skip = bytes.iter().take_while(|byte| **byte == 0).count();
+    output.push(0x80 | (bytes.len() - skip) as u8);
+    output.extend_from_slice(&bytes[skip..]);
+  }
+  output.extend_from_slice(contents);
+  output
+}
+
+pub fn sequence(items: &[Vec<u8>]) -> Vec<u8> {
+  encode(SEQUENCE, &items.concat())
+}
+
+/// Encodes the big-endian bytes of a non-negative integer.
+pub fn unsigned_integer(bytes: &[u8]) -> Vec<u8> {
+  let skip = bytes.iter().take_while(|byte| **byte == 0).count();
+  let bytes = &bytes[skip..];
+  if bytes.first().map_or(true, |byte| byte & 0x80 != 0) {
+    encode(INTEGER, &[&[0], bytes].concat())
+  } else {
+    encode(INTEGER, bytes)
+  }
+}
+
+pub fn bit_string(bytes: &[u8]) -> Vec<u8> {
+  encode(BIT_STRING, &[&[0], bytes].concat())
+}
+
+/// Reads the values of a DER document one after the other.
+pub struct Reader<'a> {
+  input: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+  pub fn new(input: &'a [u8]) -> Self {
+    Self { input }
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.input.is_empty()
+  }
+
+  pub fn peek(&self, tag: u8) -> bool {
+    self.input.first() == Some(&tag)
+  }
+
+  /// Returns the contents of the next value, which must have the given tag.
+  pub fn read(&mut self, tag: u8) -> Result<&'a [u8], AnyError> {
+    let invalid = || data_error("Invalid DER encoding");
+    if !self.peek(tag) {
+      return Err(invalid());
+    }
+    let first = *self.input.get(1).ok_or_else(invalid)?;
+    let (length, header) = if first < 0x80 {
+      (first as usize, 2)
+    } else {
+      let count = (first & 0x7f) as usize;
+      if count == 0 || count > std::mem::size_of::<usize>() {
+        return Err(invalid());
+      }
+      let bytes = self.input.get(2..2 + count).ok_or_else(invalid)?;
+      let length = bytes
+        .iter()
+        .fold(0usize, |length, byte| (length << 8) | *byte as usize);
+      (length, 2 + count)
+    };
+    let end = header.checked_add(length).ok_or_else(invalid)?;
+    let contents = self.input.get(header..end).ok_or_else(invalid)?;
+    self.input = &self.input[end..];
+    Ok(contents)
+  }
+
+  /// Returns the big-endian bytes of a non-negative integer.
+  pub fn read_unsigned_integer(&mut self) -> Result<&'a [u8], AnyError> {
+    let bytes = self.read(INTEGER)?;
+    if bytes.first().map_or(true, |byte| byte & 0x80 != 0) {
+      return Err(data_error("Invalid DER encoding"));
+    }
+    let skip = bytes.iter().take_while(|byte| **byte == 0).count();
+    Ok(&bytes[skip..])
+  }
+
+  /// Returns the bytes of a bit string without unused bits.
+  pub fn read_bit_string(&mut self) -> Result<&'a [u8], AnyError> {
+    match self.read(BIT_STRING)?.split_first() {
+      Some((0, bytes)) => Ok(bytes),
+      _ => Err(data_error("Invalid DER encoding")),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn encode_read() {
+    let long = vec![0xff; 300];
+    let document = sequence(&[
+      unsigned_integer(&[0x00, 0x80]),
+      encode(OCTET_STRING, &long),
+      bit_string(&[1, 2]),
+    ]);
+    assert_eq!(&document[..4], &[SEQUENCE, 0x82, 0x01, 0x39]);
+
+    let mut reader = Reader::new(&document);
+    let mut sequence = Reader::new(reader.read(SEQUENCE).unwrap());
+    assert!(reader.is_empty());
+    assert_eq!(sequence.read_unsigned_integer().unwrap(), &[0x80]);
+    assert_eq!(sequence.read(OCTET_STRING).unwrap(), &long[..]);
+    assert_eq!(sequence.read_bit_string().unwrap(), &[1, 2]);
+    assert!(sequence.is_empty());
+    assert!(sequence.read(NULL).is_err());
+  }
+
+  #[test]
+  fn read_invalid() {
+    assert!(Reader::new(&[SEQUENCE]).read(SEQUENCE).is_err());
+    assert!(Reader::new(&[SEQUENCE, 0x03, 0x00]).read(SEQUENCE).is_err());
+    assert!(Reader::new(&[SEQUENCE, 0x80]).read(SEQUENCE).is_err());
+    assert!(Reader::new(&[INTEGER, 0x01, 0x80])
+      .read_unsigned_integer()
+      .is_err());
+    assert!(Reader::new(&[BIT_STRING, 0x02, 0x01, 0x00])
+      .read_bit_string()
+      .is_err());
+  }
+}
@@ -0,0 +1,202 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+//! Bit derivation with PBKDF2, HKDF and ECDH.
+
+use crate::key::invalid_access_error;
+use crate::key::not_supported_error;
+use crate::key::operation_error;
+use crate::key::HashAlgorithm;
+use crate::key::KeyMaterial;
+use crate::key::NamedCurve;
+use deno_core::error::type_error;
+use deno_core::error::AnyError;
+use ring::hkdf;
+use ring::pbkdf2;
+use serde::Deserialize;
+use std::num::NonZeroU32;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeriveBitsArgs {
+  pub key: u32,
+  pub name: String,
+  pub hash: Option<HashAlgorithm>,
+  pub iterations: Option<u32>,
+  /// The id of the ECDH public key.
+  pub public_key: Option<u32>,
+}
+
+/// The buffers that go along with the output of a derivation.
+pub struct DeriveParams {
+  pub salt: Vec<u8>,
+  /// The HKDF info.
+  pub info: Vec<u8>,
+}
+
+impl HashAlgorithm {
+  fn pbkdf2_algorithm(self) -> pbkdf2::Algorithm {
+    match self {
+      HashAlgorithm::Sha1 => pbkdf2::PBKDF2_HMAC_SHA1,
+      HashAlgorithm::Sha256 => pbkdf2::PBKDF2_HMAC_SHA256,
+      HashAlgorithm::Sha384 => pbkdf2::PBKDF2_HMAC_SHA384,
+      HashAlgorithm::Sha512 => pbkdf2::PBKDF2_HMAC_SHA512,
+    }
+  }
+
+  fn hkdf_algorithm(self) -> hkdf::Algorithm {
+    match self {
+      HashAlgorithm::Sha1 => hkdf::HKDF_SHA1_FOR_LEGACY_USE_ONLY,
+      HashAlgorithm::Sha256 => hkdf::HKDF_SHA256,
+      HashAlgorithm::Sha384 => hkdf::HKDF_SHA384,
+      HashAlgorithm::Sha512 => hkdf::HKDF_SHA512,
+    }
+  }
+}
+
+/// The output length of an HKDF expansion, as `ring` wants it.
+struct OutputLength(usize);
+
+impl hkdf::KeyType for OutputLength {
+  fn len(&self) -> usize {
+    self.0
+  }
+}
+
+fn ecdh(
+  private_key: &[u8],
+  public_key: &[u8],
+  output: &mut [u8],
+) -> Result<(), AnyError> {
+  let secret = p256::SecretKey::from_bytes(private_key)
+    .map_err(|_| operation_error("Invalid ECDH private key"))?;
+  let public = p256::PublicKey::from_sec1_bytes(public_key)
+    .map_err(|_| operation_error("Invalid ECDH public key"))?;
+  let shared =
+    p256::ecdh::diffie_hellman(secret.secret_scalar(), public.as_affine());
+  let shared = shared.as_bytes();
+  if output.len() > shared.len() {
+    return Err(operation_error(format!(
+      "ECDH can derive at most {} bits",
+      shared.len() * 8
+    )));
+  }
+  output.copy_from_slice(&shared[..output.len()]);
+  Ok(())
+}
+
+/// Fills `output` with derived bits.
+pub fn derive_bits(
+  key: &KeyMaterial,
+  public_key: Option<&KeyMaterial>,
+  args: &DeriveBitsArgs,
+  params: &DeriveParams,
+  output: &mut [u8],
+) -> Result<(), AnyError> {
+  match (args.name.as_str(), key) {
+    ("PBKDF2", KeyMaterial::Secret(secret)) => {
+      let hash = args.hash.ok_or_else(|| type_error("Missing hash"))?;
+      let iterations = args
+        .iterations
+        .and_then(NonZeroU32::new)
+        .ok_or_else(|| operation_error("PBKDF2 iterations must not be 0"))?;
+      if output.is_empty() {
+        return Err(operation_error("PBKDF2 length must not be 0"));
+      }
+      pbkdf2::derive(
+        hash.pbkdf2_algorithm(),
+        iterations,
+        &params.salt,
+        secret,
+        output,
+      );
+      Ok(())
+    }
+    ("HKDF", KeyMaterial::Secret(secret)) => {
+      let hash = args.hash.ok_or_else(|| type_error("Missing hash"))?;
+      let salt = hkdf::Salt::new(hash.hkdf_algorithm(), &params.salt);
+      let info = [&params.info[..]];
+      salt
+        .extract(secret)
+        .expand(&info, OutputLength(output.len()))
+        .and_then(|okm| okm.fill(output))
+        .map_err(|_| operation_error("HKDF length is too large"))
+    }
+    (
+      "ECDH",
+      KeyMaterial::EcPrivate {
+        curve, private_key, ..
+      },
+    ) => match public_key {
+      Some(KeyMaterial::EcPublic {
+        curve: public_curve,
+        point,
+      }) => {
+        if curve != public_curve {
+          return Err(invalid_access_error(
+            "ECDH keys must be on the same curve",
+          ));
+        }
+        if *curve != NamedCurve::P256 {
+          return Err(not_supported_error("ECDH only supports P-256"));
+        }
+        ecdh(private_key, point, output)
+      }
+      _ => Err(invalid_access_error("ECDH public key must be a public key")),
+    },
+    (name, _) => Err(type_error(format!("Key can't be used with {}", name))),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+  }
+
+  fn args(name: &str, hash: HashAlgorithm) -> DeriveBitsArgs {
+    DeriveBitsArgs {
+      key: 0,
+      name: name.to_string(),
+      hash: Some(hash),
+      iterations: Some(2),
+      public_key: None,
+    }
+  }
+
+  #[test]
+  fn pbkdf2_vector() {
+    // RFC 6070, test case 2.
+    let key = KeyMaterial::Secret(b"password".to_vec());
+    let params = DeriveParams {
+      salt: b"salt".to_vec(),
+      info: vec![],
+    };
+    let mut output = [0; 20];
+    let args = args("PBKDF2", HashAlgorithm::Sha1);
+    derive_bits(&key, None, &args, &params, &mut output).unwrap();
+    assert_eq!(hex(&output), "ea6c014dc72d6f8ccd1ed92ace1d41f0d8de8957");
+  }
+
+  #[test]
+  fn hkdf_vector() {
+    // RFC 5869, test case 1.
+    let key = KeyMaterial::Secret(vec![0x0b; 22]);
+    let params = DeriveParams {
+      salt: (0x00..=0x0c).collect(),
+      info: (0xf0..=0xf9).collect(),
+    };
+    let mut output = [0; 42];
+    let args = args("HKDF", HashAlgorithm::Sha256);
+    derive_bits(&key, None, &args, &params, &mut output).unwrap();
+    assert_eq!(
+      hex(&output),
+      "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf\
+       34007208d5b887185865"
+    );
+
+    let mut output = vec![0; 255 * 32 + 1];
+    assert!(derive_bits(&key, None, &args, &params, &mut output).is_err());
+  }
+}
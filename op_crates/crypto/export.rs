@@ -0,0 +1,590 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+//! Importing and exporting key material in the raw, PKCS #8,
+//! SubjectPublicKeyInfo and JWK formats.
+
+use crate::der;
+use crate::der::Reader;
+use crate::key::data_error;
+use crate::key::invalid_access_error;
+use crate::key::KeyMaterial;
+use crate::key::NamedCurve;
+use deno_core::error::type_error;
+use deno_core::error::AnyError;
+use deno_core::serde_json::json;
+use deno_core::serde_json::Value;
+use ring::signature;
+use ring::signature::EcdsaKeyPair;
+use rsa::BigUint;
+use rsa::PublicKeyParts;
+use rsa::RSAPrivateKey;
+use rsa::RSAPublicKey;
+use serde::Deserialize;
+use serde::Serialize;
+
+#[derive(Clone, Copy, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyFormat {
+  Raw,
+  Pkcs8,
+  Spki,
+  Jwk,
+}
+
+/// The kind of key material an algorithm uses.
+#[derive(Clone, Copy, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyKind {
+  Secret,
+  Ec,
+  Rsa,
+}
+
+/// The key material members of a JSON Web Key. The other members are
+/// handled in JS.
+#[derive(Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Jwk {
+  pub kty: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub k: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub crv: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub x: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub y: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub d: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub n: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub e: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub p: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub q: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub dp: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub dq: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub qi: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportKeyArgs {
+  pub format: KeyFormat,
+  pub kind: KeyKind,
+  pub named_curve: Option<NamedCurve>,
+  pub jwk: Option<Jwk>,
+}
+
+pub enum ExportedKey {
+  Bytes(Vec<u8>),
+  Jwk(Jwk),
+}
+
+impl NamedCurve {
+  fn oid(self) -> &'static [u8] {
+    match self {
+      NamedCurve::P256 => der::SECP256R1,
+      NamedCurve::P384 => der::SECP384R1,
+    }
+  }
+
+  fn jwk_name(self) -> &'static str {
+    match self {
+      NamedCurve::P256 => "P-256",
+      NamedCurve::P384 => "P-384",
+    }
+  }
+}
+
+fn base64_decode(
+  value: &Option<String>,
+  member: &str,
+) -> Result<Vec<u8>, AnyError> {
+  let value = value.as_ref().ok_or_else(|| {
+    data_error(format!("JWK member \"{}\" is missing", member))
+  })?;
+  base64::decode_config(value, base64::URL_SAFE_NO_PAD).map_err(|_| {
+    data_error(format!("JWK member \"{}\" is not base64url", member))
+  })
+}
+
+fn base64_encode(bytes: &[u8]) -> Option<String> {
+  Some(base64::encode_config(bytes, base64::URL_SAFE_NO_PAD))
+}
+
+/// Checks that `point` is an uncompressed point of the size of the curve.
+fn check_ec_point(curve: NamedCurve, point: &[u8]) -> Result<(), AnyError> {
+  if point.len() != 1 + 2 * curve.scalar_length() || point[0] != 0x04 {
+    return Err(data_error("Only uncompressed EC points are supported"));
+  }
+  Ok(())
+}
+
+/// Checks that the private scalar and the public point of a key belong
+/// together.
+fn check_ec_private_key(
+  curve: NamedCurve,
+  private_key: &[u8],
+  point: &[u8],
+) -> Result<(), AnyError> {
+  check_ec_point(curve, point)?;
+  let algorithm = match curve {
+    NamedCurve::P256 => &signature::ECDSA_P256_SHA256_FIXED_SIGNING,
+    NamedCurve::P384 => &signature::ECDSA_P384_SHA384_FIXED_SIGNING,
+  };
+  EcdsaKeyPair::from_private_key_and_public_key(algorithm, private_key, point)
+    .map_err(|_| data_error("Invalid EC private key"))?;
+  Ok(())
+}
+
+/// Reads the `AlgorithmIdentifier` of a PKCS #8 or SPKI document, and checks
+/// that it is the one of EC keys on `curve`.
+fn read_ec_algorithm(
+  reader: &mut Reader,
+  curve: NamedCurve,
+) -> Result<(), AnyError> {
+  let mut algorithm = Reader::new(reader.read(der::SEQUENCE)?);
+  if algorithm.read(der::OBJECT_IDENTIFIER)? != der::EC_PUBLIC_KEY {
+    return Err(data_error("Key is not an EC key"));
+  }
+  if algorithm.read(der::OBJECT_IDENTIFIER)? != curve.oid() {
+    return Err(data_error("Key curve doesn't match namedCurve"));
+  }
+  Ok(())
+}
+
+fn ec_algorithm(curve: NamedCurve) -> Vec<u8> {
+  der::sequence(&[
+    der::encode(der::OBJECT_IDENTIFIER, der::EC_PUBLIC_KEY),
+    der::encode(der::OBJECT_IDENTIFIER, curve.oid()),
+  ])
+}
+
+fn rsa_algorithm() -> Vec<u8> {
+  der::sequence(&[
+    der::encode(der::OBJECT_IDENTIFIER, der::RSA_ENCRYPTION),
+    der::encode(der::NULL, &[]),
+  ])
+}
+
+/// Returns the private scalar and the public point of a PKCS #8 document
+/// holding an EC key on `curve`.
+pub fn parse_ec_pkcs8(
+  pkcs8: &[u8],
+  curve: NamedCurve,
+) -> Result<(Vec<u8>, Vec<u8>), AnyError> {
+  let mut document = Reader::new(pkcs8);
+  let mut info = Reader::new(document.read(der::SEQUENCE)?);
+  if !document.is_empty() || info.read_unsigned_integer()?.len() > 1 {
+    return Err(data_error("Invalid PKCS #8 document"));
+  }
+  read_ec_algorithm(&mut info, curve)?;
+
+  let mut private_key = Reader::new(info.read(der::OCTET_STRING)?);
+  let mut ec_private_key = Reader::new(private_key.read(der::SEQUENCE)?);
+  if ec_private_key.read_unsigned_integer()? != [1] {
+    return Err(data_error("Invalid EC private key"));
+  }
+  let scalar = ec_private_key.read(der::OCTET_STRING)?.to_vec();
+  if ec_private_key.peek(der::CONTEXT_SPECIFIC_0) {
+    let mut parameters =
+      Reader::new(ec_private_key.read(der::CONTEXT_SPECIFIC_0)?);
+    if parameters.read(der::OBJECT_IDENTIFIER)? != curve.oid() {
+      return Err(data_error("Key curve doesn't match namedCurve"));
+    }
+  }
+  if !ec_private_key.peek(der::CONTEXT_SPECIFIC_1) {
+    return Err(data_error(
+      "EC private keys without their public key are not supported",
+    ));
+  }
+  let point = Reader::new(ec_private_key.read(der::CONTEXT_SPECIFIC_1)?)
+    .read_bit_string()?
+    .to_vec();
+  check_ec_private_key(curve, &scalar, &point)?;
+  Ok((scalar, point))
+}
+
+fn parse_ec_spki(spki: &[u8], curve: NamedCurve) -> Result<Vec<u8>, AnyError> {
+  let mut document = Reader::new(spki);
+  let mut info = Reader::new(document.read(der::SEQUENCE)?);
+  read_ec_algorithm(&mut info, curve)?;
+  let point = info.read_bit_string()?.to_vec();
+  if !document.is_empty() || !info.is_empty() {
+    return Err(data_error("Invalid SubjectPublicKeyInfo document"));
+  }
+  check_ec_point(curve, &point)?;
+  Ok(point)
+}
+
+/// Returns n, e, d, p, q, dp, dq and qi of a key with two primes.
+fn rsa_private_components(
+  key: &RSAPrivateKey,
+) -> Result<[BigUint; 8], AnyError> {
+  let primes = key.primes();
+  if primes.len() != 2 {
+    return Err(data_error(
+      "RSA keys with more than two primes are not supported",
+    ));
+  }
+  let (p, q) = (&primes[0], &primes[1]);
+  let one = BigUint::from(1u32);
+  let two = BigUint::from(2u32);
+  let dp = key.d() % (p - &one);
+  let dq = key.d() % (q - &one);
+  // p is prime, so q^(p - 2) is the inverse of q modulo p.
+  let qi = q.modpow(&(p - &two), p);
+  Ok([
+    key.n().clone(),
+    key.e().clone(),
+    key.d().clone(),
+    p.clone(),
+    q.clone(),
+    dp,
+    dq,
+    qi,
+  ])
+}
+
+fn rsa_public_key(key: &impl PublicKeyParts) -> Vec<u8> {
+  der::sequence(&[
+    der::unsigned_integer(&key.n().to_bytes_be()),
+    der::unsigned_integer(&key.e().to_bytes_be()),
+  ])
+}
+
+fn import_jwk(
+  jwk: &Jwk,
+  kind: KeyKind,
+  curve: Option<NamedCurve>,
+) -> Result<KeyMaterial, AnyError> {
+  match (kind, curve) {
+    (KeyKind::Secret, _) => {
+      if jwk.kty != "oct" {
+        return Err(data_error("JWK \"kty\" must be \"oct\""));
+      }
+      Ok(KeyMaterial::Secret(base64_decode(&jwk.k, "k")?))
+    }
+    (KeyKind::Ec, Some(curve)) => {
+      if jwk.kty != "EC" {
+        return Err(data_error("JWK \"kty\" must be \"EC\""));
+      }
+      if jwk.crv.as_deref() != Some(curve.jwk_name()) {
+        return Err(data_error("JWK \"crv\" doesn't match namedCurve"));
+      }
+      let x = base64_decode(&jwk.x, "x")?;
+      let y = base64_decode(&jwk.y, "y")?;
+      if x.len() != curve.scalar_length() || y.len() != curve.scalar_length() {
+        return Err(data_error("Invalid EC point"));
+      }
+      let point = [&[0x04], &x[..], &y[..]].concat();
+      if jwk.d.is_none() {
+        return Ok(KeyMaterial::EcPublic { curve, point });
+      }
+      let private_key = base64_decode(&jwk.d, "d")?;
+      check_ec_private_key(curve, &private_key, &point)?;
+      Ok(KeyMaterial::EcPrivate {
+        curve,
+        private_key,
+        point,
+      })
+    }
+    (KeyKind::Rsa, _) => {
+      if jwk.kty != "RSA" {
+        return Err(data_error("JWK \"kty\" must be \"RSA\""));
+      }
+      let decode = |value, member| {
+        base64_decode(value, member).map(|bytes| BigUint::from_bytes_be(&bytes))
+      };
+      let n = decode(&jwk.n, "n")?;
+      let e = decode(&jwk.e, "e")?;
+      if jwk.d.is_none() {
+        let key =
+          RSAPublicKey::new(n, e).map_err(|err| data_error(err.to_string()))?;
+        return Ok(KeyMaterial::RsaPublic(key));
+      }
+      let d = decode(&jwk.d, "d")?;
+      let primes = vec![decode(&jwk.p, "p")?, decode(&jwk.q, "q")?];
+      let mut key = RSAPrivateKey::from_components(n, e, d, primes);
+      key.validate().map_err(|err| data_error(err.to_string()))?;
+      key
+        .precompute()
+        .map_err(|err| data_error(err.to_string()))?;
+      Ok(KeyMaterial::RsaPrivate(key))
+    }
+    _ => unreachable!(),
+  }
+}
+
+pub fn import_key(
+  args: &ImportKeyArgs,
+  data: &[u8],
+) -> Result<KeyMaterial, AnyError> {
+  let curve = args.named_curve;
+  if args.kind == KeyKind::Ec && curve.is_none() {
+    return Err(type_error("Missing namedCurve"));
+  }
+  match (args.format, args.kind) {
+    (KeyFormat::Jwk, kind) => {
+      let jwk = args.jwk.as_ref().ok_or_else(|| type_error("Missing jwk"))?;
+      import_jwk(jwk, kind, curve)
+    }
+    (KeyFormat::Raw, KeyKind::Secret) => Ok(KeyMaterial::Secret(data.to_vec())),
+    (KeyFormat::Raw, KeyKind::Ec) => {
+      let curve = curve.unwrap();
+      check_ec_point(curve, data)?;
+      Ok(KeyMaterial::EcPublic {
+        curve,
+        point: data.to_vec(),
+      })
+    }
+    (KeyFormat::Pkcs8, KeyKind::Ec) => {
+      let curve = curve.unwrap();
+      let (private_key, point) = parse_ec_pkcs8(data, curve)?;
+      Ok(KeyMaterial::EcPrivate {
+        curve,
+        private_key,
+        point,
+      })
+    }
+    (KeyFormat::Spki, KeyKind::Ec) => {
+      let curve = curve.unwrap();
+      Ok(KeyMaterial::EcPublic {
+        curve,
+        point: parse_ec_spki(data, curve)?,
+      })
+    }
+    (KeyFormat::Pkcs8, KeyKind::Rsa) => {
+      let key = RSAPrivateKey::from_pkcs8(data)
+        .map_err(|err| data_error(err.to_string()))?;
+      Ok(KeyMaterial::RsaPrivate(key))
+    }
+    (KeyFormat::Spki, KeyKind::Rsa) => {
+      let key = RSAPublicKey::from_pkcs8(data)
+        .map_err(|err| data_error(err.to_string()))?;
+      Ok(KeyMaterial::RsaPublic(key))
+    }
+    _ => Err(type_error("Unsupported key format")),
+  }
+}
+
+pub fn export_key(
+  key: &KeyMaterial,
+  format: KeyFormat,
+) -> Result<ExportedKey, AnyError> {
+  match (format, key) {
+    (KeyFormat::Raw, KeyMaterial::Secret(secret)) => {
+      Ok(ExportedKey::Bytes(secret.clone()))
+    }
+    (KeyFormat::Jwk, KeyMaterial::Secret(secret)) => {
+      Ok(ExportedKey::Jwk(Jwk {
+        kty: "oct".to_string(),
+        k: base64_encode(secret),
+        ..Default::default()
+      }))
+    }
+    (KeyFormat::Raw, KeyMaterial::EcPublic { point, .. }) => {
+      Ok(ExportedKey::Bytes(point.clone()))
+    }
+    (KeyFormat::Spki, KeyMaterial::EcPublic { curve, point }) => {
+      Ok(ExportedKey::Bytes(der::sequence(&[
+        ec_algorithm(*curve),
+        der::bit_string(point),
+      ])))
+    }
+    (
+      KeyFormat::Pkcs8,
+      KeyMaterial::EcPrivate {
+        curve,
+        private_key,
+        point,
+      },
+    ) => {
+      let ec_private_key = der::sequence(&[
+        der::unsigned_integer(&[1]),
+        der::encode(der::OCTET_STRING, private_key),
+        der::encode(der::CONTEXT_SPECIFIC_1, &der::bit_string(point)),
+      ]);
+      Ok(ExportedKey::Bytes(der::sequence(&[
+        der::unsigned_integer(&[0]),
+        ec_algorithm(*curve),
+        der::encode(der::OCTET_STRING, &ec_private_key),
+      ])))
+    }
+    (KeyFormat::Jwk, KeyMaterial::EcPublic { curve, point })
+    | (KeyFormat::Jwk, KeyMaterial::EcPrivate { curve, point, .. }) => {
+      let (x, y) = point[1..].split_at(curve.scalar_length());
+      let d = match key {
+        KeyMaterial::EcPrivate { private_key, .. } => {
+          base64_encode(private_key)
+        }
+        _ => None,
+      };
+      Ok(ExportedKey::Jwk(Jwk {
+        kty: "EC".to_string(),
+        crv: Some(curve.jwk_name().to_string()),
+        x: base64_encode(x),
+        y: base64_encode(y),
+        d,
+        ..Default::default()
+      }))
+    }
+    (KeyFormat::Spki, KeyMaterial::RsaPublic(key)) => {
+      Ok(ExportedKey::Bytes(der::sequence(&[
+        rsa_algorithm(),
+        der::bit_string(&rsa_public_key(key)),
+      ])))
+    }
+    (KeyFormat::Pkcs8, KeyMaterial::RsaPrivate(key)) => {
+      let mut components = vec![der::unsigned_integer(&[0])];
+      for component in rsa_private_components(key)?.iter() {
+        components.push(der::unsigned_integer(&component.to_bytes_be()));
+      }
+      Ok(ExportedKey::Bytes(der::sequence(&[
+        der::unsigned_integer(&[0]),
+        rsa_algorithm(),
+        der::encode(der::OCTET_STRING, &der::sequence(&components)),
+      ])))
+    }
+    (KeyFormat::Jwk, KeyMaterial::RsaPublic(key)) => {
+      Ok(ExportedKey::Jwk(Jwk {
+        kty: "RSA".to_string(),
+        n: base64_encode(&key.n().to_bytes_be()),
+        e: base64_encode(&key.e().to_bytes_be()),
+        ..Default::default()
+      }))
+    }
+    (KeyFormat::Jwk, KeyMaterial::RsaPrivate(key)) => {
+      let [n, e, d, p, q, dp, dq, qi] = rsa_private_components(key)?;
+      let encode = |value: BigUint| base64_encode(&value.to_bytes_be());
+      Ok(ExportedKey::Jwk(Jwk {
+        kty: "RSA".to_string(),
+        n: encode(n),
+        e: encode(e),
+        d: encode(d),
+        p: encode(p),
+        q: encode(q),
+        dp: encode(dp),
+        dq: encode(dq),
+        qi: encode(qi),
+        ..Default::default()
+      }))
+    }
+    _ => Err(invalid_access_error(
+      "Key can't be exported in the requested format",
+    )),
+  }
+}
+
+/// Describes the key material to JS: its type, and the key algorithm
+/// members that depend on it.
+pub fn key_info(key: &KeyMaterial) -> Value {
+  match key {
+    KeyMaterial::Secret(secret) => {
+      json!({ "type": "secret", "length": secret.len() * 8 })
+    }
+    KeyMaterial::EcPrivate { .. } => json!({ "type": "private" }),
+    KeyMaterial::EcPublic { .. } => json!({ "type": "public" }),
+    KeyMaterial::RsaPrivate(key) => json!({
+      "type": "private",
+      "modulusLength": key.n().bits(),
+      "publicExponent": key.e().to_bytes_be(),
+    }),
+    KeyMaterial::RsaPublic(key) => json!({
+      "type": "public",
+      "modulusLength": key.n().bits(),
+      "publicExponent": key.e().to_bytes_be(),
+    }),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn export_bytes(key: &KeyMaterial, format: KeyFormat) -> Vec<u8> {
+    match export_key(key, format).unwrap() {
+      ExportedKey::Bytes(bytes) => bytes,
+      ExportedKey::Jwk(_) => unreachable!(),
+    }
+  }
+
+  #[test]
+  fn ec_pkcs8_spki_round_trip() {
+    // A P-256 key pair generated with `openssl genpkey`.
+    let pkcs8 = base64::decode(
+      "MIGHAgEAMBMGByqGSM49AgEGCCqGSM49AwEHBG0wawIBAQQg2E4ZuOdJlxr8fbyh\
+       MwSI7mQiu1YNl7EZEfSznz0qbjKhRANCAARBrkl0gEKk50g4nkPpGKpgiYF4wGvF\
+       AuEbF9UeA2fQKaitHNWmQix/n7QBFcsk8+nMqf0goQYaZtp07BRNKWjy",
+    )
+    .unwrap();
+    let args = ImportKeyArgs {
+      format: KeyFormat::Pkcs8,
+      kind: KeyKind::Ec,
+      named_curve: Some(NamedCurve::P256),
+      jwk: None,
+    };
+    let private_key = import_key(&args, &pkcs8).unwrap();
+    let point = match &private_key {
+      KeyMaterial::EcPrivate { point, .. } => point.clone(),
+      _ => unreachable!(),
+    };
+    let public_key = KeyMaterial::EcPublic {
+      curve: NamedCurve::P256,
+      point,
+    };
+    let spki = export_bytes(&public_key, KeyFormat::Spki);
+    let args = ImportKeyArgs {
+      format: KeyFormat::Spki,
+      ..args
+    };
+    assert!(import_key(&args, &spki).is_ok());
+
+    // The exported document can be imported again, and by `ring`.
+    let exported = export_bytes(&private_key, KeyFormat::Pkcs8);
+    assert!(EcdsaKeyPair::from_pkcs8(
+      &signature::ECDSA_P256_SHA256_FIXED_SIGNING,
+      &exported
+    )
+    .is_ok());
+
+    let args = ImportKeyArgs {
+      format: KeyFormat::Pkcs8,
+      kind: KeyKind::Ec,
+      named_curve: Some(NamedCurve::P384),
+      jwk: None,
+    };
+    assert!(import_key(&args, &pkcs8).is_err());
+  }
+
+  #[test]
+  fn rsa_jwk_round_trip() {
+    let mut rng = rand::rngs::OsRng;
+    let key =
+      KeyMaterial::RsaPrivate(RSAPrivateKey::new(&mut rng, 512).unwrap());
+    let jwk = match export_key(&key, KeyFormat::Jwk).unwrap() {
+      ExportedKey::Jwk(jwk) => jwk,
+      ExportedKey::Bytes(_) => unreachable!(),
+    };
+    let args = ImportKeyArgs {
+      format: KeyFormat::Jwk,
+      kind: KeyKind::Rsa,
+      named_curve: None,
+      jwk: Some(jwk),
+    };
+    let imported = import_key(&args, &[]).unwrap();
+    assert_eq!(
+      export_bytes(&imported, KeyFormat::Pkcs8),
+      export_bytes(&key, KeyFormat::Pkcs8)
+    );
+    // The PKCS #8 document can be read by `rsa`.
+    let pkcs8 = export_bytes(&key, KeyFormat::Pkcs8);
+    assert!(RSAPrivateKey::from_pkcs8(&pkcs8).is_ok());
+  }
+}
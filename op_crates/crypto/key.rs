@@ -0,0 +1,464 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+//! Key material of `CryptoKey` objects. JS only holds the id of a key in the
+//! `CryptoKeyTable`, the material itself never leaves Rust.
+
+use crate::export::parse_ec_pkcs8;
+use deno_core::error::custom_error;
+use deno_core::error::type_error;
+use deno_core::error::AnyError;
+use rand::rngs::OsRng;
+use ring::digest;
+use ring::hmac;
+use ring::rand::SecureRandom;
+use ring::rand::SystemRandom;
+use ring::signature;
+use ring::signature::EcdsaKeyPair;
+use rsa::PaddingScheme;
+use rsa::PublicKey;
+use rsa::RSAPrivateKey;
+use rsa::RSAPublicKey;
+use serde::Deserialize;
+use sha2::digest::DynDigest;
+use sha2::Digest;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+pub fn operation_error(message: impl Into<String>) -> AnyError {
+  custom_error("DOMExceptionOperationError", message.into())
+}
+
+pub fn data_error(message: impl Into<String>) -> AnyError {
+  custom_error("DOMExceptionDataError", message.into())
+}
+
+pub fn invalid_access_error(message: impl Into<String>) -> AnyError {
+  custom_error("DOMExceptionInvalidAccessError", message.into())
+}
+
+pub fn not_supported_error(message: impl Into<String>) -> AnyError {
+  custom_error("DOMExceptionNotSupportedError", message.into())
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+pub enum HashAlgorithm {
+  #[serde(rename = "SHA-1")]
+  Sha1,
+  #[serde(rename = "SHA-256")]
+  Sha256,
+  #[serde(rename = "SHA-384")]
+  Sha384,
+  #[serde(rename = "SHA-512")]
+  Sha512,
+}
+
+impl HashAlgorithm {
+  pub fn digest_algorithm(self) -> &'static digest::Algorithm {
+    match self {
+      HashAlgorithm::Sha1 => &digest::SHA1_FOR_LEGACY_USE_ONLY,
+      HashAlgorithm::Sha256 => &digest::SHA256,
+      HashAlgorithm::Sha384 => &digest::SHA384,
+      HashAlgorithm::Sha512 => &digest::SHA512,
+    }
+  }
+
+  fn hmac_algorithm(self) -> hmac::Algorithm {
+    match self {
+      HashAlgorithm::Sha1 => hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY,
+      HashAlgorithm::Sha256 => hmac::HMAC_SHA256,
+      HashAlgorithm::Sha384 => hmac::HMAC_SHA384,
+      HashAlgorithm::Sha512 => hmac::HMAC_SHA512,
+    }
+  }
+
+  fn rsa_hash(self) -> rsa::Hash {
+    match self {
+      HashAlgorithm::Sha1 => rsa::Hash::SHA1,
+      HashAlgorithm::Sha256 => rsa::Hash::SHA2_256,
+      HashAlgorithm::Sha384 => rsa::Hash::SHA2_384,
+      HashAlgorithm::Sha512 => rsa::Hash::SHA2_512,
+    }
+  }
+
+  fn dyn_digest(self) -> Box<dyn DynDigest> {
+    match self {
+      HashAlgorithm::Sha1 => Box::new(sha1::Sha1::new()),
+      HashAlgorithm::Sha256 => Box::new(sha2::Sha256::new()),
+      HashAlgorithm::Sha384 => Box::new(sha2::Sha384::new()),
+      HashAlgorithm::Sha512 => Box::new(sha2::Sha512::new()),
+    }
+  }
+
+  fn pss_padding(self, salt_length: usize) -> PaddingScheme {
+    PaddingScheme::PSS {
+      salt_rng: Box::new(OsRng),
+      digest: self.dyn_digest(),
+      salt_len: Some(salt_length),
+    }
+  }
+
+  pub fn oaep_padding(self, label: Option<String>) -> PaddingScheme {
+    PaddingScheme::OAEP {
+      digest: self.dyn_digest(),
+      label,
+    }
+  }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+pub enum NamedCurve {
+  #[serde(rename = "P-256")]
+  P256,
+  #[serde(rename = "P-384")]
+  P384,
+}
+
+impl NamedCurve {
+  /// The length in bytes of private keys and of the coordinates of points.
+  pub fn scalar_length(self) -> usize {
+    match self {
+      NamedCurve::P256 => 32,
+      NamedCurve::P384 => 48,
+    }
+  }
+
+  fn signing_algorithm(
+    self,
+    hash: HashAlgorithm,
+  ) -> Result<&'static signature::EcdsaSigningAlgorithm, AnyError> {
+    match (self, hash) {
+      (NamedCurve::P256, HashAlgorithm::Sha256) => {
+        Ok(&signature::ECDSA_P256_SHA256_FIXED_SIGNING)
+      }
+      (NamedCurve::P384, HashAlgorithm::Sha384) => {
+        Ok(&signature::ECDSA_P384_SHA384_FIXED_SIGNING)
+      }
+      _ => Err(not_supported_error(
+        "ECDSA signing is only supported with SHA-256 on P-256 and \
+         SHA-384 on P-384",
+      )),
+    }
+  }
+
+  fn verification_algorithm(
+    self,
+    hash: HashAlgorithm,
+  ) -> Result<&'static signature::EcdsaVerificationAlgorithm, AnyError> {
+    match (self, hash) {
+      (NamedCurve::P256, HashAlgorithm::Sha256) => {
+        Ok(&signature::ECDSA_P256_SHA256_FIXED)
+      }
+      (NamedCurve::P256, HashAlgorithm::Sha384) => {
+        Ok(&signature::ECDSA_P256_SHA384_FIXED)
+      }
+      (NamedCurve::P384, HashAlgorithm::Sha256) => {
+        Ok(&signature::ECDSA_P384_SHA256_FIXED)
+      }
+      (NamedCurve::P384, HashAlgorithm::Sha384) => {
+        Ok(&signature::ECDSA_P384_SHA384_FIXED)
+      }
+      _ => Err(not_supported_error(
+        "ECDSA verification is only supported with SHA-256 and SHA-384",
+      )),
+    }
+  }
+}
+
+pub enum KeyMaterial {
+  Secret(Vec<u8>),
+  /// The private scalar and the public point.
+  EcPrivate {
+    curve: NamedCurve,
+    private_key: Vec<u8>,
+    point: Vec<u8>,
+  },
+  /// An uncompressed curve point.
+  EcPublic {
+    curve: NamedCurve,
+    point: Vec<u8>,
+  },
+  RsaPrivate(RSAPrivateKey),
+  RsaPublic(RSAPublicKey),
+}
+
+/// The key material of all `CryptoKey`s of an isolate, by id.
+///
+/// Keys have no `close()` in the Web Crypto API, so unlike resources they are
+/// dropped when their `CryptoKey` is garbage collected, and they are kept out
+/// of the resource table so that tests don't report them as leaks.
+#[derive(Default)]
+pub struct CryptoKeyTable {
+  next_id: u32,
+  keys: HashMap<u32, Arc<KeyMaterial>>,
+}
+
+impl CryptoKeyTable {
+  pub fn add(&mut self, key: KeyMaterial) -> u32 {
+    let id = self.next_id;
+    self.next_id += 1;
+    self.keys.insert(id, Arc::new(key));
+    id
+  }
+
+  pub fn get(&self, id: u32) -> Result<Arc<KeyMaterial>, AnyError> {
+    self
+      .keys
+      .get(&id)
+      .cloned()
+      .ok_or_else(|| type_error("Invalid CryptoKey"))
+  }
+
+  pub fn remove(&mut self, id: u32) {
+    self.keys.remove(&id);
+  }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerateKeyArgs {
+  pub name: String,
+  /// Length of an HMAC or AES key in bits.
+  pub length: Option<usize>,
+  pub named_curve: Option<NamedCurve>,
+  pub modulus_length: Option<usize>,
+}
+
+pub enum GeneratedKey {
+  Secret(KeyMaterial),
+  Pair {
+    private_key: KeyMaterial,
+    public_key: KeyMaterial,
+  },
+}
+
+pub fn generate_key(args: &GenerateKeyArgs) -> Result<GeneratedKey, AnyError> {
+  let rng = SystemRandom::new();
+  match args.name.as_str() {
+    "HMAC" => {
+      let length = args.length.ok_or_else(|| type_error("Missing length"))?;
+      if length == 0 || length % 8 != 0 {
+        return Err(operation_error(
+          "HMAC key length must be a non-zero multiple of 8",
+        ));
+      }
+      generate_secret(&rng, length)
+    }
+    "AES-GCM" | "AES-CBC" | "AES-CTR" => {
+      let length = args.length.ok_or_else(|| type_error("Missing length"))?;
+      if length != 128 && length != 192 && length != 256 {
+        return Err(operation_error(
+          "AES key length must be 128, 192 or 256 bits",
+        ));
+      }
+      generate_secret(&rng, length)
+    }
+    "ECDSA" | "ECDH" => {
+      let curve = args
+        .named_curve
+        .ok_or_else(|| type_error("Missing namedCurve"))?;
+      // The key pair only depends on the curve, not on the hash.
+      let algorithm = match curve {
+        NamedCurve::P256 => &signature::ECDSA_P256_SHA256_FIXED_SIGNING,
+        NamedCurve::P384 => &signature::ECDSA_P384_SHA384_FIXED_SIGNING,
+      };
+      let pkcs8 = EcdsaKeyPair::generate_pkcs8(algorithm, &rng)
+        .map_err(|_| operation_error("Failed to generate key"))?;
+      let (private_key, point) = parse_ec_pkcs8(pkcs8.as_ref(), curve)?;
+      Ok(GeneratedKey::Pair {
+        public_key: KeyMaterial::EcPublic {
+          curve,
+          point: point.clone(),
+        },
+        private_key: KeyMaterial::EcPrivate {
+          curve,
+          private_key,
+          point,
+        },
+      })
+    }
+    "RSASSA-PKCS1-v1_5" | "RSA-PSS" | "RSA-OAEP" => {
+      let modulus_length = args
+        .modulus_length
+        .ok_or_else(|| type_error("Missing modulusLength"))?;
+      let private_key = RSAPrivateKey::new(&mut OsRng, modulus_length)
+        .map_err(|err| operation_error(err.to_string()))?;
+      Ok(GeneratedKey::Pair {
+        public_key: KeyMaterial::RsaPublic(private_key.to_public_key()),
+        private_key: KeyMaterial::RsaPrivate(private_key),
+      })
+    }
+    name => Err(type_error(format!("Unsupported algorithm: {}", name))),
+  }
+}
+
+fn generate_secret(
+  rng: &SystemRandom,
+  length: usize,
+) -> Result<GeneratedKey, AnyError> {
+  let mut secret = vec![0; length / 8];
+  rng
+    .fill(&mut secret)
+    .map_err(|_| operation_error("Failed to generate key"))?;
+  Ok(GeneratedKey::Secret(KeyMaterial::Secret(secret)))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignArgs {
+  pub key: u32,
+  pub name: String,
+  pub hash: HashAlgorithm,
+  pub salt_length: Option<usize>,
+}
+
+fn salt_length(args: &SignArgs) -> Result<usize, AnyError> {
+  args
+    .salt_length
+    .ok_or_else(|| type_error("Missing saltLength"))
+}
+
+fn wrong_key_type(name: &str) -> AnyError {
+  type_error(format!("Key can't be used with {}", name))
+}
+
+pub fn sign(
+  key: &KeyMaterial,
+  args: &SignArgs,
+  data: &[u8],
+) -> Result<Vec<u8>, AnyError> {
+  match (args.name.as_str(), key) {
+    ("HMAC", KeyMaterial::Secret(secret)) => {
+      let key = hmac::Key::new(args.hash.hmac_algorithm(), secret);
+      Ok(hmac::sign(&key, data).as_ref().to_vec())
+    }
+    (
+      "ECDSA",
+      KeyMaterial::EcPrivate {
+        curve,
+        private_key,
+        point,
+      },
+    ) => {
+      let algorithm = curve.signing_algorithm(args.hash)?;
+      let pair = EcdsaKeyPair::from_private_key_and_public_key(
+        algorithm,
+        private_key,
+        point,
+      )
+      .map_err(|_| operation_error("Invalid ECDSA key"))?;
+      let signature = pair
+        .sign(&SystemRandom::new(), data)
+        .map_err(|_| operation_error("Failed to sign"))?;
+      Ok(signature.as_ref().to_vec())
+    }
+    ("RSASSA-PKCS1-v1_5", KeyMaterial::RsaPrivate(key)) => {
+      let hashed = digest::digest(args.hash.digest_algorithm(), data);
+      let padding =
+        PaddingScheme::new_pkcs1v15_sign(Some(args.hash.rsa_hash()));
+      key
+        .sign_blinded(&mut OsRng, padding, hashed.as_ref())
+        .map_err(|err| operation_error(err.to_string()))
+    }
+    ("RSA-PSS", KeyMaterial::RsaPrivate(key)) => {
+      let hashed = digest::digest(args.hash.digest_algorithm(), data);
+      let padding = args.hash.pss_padding(salt_length(args)?);
+      key
+        .sign_blinded(&mut OsRng, padding, hashed.as_ref())
+        .map_err(|err| operation_error(err.to_string()))
+    }
+    (name, _) => Err(wrong_key_type(name)),
+  }
+}
+
+pub fn verify(
+  key: &KeyMaterial,
+  args: &SignArgs,
+  data: &[u8],
+  signature: &[u8],
+) -> Result<bool, AnyError> {
+  match (args.name.as_str(), key) {
+    ("HMAC", KeyMaterial::Secret(secret)) => {
+      let key = hmac::Key::new(args.hash.hmac_algorithm(), secret);
+      Ok(hmac::verify(&key, data, signature).is_ok())
+    }
+    ("ECDSA", KeyMaterial::EcPublic { curve, point }) => {
+      let algorithm = curve.verification_algorithm(args.hash)?;
+      let public_key = signature::UnparsedPublicKey::new(algorithm, point);
+      Ok(public_key.verify(data, signature).is_ok())
+    }
+    ("RSASSA-PKCS1-v1_5", KeyMaterial::RsaPublic(key)) => {
+      let hashed = digest::digest(args.hash.digest_algorithm(), data);
+      let padding =
+        PaddingScheme::new_pkcs1v15_sign(Some(args.hash.rsa_hash()));
+      Ok(key.verify(padding, hashed.as_ref(), signature).is_ok())
+    }
+    ("RSA-PSS", KeyMaterial::RsaPublic(key)) => {
+      let hashed = digest::digest(args.hash.digest_algorithm(), data);
+      let padding = args.hash.pss_padding(salt_length(args)?);
+      Ok(key.verify(padding, hashed.as_ref(), signature).is_ok())
+    }
+    (name, _) => Err(wrong_key_type(name)),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn sign_args(name: &str, hash: HashAlgorithm) -> SignArgs {
+    SignArgs {
+      key: 0,
+      name: name.to_string(),
+      hash,
+      salt_length: Some(32),
+    }
+  }
+
+  #[test]
+  fn hmac_sign_verify() {
+    // RFC 4231, test case 2.
+    let key = KeyMaterial::Secret(b"Jefe".to_vec());
+    let args = sign_args("HMAC", HashAlgorithm::Sha256);
+    let data = b"what do ya want for nothing?";
+    let signature = sign(&key, &args, data).unwrap();
+    assert_eq!(
+      signature,
+      vec![
+        0x5b, 0xdc, 0xc1, 0x46, 0xbf, 0x60, 0x75, 0x4e, 0x6a, 0x04, 0x24, 0x26,
+        0x08, 0x95, 0x75, 0xc7, 0x5a, 0x00, 0x3f, 0x08, 0x9d, 0x27, 0x39, 0x83,
+        0x9d, 0xec, 0x58, 0xb9, 0x64, 0xec, 0x38, 0x43
+      ]
+    );
+    assert!(verify(&key, &args, data, &signature).unwrap());
+    assert!(!verify(&key, &args, b"something else", &signature).unwrap());
+  }
+
+  #[test]
+  fn ecdsa_sign_verify() {
+    let generated = generate_key(&GenerateKeyArgs {
+      name: "ECDSA".to_string(),
+      length: None,
+      named_curve: Some(NamedCurve::P256),
+      modulus_length: None,
+    })
+    .unwrap();
+    let (private_key, public_key) = match generated {
+      GeneratedKey::Pair {
+        private_key,
+        public_key,
+      } => (private_key, public_key),
+      GeneratedKey::Secret(_) => unreachable!(),
+    };
+    let args = sign_args("ECDSA", HashAlgorithm::Sha256);
+    let signature = sign(&private_key, &args, b"hello").unwrap();
+    assert_eq!(signature.len(), 64);
+    assert!(verify(&public_key, &args, b"hello", &signature).unwrap());
+    assert!(!verify(&public_key, &args, b"hellO", &signature).unwrap());
+    // The private key can't verify and the public key can't sign.
+    assert!(verify(&private_key, &args, b"hello", &signature).is_err());
+    assert!(sign(&public_key, &args, b"hello").is_err());
+    // ring can't sign with P-256 and SHA-384.
+    let args = sign_args("ECDSA", HashAlgorithm::Sha384);
+    assert!(sign(&private_key, &args, b"hello").is_err());
+  }
+}
@@ -0,0 +1,295 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+/// <reference no-default-lib="true" />
+/// <reference lib="esnext" />
+
+declare var crypto: Crypto;
+
+declare interface Crypto {
+  readonly subtle: SubtleCrypto;
+  getRandomValues<
+    T extends
+      | Int8Array
+      | Int16Array
+      | Int32Array
+      | Uint8Array
+      | Uint16Array
+      | Uint32Array
+      | Uint8ClampedArray
+      | Float32Array
+      | Float64Array
+      | DataView
+      | null,
+  >(
+    array: T,
+  ): T;
+}
+
+interface Algorithm {
+  name: string;
+}
+
+type AlgorithmIdentifier = string | Algorithm;
+type HashAlgorithmIdentifier = AlgorithmIdentifier;
+type KeyType = "private" | "public" | "secret";
+type KeyFormat = "jwk" | "pkcs8" | "raw" | "spki";
+type KeyUsage =
+  | "decrypt"
+  | "deriveBits"
+  | "deriveKey"
+  | "encrypt"
+  | "sign"
+  | "verify";
+type NamedCurve = string;
+
+interface KeyAlgorithm {
+  name: string;
+}
+
+interface HmacKeyGenParams extends Algorithm {
+  hash: HashAlgorithmIdentifier;
+  length?: number;
+}
+
+interface EcKeyGenParams extends Algorithm {
+  namedCurve: NamedCurve;
+}
+
+interface RsaHashedKeyGenParams extends Algorithm {
+  modulusLength: number;
+  publicExponent: Uint8Array;
+  hash: HashAlgorithmIdentifier;
+}
+
+interface AesKeyGenParams extends Algorithm {
+  length: number;
+}
+
+interface HmacImportParams extends Algorithm {
+  hash: HashAlgorithmIdentifier;
+  length?: number;
+}
+
+interface EcKeyImportParams extends Algorithm {
+  namedCurve: NamedCurve;
+}
+
+interface RsaHashedImportParams extends Algorithm {
+  hash: HashAlgorithmIdentifier;
+}
+
+interface AesGcmParams extends Algorithm {
+  iv: BufferSource;
+  additionalData?: BufferSource;
+  tagLength?: number;
+}
+
+interface AesCbcParams extends Algorithm {
+  iv: BufferSource;
+}
+
+interface AesCtrParams extends Algorithm {
+  counter: BufferSource;
+  length: number;
+}
+
+interface RsaOaepParams extends Algorithm {
+  label?: BufferSource;
+}
+
+interface Pbkdf2Params extends Algorithm {
+  hash: HashAlgorithmIdentifier;
+  iterations: number;
+  salt: BufferSource;
+}
+
+interface HkdfParams extends Algorithm {
+  hash: HashAlgorithmIdentifier;
+  info: BufferSource;
+  salt: BufferSource;
+}
+
+interface EcdhKeyDeriveParams extends Algorithm {
+  public: CryptoKey;
+}
+
+interface JsonWebKey {
+  alg?: string;
+  crv?: string;
+  d?: string;
+  dp?: string;
+  dq?: string;
+  e?: string;
+  ext?: boolean;
+  k?: string;
+  key_ops?: string[];
+  kty?: string;
+  n?: string;
+  p?: string;
+  q?: string;
+  qi?: string;
+  x?: string;
+  y?: string;
+}
+
+interface EcdsaParams extends Algorithm {
+  hash: HashAlgorithmIdentifier;
+}
+
+interface RsaPssParams extends Algorithm {
+  saltLength: number;
+}
+
+/** A key of the Web Crypto API. The key material is held by Deno and can't
+ * be read from the key itself. */
+interface CryptoKey {
+  readonly algorithm: KeyAlgorithm;
+  readonly extractable: boolean;
+  readonly type: KeyType;
+  readonly usages: KeyUsage[];
+}
+
+declare var CryptoKey: {
+  prototype: CryptoKey;
+  new (): never;
+};
+
+interface CryptoKeyPair {
+  privateKey: CryptoKey;
+  publicKey: CryptoKey;
+}
+
+/** The low-level cryptographic functions of the Web Crypto API, available as
+ * `crypto.subtle`. */
+interface SubtleCrypto {
+  /** Returns the digest of `data` as an `ArrayBuffer`. Supported algorithms
+   * are `"SHA-1"`, `"SHA-256"`, `"SHA-384"` and `"SHA-512"`.
+   *
+   *       const data = new TextEncoder().encode("hello");
+   *       const hash = await crypto.subtle.digest("SHA-256", data);
+   */
+  digest(
+    algorithm: HashAlgorithmIdentifier,
+    data: BufferSource,
+  ): Promise<ArrayBuffer>;
+  /** Generates a new key, or key pair for asymmetric algorithms. Supported
+   * algorithms are `"HMAC"`, `"AES-GCM"`, `"AES-CBC"`, `"AES-CTR"`,
+   * `"ECDSA"` and `"ECDH"` with the curves `"P-256"` and `"P-384"`, and
+   * `"RSASSA-PKCS1-v1_5"`, `"RSA-PSS"` and `"RSA-OAEP"` with the public
+   * exponent 65537.
+   *
+   *       const key = await crypto.subtle.generateKey(
+   *         { name: "HMAC", hash: "SHA-256" },
+   *         false,
+   *         ["sign", "verify"],
+   *       );
+   */
+  generateKey(
+    algorithm: RsaHashedKeyGenParams | EcKeyGenParams,
+    extractable: boolean,
+    keyUsages: KeyUsage[],
+  ): Promise<CryptoKeyPair>;
+  generateKey(
+    algorithm: HmacKeyGenParams | AesKeyGenParams,
+    extractable: boolean,
+    keyUsages: KeyUsage[],
+  ): Promise<CryptoKey>;
+  /** Returns the signature of `data`. ECDSA signatures are the concatenated
+   * r and s values, and can only be made with SHA-256 on P-256 and SHA-384 on
+   * P-384. */
+  sign(
+    algorithm: AlgorithmIdentifier | RsaPssParams | EcdsaParams,
+    key: CryptoKey,
+    data: BufferSource,
+  ): Promise<ArrayBuffer>;
+  /** Returns whether `signature` is a valid signature of `data`. */
+  verify(
+    algorithm: AlgorithmIdentifier | RsaPssParams | EcdsaParams,
+    key: CryptoKey,
+    signature: BufferSource,
+    data: BufferSource,
+  ): Promise<boolean>;
+  /** Returns the encryption of `data`. AES-GCM only supports 96 bit ivs and
+   * 128 bit tags, and RSA-OAEP labels must be valid UTF-8. */
+  encrypt(
+    algorithm: AesGcmParams | AesCbcParams | AesCtrParams | RsaOaepParams,
+    key: CryptoKey,
+    data: BufferSource,
+  ): Promise<ArrayBuffer>;
+  /** Returns the decryption of `data`, and rejects with an `OperationError`
+   * if it isn't authentic or correctly padded. */
+  decrypt(
+    algorithm: AesGcmParams | AesCbcParams | AesCtrParams | RsaOaepParams,
+    key: CryptoKey,
+    data: BufferSource,
+  ): Promise<ArrayBuffer>;
+  /** Returns `length` bits derived from `baseKey`. ECDH is only supported on
+   * P-256, and derives all bits of the shared secret when `length` is
+   * `null`.
+   *
+   *       const password = new TextEncoder().encode("password");
+   *       const salt = crypto.getRandomValues(new Uint8Array(16));
+   *       const key = await crypto.subtle.importKey(
+   *         "raw",
+   *         password,
+   *         "PBKDF2",
+   *         false,
+   *         ["deriveBits"],
+   *       );
+   *       const bits = await crypto.subtle.deriveBits(
+   *         { name: "PBKDF2", hash: "SHA-256", salt, iterations: 100000 },
+   *         key,
+   *         256,
+   *       );
+   */
+  deriveBits(
+    algorithm: Pbkdf2Params | HkdfParams | EcdhKeyDeriveParams,
+    baseKey: CryptoKey,
+    length: number | null,
+  ): Promise<ArrayBuffer>;
+  /** Derives an HMAC or AES key from `baseKey`. */
+  deriveKey(
+    algorithm: Pbkdf2Params | HkdfParams | EcdhKeyDeriveParams,
+    baseKey: CryptoKey,
+    derivedKeyType: HmacImportParams | AesKeyGenParams,
+    extractable: boolean,
+    keyUsages: KeyUsage[],
+  ): Promise<CryptoKey>;
+  /** Imports a key. Secret keys are imported from the `"raw"` or `"jwk"`
+   * formats, EC keys from all formats, where `"raw"` is an uncompressed
+   * public point, and RSA keys from the `"pkcs8"`, `"spki"` or `"jwk"`
+   * formats. */
+  importKey(
+    format: "jwk",
+    keyData: JsonWebKey,
+    algorithm:
+      | AlgorithmIdentifier
+      | HmacImportParams
+      | EcKeyImportParams
+      | RsaHashedImportParams,
+    extractable: boolean,
+    keyUsages: KeyUsage[],
+  ): Promise<CryptoKey>;
+  importKey(
+    format: Exclude<KeyFormat, "jwk">,
+    keyData: BufferSource,
+    algorithm:
+      | AlgorithmIdentifier
+      | HmacImportParams
+      | EcKeyImportParams
+      | RsaHashedImportParams,
+    extractable: boolean,
+    keyUsages: KeyUsage[],
+  ): Promise<CryptoKey>;
+  /** Exports an extractable key in the given format. */
+  exportKey(format: "jwk", key: CryptoKey): Promise<JsonWebKey>;
+  exportKey(
+    format: Exclude<KeyFormat, "jwk">,
+    key: CryptoKey,
+  ): Promise<ArrayBuffer>;
+}
+
+declare var SubtleCrypto: {
+  prototype: SubtleCrypto;
+  new (): never;
+};
@@ -2,18 +2,45 @@
 
 #![deny(warnings)]
 
+use cipher::EncryptArgs;
+use cipher::EncryptParams;
+use deno_core::error::type_error;
 use deno_core::error::AnyError;
+use deno_core::serde_json;
 use deno_core::serde_json::json;
 use deno_core::serde_json::Value;
+use deno_core::BufVec;
 use deno_core::JsRuntime;
 use deno_core::OpState;
 use deno_core::ZeroCopyBuf;
+use derive::DeriveBitsArgs;
+use derive::DeriveParams;
+use export::ExportedKey;
+use export::ImportKeyArgs;
+use export::KeyFormat;
+use key::GenerateKeyArgs;
+use key::GeneratedKey;
+use key::HashAlgorithm;
+use key::SignArgs;
 use rand::rngs::StdRng;
 use rand::thread_rng;
 use rand::Rng;
+use ring::digest;
+use serde::Deserialize;
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
 
 pub use rand; // Re-export rand
 
+mod cipher;
+mod der;
+mod derive;
+mod export;
+mod key;
+
+pub use key::CryptoKeyTable;
+
 /// Execute this crates' JS source files.
 pub fn init(isolate: &mut JsRuntime) {
   let files = vec![(
@@ -41,3 +68,251 @@ pub fn op_get_random_values(
 
   Ok(json!({}))
 }
+
+#[derive(Deserialize)]
+struct DigestArgs {
+  algorithm: HashAlgorithm,
+}
+
+/// Hashes the first buffer into the second one, which must have the length of
+/// the algorithm's output. The input is copied before hashing on a blocking
+/// thread, so JS may change it as soon as the op was called.
+pub async fn op_crypto_subtle_digest(
+  _state: Rc<RefCell<OpState>>,
+  args: Value,
+  zero_copy: BufVec,
+) -> Result<Value, AnyError> {
+  let args: DigestArgs = serde_json::from_value(args)?;
+  let algorithm = args.algorithm.digest_algorithm();
+  assert_eq!(zero_copy.len(), 2);
+  let mut zero_copy = zero_copy.into_iter();
+  let data = zero_copy.next().unwrap().to_vec();
+  let mut output = zero_copy.next().unwrap();
+  if output.len() != algorithm.output_len {
+    return Err(type_error("Output buffer has the wrong length"));
+  }
+
+  let digest =
+    tokio::task::spawn_blocking(move || digest::digest(algorithm, &data))
+      .await?;
+  output.copy_from_slice(digest.as_ref());
+
+  Ok(json!({}))
+}
+
+/// Generates a key or key pair and returns the ids of its material in the
+/// `CryptoKeyTable`.
+pub async fn op_crypto_generate_key(
+  state: Rc<RefCell<OpState>>,
+  args: Value,
+  _zero_copy: BufVec,
+) -> Result<Value, AnyError> {
+  let args: GenerateKeyArgs = serde_json::from_value(args)?;
+  let generated =
+    tokio::task::spawn_blocking(move || key::generate_key(&args)).await??;
+
+  let mut state = state.borrow_mut();
+  let key_table = state.borrow_mut::<CryptoKeyTable>();
+  match generated {
+    GeneratedKey::Secret(secret_key) => {
+      Ok(json!({ "secretKey": key_table.add(secret_key) }))
+    }
+    GeneratedKey::Pair {
+      private_key,
+      public_key,
+    } => Ok(json!({
+      "privateKey": key_table.add(private_key),
+      "publicKey": key_table.add(public_key),
+    })),
+  }
+}
+
+/// Signs the first buffer into the second one, which must have the length of
+/// the signature.
+pub async fn op_crypto_sign(
+  state: Rc<RefCell<OpState>>,
+  args: Value,
+  zero_copy: BufVec,
+) -> Result<Value, AnyError> {
+  let args: SignArgs = serde_json::from_value(args)?;
+  let key = state.borrow().borrow::<CryptoKeyTable>().get(args.key)?;
+  assert_eq!(zero_copy.len(), 2);
+  let mut zero_copy = zero_copy.into_iter();
+  let data = zero_copy.next().unwrap().to_vec();
+  let mut output = zero_copy.next().unwrap();
+
+  let signature =
+    tokio::task::spawn_blocking(move || key::sign(&key, &args, &data))
+      .await??;
+  if output.len() != signature.len() {
+    return Err(type_error("Output buffer has the wrong length"));
+  }
+  output.copy_from_slice(&signature);
+  Ok(json!({}))
+}
+
+/// Checks the signature in the second buffer against the data in the first.
+pub async fn op_crypto_verify(
+  state: Rc<RefCell<OpState>>,
+  args: Value,
+  zero_copy: BufVec,
+) -> Result<Value, AnyError> {
+  let args: SignArgs = serde_json::from_value(args)?;
+  let key = state.borrow().borrow::<CryptoKeyTable>().get(args.key)?;
+  assert_eq!(zero_copy.len(), 2);
+  let data = zero_copy[0].to_vec();
+  let signature = zero_copy[1].to_vec();
+
+  let valid = tokio::task::spawn_blocking(move || {
+    key::verify(&key, &args, &data, &signature)
+  })
+  .await??;
+  Ok(json!({ "valid": valid }))
+}
+
+/// Encrypts or decrypts the first buffer into the second one, which JS
+/// allocates with the largest possible output length, and returns the length
+/// actually written. The third buffer is the iv, counter block or label, and
+/// the fourth the AES-GCM additional data; both may be empty.
+async fn crypt(
+  state: Rc<RefCell<OpState>>,
+  args: Value,
+  zero_copy: BufVec,
+  encrypt: bool,
+) -> Result<Value, AnyError> {
+  let args: EncryptArgs = serde_json::from_value(args)?;
+  let key = state.borrow().borrow::<CryptoKeyTable>().get(args.key)?;
+  assert_eq!(zero_copy.len(), 4);
+  let mut zero_copy = zero_copy.into_iter();
+  let data = zero_copy.next().unwrap().to_vec();
+  let mut output = zero_copy.next().unwrap();
+  let params = EncryptParams {
+    iv: zero_copy.next().unwrap().to_vec(),
+    additional_data: zero_copy.next().unwrap().to_vec(),
+  };
+
+  let result = tokio::task::spawn_blocking(move || {
+    if encrypt {
+      cipher::encrypt(&key, &args, &params, &data)
+    } else {
+      cipher::decrypt(&key, &args, &params, &data)
+    }
+  })
+  .await??;
+  if output.len() < result.len() {
+    return Err(type_error("Output buffer is too small"));
+  }
+  output[..result.len()].copy_from_slice(&result);
+  Ok(json!({ "length": result.len() }))
+}
+
+pub async fn op_crypto_encrypt(
+  state: Rc<RefCell<OpState>>,
+  args: Value,
+  zero_copy: BufVec,
+) -> Result<Value, AnyError> {
+  crypt(state, args, zero_copy, true).await
+}
+
+pub async fn op_crypto_decrypt(
+  state: Rc<RefCell<OpState>>,
+  args: Value,
+  zero_copy: BufVec,
+) -> Result<Value, AnyError> {
+  crypt(state, args, zero_copy, false).await
+}
+
+/// Imports the key material in the buffer, or in the `jwk` argument, and
+/// returns its id along with the key algorithm members it determines.
+pub fn op_crypto_import_key(
+  state: &mut OpState,
+  args: Value,
+  zero_copy: &mut [ZeroCopyBuf],
+) -> Result<Value, AnyError> {
+  let args: ImportKeyArgs = serde_json::from_value(args)?;
+  assert_eq!(zero_copy.len(), 1);
+  let key = export::import_key(&args, &zero_copy[0])?;
+  let mut info = export::key_info(&key);
+  info["key"] = json!(state.borrow_mut::<CryptoKeyTable>().add(key));
+  Ok(info)
+}
+
+#[derive(Deserialize)]
+struct ExportKeyArgs {
+  key: u32,
+  format: KeyFormat,
+}
+
+pub fn op_crypto_export_key(
+  state: &mut OpState,
+  args: Value,
+  _zero_copy: &mut [ZeroCopyBuf],
+) -> Result<Value, AnyError> {
+  let args: ExportKeyArgs = serde_json::from_value(args)?;
+  let key = state.borrow::<CryptoKeyTable>().get(args.key)?;
+  match export::export_key(&key, args.format)? {
+    ExportedKey::Bytes(data) => Ok(json!({ "data": data })),
+    ExportedKey::Jwk(jwk) => Ok(json!({ "jwk": jwk })),
+  }
+}
+
+/// Derives bits into the last buffer. PBKDF2 takes the salt before it, and
+/// HKDF the salt and the info.
+pub async fn op_crypto_derive_bits(
+  state: Rc<RefCell<OpState>>,
+  args: Value,
+  zero_copy: BufVec,
+) -> Result<Value, AnyError> {
+  let args: DeriveBitsArgs = serde_json::from_value(args)?;
+  let (key, public_key) = {
+    let state = state.borrow();
+    let key_table = state.borrow::<CryptoKeyTable>();
+    let public_key = match args.public_key {
+      Some(id) => Some(key_table.get(id)?),
+      None => None,
+    };
+    (key_table.get(args.key)?, public_key)
+  };
+  let mut zero_copy = zero_copy.into_iter();
+  let mut output = zero_copy.next_back().unwrap();
+  let params = DeriveParams {
+    salt: zero_copy.next().map(|buf| buf.to_vec()).unwrap_or_default(),
+    info: zero_copy.next().map(|buf| buf.to_vec()).unwrap_or_default(),
+  };
+
+  let length = output.len();
+  let derived = tokio::task::spawn_blocking(move || {
+    let mut derived = vec![0; length];
+    derive::derive_bits(
+      &key,
+      public_key.as_deref(),
+      &args,
+      &params,
+      &mut derived,
+    )
+    .map(|_| derived)
+  })
+  .await??;
+  output.copy_from_slice(&derived);
+  Ok(json!({}))
+}
+
+#[derive(Deserialize)]
+struct DropKeyArgs {
+  key: u32,
+}
+
+/// Drops the material of a garbage collected `CryptoKey`.
+pub fn op_crypto_drop_key(
+  state: &mut OpState,
+  args: Value,
+  _zero_copy: &mut [ZeroCopyBuf],
+) -> Result<Value, AnyError> {
+  let args: DropKeyArgs = serde_json::from_value(args)?;
+  state.borrow_mut::<CryptoKeyTable>().remove(args.key);
+  Ok(json!({}))
+}
+
+pub fn get_declaration() -> PathBuf {
+  PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("lib.deno_crypto.d.ts")
+}
@@ -1233,6 +1233,35 @@
     return await opFetchSend({ rid: requestRid });
   }
 
+  /** Returns the bytes of a blob, without copying them. */
+  function getBlobBytes(blob) {
+    return blob[bytesSymbol];
+  }
+
+  /** Fetches a URL created with `URL.createObjectURL()`, which can only be
+   * read with `GET` requests. */
+  function fetchBlobUrl(url, method) {
+    if (method != null && method.toUpperCase() !== "GET") {
+      throw new TypeError(`Method "${method}" is not allowed for blob URLs`);
+    }
+    const blob = core.jsonOpSync("op_resolve_object_url", { url });
+    if (blob === null) {
+      throw new TypeError(`Blob URL not found: ${url}`);
+    }
+    const body = new Uint8Array(blob.size);
+    core.jsonOpSync("op_read_object_url", { url }, body);
+    const responseInit = {
+      status: 200,
+      statusText: "OK",
+      headers: [
+        ["content-type", blob.type],
+        ["content-length", String(blob.size)],
+      ],
+    };
+    responseData.set(responseInit, { redirected: false, status: 200, url });
+    return new Response(body, responseInit);
+  }
+
   async function fetch(input, init) {
     let url;
     let method = null;
@@ -1309,6 +1338,10 @@
       }
     }
 
+    if (url.startsWith("blob:")) {
+      return fetchBlobUrl(url, method);
+    }
+
     let responseBody;
     let responseInit = {};
     while (remRedirectCount) {
@@ -1431,6 +1464,7 @@
     FormData,
     setBaseUrl,
     fetch,
+    getBlobBytes,
     Request,
     Response,
     HttpClient,
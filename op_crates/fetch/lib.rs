@@ -23,6 +23,7 @@ use deno_core::RcRef;
 use deno_core::Resource;
 use deno_core::ZeroCopyBuf;
 
+use reqwest::header::HeaderMap;
 use reqwest::header::HeaderName;
 use reqwest::header::HeaderValue;
 use reqwest::redirect::Policy;
@@ -39,6 +40,7 @@ use std::io::Read;
 use std::path::PathBuf;
 use std::pin::Pin;
 use std::rc::Rc;
+use std::time::Duration;
 use tokio::io::AsyncReadExt;
 use tokio::sync::mpsc;
 use tokio_stream::wrappers::ReceiverStream;
@@ -46,6 +48,8 @@ use tokio_util::io::StreamReader;
 
 pub use reqwest; // Re-export reqwest
 
+mod unix;
+
 /// Execute this crates' JS source files.
 pub fn init(isolate: &mut JsRuntime) {
   let files = vec![
@@ -78,6 +82,7 @@ pub fn init(isolate: &mut JsRuntime) {
 pub trait FetchPermissions {
   fn check_net_url(&self, _url: &Url) -> Result<(), AnyError>;
   fn check_read(&self, _p: &PathBuf) -> Result<(), AnyError>;
+  fn check_write(&self, _p: &PathBuf) -> Result<(), AnyError>;
 }
 
 /// For use with `op_fetch` when the user does not want permissions.
@@ -91,6 +96,10 @@ impl FetchPermissions for NoFetchPermissions {
   fn check_read(&self, _p: &PathBuf) -> Result<(), AnyError> {
     Ok(())
   }
+
+  fn check_write(&self, _p: &PathBuf) -> Result<(), AnyError> {
+    Ok(())
+  }
 }
 
 pub fn get_declaration() -> PathBuf {
@@ -118,15 +127,16 @@ where
 
   let args: FetchArgs = serde_json::from_value(args)?;
 
-  let client = if let Some(rid) = args.client_rid {
+  let (client, read_timeout, client_socket) = if let Some(rid) = args.client_rid
+  {
     let r = state
       .resource_table
       .get::<HttpClientResource>(rid)
       .ok_or_else(bad_resource_id)?;
-    r.client.clone()
+    (r.client.clone(), r.read_timeout, r.unix_socket.clone())
   } else {
     let client = state.borrow::<reqwest::Client>();
-    client.clone()
+    (client.clone(), None, None)
   };
 
   let method = match args.method {
@@ -143,22 +153,36 @@ where
     .parse(&args.url)?;
 
   // Check scheme before asking for net permission
-  let scheme = url.scheme();
-  if scheme != "http" && scheme != "https" {
-    return Err(type_error(format!("scheme '{}' not supported", scheme)));
-  }
+  let socket = match url.scheme() {
+    "http+unix" => Some(unix::socket_path(&url)?),
+    "http" => client_socket,
+    "https" if client_socket.is_some() => {
+      return Err(type_error("HTTPS is not supported over Unix sockets"))
+    }
+    "https" => None,
+    scheme => {
+      return Err(type_error(format!("scheme '{}' not supported", scheme)))
+    }
+  };
 
+  // Requests over a Unix socket need access to its path instead of the
+  // network.
   let permissions = state.borrow::<FP>();
-  permissions.check_net_url(&url)?;
-
-  let mut request = client.request(method, url);
+  match &socket {
+    Some(socket) => {
+      permissions.check_read(socket)?;
+      permissions.check_write(socket)?;
+    }
+    None => permissions.check_net_url(&url)?,
+  }
 
+  let mut body = None;
   let maybe_request_body_rid = if args.has_body {
     match data.len() {
       0 => {
         // If no body is passed, we return a writer for streaming the body.
         let (tx, rx) = mpsc::channel::<std::io::Result<Vec<u8>>>(1);
-        request = request.body(Body::wrap_stream(ReceiverStream::new(rx)));
+        body = Some(RequestBody::Stream(ReceiverStream::new(rx)));
 
         let request_body_rid =
           state.resource_table.add(FetchRequestBodyResource {
@@ -170,7 +194,7 @@ where
       }
       1 => {
         // If a body is passed, we use it, and don't return a body for streaming.
-        request = request.body(Vec::from(&*data[0]));
+        body = Some(RequestBody::Bytes(Vec::from(&*data[0])));
         None
       }
       _ => panic!("Invalid number of arguments"),
@@ -179,17 +203,35 @@ where
     None
   };
 
+  let mut headers = HeaderMap::new();
   for (key, value) in args.headers {
     let name = HeaderName::from_bytes(key.as_bytes()).unwrap();
     let v = HeaderValue::from_str(&value).unwrap();
-    request = request.header(name, v);
+    headers.append(name, v);
   }
 
-  let fut = request.send();
+  let future = match socket {
+    Some(socket) => unix::request(socket, method, &url, headers, body)?,
+    None => {
+      let mut request = client.request(method, url).headers(headers);
+      request = match body {
+        Some(RequestBody::Bytes(bytes)) => request.body(bytes),
+        Some(RequestBody::Stream(stream)) => {
+          request.body(Body::wrap_stream(stream))
+        }
+        None => request,
+      };
+      let future = request.send();
+      Box::pin(
+        async move { future.await.map_err(|e| type_error(e.to_string())) },
+      ) as ResponseFuture
+    }
+  };
 
-  let request_rid = state
-    .resource_table
-    .add(FetchRequestResource(Box::pin(fut)));
+  let request_rid = state.resource_table.add(FetchRequestResource {
+    future,
+    read_timeout,
+  });
 
   Ok(json!({
     "requestRid": request_rid,
@@ -220,10 +262,13 @@ pub async fn op_fetch_send(
     .ok()
     .expect("multiple op_fetch_send ongoing");
 
-  let res = match request.0.await {
-    Ok(res) => res,
-    Err(e) => return Err(type_error(e.to_string())),
+  let res = match request.read_timeout {
+    Some(read_timeout) => tokio::time::timeout(read_timeout, request.future)
+      .await
+      .map_err(|_| timed_out())?,
+    None => request.future.await,
   };
+  let res = res?;
 
   //debug!("Fetch response {}", url);
   let status = res.status();
@@ -255,6 +300,7 @@ pub async fn op_fetch_send(
     .add(FetchResponseBodyResource {
       reader: AsyncRefCell::new(stream_reader),
       cancel: CancelHandle::default(),
+      read_timeout: request.read_timeout,
     });
 
   Ok(json!({
@@ -319,16 +365,37 @@ pub async fn op_fetch_response_read(
     .resource_table
     .get::<FetchResponseBodyResource>(rid as u32)
     .ok_or_else(bad_resource_id)?;
+  let read_timeout = resource.read_timeout;
   let mut reader = RcRef::map(&resource, |r| &r.reader).borrow_mut().await;
   let cancel = RcRef::map(resource, |r| &r.cancel);
   let mut buf = data[0].clone();
-  let read = reader.read(&mut buf).try_or_cancel(cancel).await?;
+  let read = async {
+    match read_timeout {
+      Some(read_timeout) => {
+        tokio::time::timeout(read_timeout, reader.read(&mut buf))
+          .await
+          .unwrap_or_else(|_| Err(timed_out()))
+      }
+      None => reader.read(&mut buf).await,
+    }
+  };
+  let read = read.try_or_cancel(cancel).await?;
   Ok(json!({ "read": read }))
 }
 
-struct FetchRequestResource(
-  Pin<Box<dyn Future<Output = Result<Response, reqwest::Error>>>>,
-);
+/// The body of a request, which is either passed whole to `op_fetch` or
+/// streamed through `op_fetch_request_write`.
+enum RequestBody {
+  Bytes(Vec<u8>),
+  Stream(ReceiverStream<std::io::Result<Vec<u8>>>),
+}
+
+type ResponseFuture = Pin<Box<dyn Future<Output = Result<Response, AnyError>>>>;
+
+struct FetchRequestResource {
+  future: ResponseFuture,
+  read_timeout: Option<Duration>,
+}
 
 impl Resource for FetchRequestResource {
   fn name(&self) -> Cow<str> {
@@ -353,6 +420,17 @@ type BytesStream =
 struct FetchResponseBodyResource {
   reader: AsyncRefCell<StreamReader<BytesStream, bytes::Bytes>>,
   cancel: CancelHandle,
+  /// The longest time to wait for a chunk of the body.
+  read_timeout: Option<Duration>,
+}
+
+/// The error of a response whose headers or body chunks don't arrive within
+/// the read timeout of its client.
+fn timed_out() -> std::io::Error {
+  std::io::Error::new(
+    std::io::ErrorKind::TimedOut,
+    "The server didn't respond within the read timeout",
+  )
 }
 
 impl Resource for FetchResponseBodyResource {
@@ -363,6 +441,11 @@ impl Resource for FetchResponseBodyResource {
 
 struct HttpClientResource {
   client: Client,
+  /// The longest time to wait for the response headers, and for each chunk
+  /// of the response body.
+  read_timeout: Option<Duration>,
+  /// The socket that the requests of `http:` URLs are sent over.
+  unix_socket: Option<PathBuf>,
 }
 
 impl Resource for HttpClientResource {
@@ -372,11 +455,44 @@ impl Resource for HttpClientResource {
 }
 
 impl HttpClientResource {
-  fn new(client: Client) -> Self {
-    Self { client }
+  fn new(
+    client: Client,
+    read_timeout: Option<Duration>,
+    unix_socket: Option<PathBuf>,
+  ) -> Self {
+    Self {
+      client,
+      read_timeout,
+      unix_socket,
+    }
   }
 }
 
+#[derive(Deserialize, Default, Debug)]
+#[serde(rename_all = "camelCase")]
+#[serde(default)]
+struct CreateHttpClientOptions {
+  ca_file: Option<String>,
+  ca_data: Option<String>,
+  /// The timeouts are in milliseconds.
+  connect_timeout: Option<f64>,
+  read_timeout: Option<f64>,
+  pool_max_idle_per_host: Option<f64>,
+  pool_idle_timeout: Option<f64>,
+  http1_only: bool,
+  http2_prior_knowledge: bool,
+  user_agent: Option<String>,
+  decompress: Option<bool>,
+  /// The client certificate chain and private key, each given either as PEM
+  /// or as the path of a PEM file.
+  cert_chain: Option<String>,
+  cert_chain_file: Option<String>,
+  private_key: Option<String>,
+  private_key_file: Option<String>,
+  /// The path of a Unix socket to send the requests of `http:` URLs over.
+  unix_socket: Option<String>,
+}
+
 pub fn op_create_http_client<FP>(
   state: &mut OpState,
   args: Value,
@@ -385,46 +501,133 @@ pub fn op_create_http_client<FP>(
 where
   FP: FetchPermissions + 'static,
 {
-  #[derive(Deserialize, Default, Debug)]
-  #[serde(rename_all = "camelCase")]
-  #[serde(default)]
-  struct CreateHttpClientOptions {
-    ca_file: Option<String>,
-    ca_data: Option<String>,
-  }
-
   let args: CreateHttpClientOptions = serde_json::from_value(args)?;
 
-  if let Some(ca_file) = args.ca_file.clone() {
-    let permissions = state.borrow::<FP>();
-    permissions.check_read(&PathBuf::from(ca_file))?;
+  let permissions = state.borrow::<FP>();
+  for path in args
+    .ca_file
+    .iter()
+    .chain(&args.cert_chain_file)
+    .chain(&args.private_key_file)
+  {
+    permissions.check_read(&PathBuf::from(path))?;
   }
 
-  let client =
-    create_http_client(args.ca_file.as_deref(), args.ca_data.as_deref())
-      .unwrap();
+  let read_timeout = duration("readTimeout", args.read_timeout)?;
+  let client = create_http_client(&args)?;
+  let unix_socket = args.unix_socket.map(PathBuf::from);
 
-  let rid = state.resource_table.add(HttpClientResource::new(client));
+  let rid = state.resource_table.add(HttpClientResource::new(
+    client,
+    read_timeout,
+    unix_socket,
+  ));
   Ok(json!(rid))
 }
 
+/// Reads a PEM option that is given either inline or as the path of a file.
+fn read_pem(
+  data: &Option<String>,
+  path: &Option<String>,
+) -> Result<Option<Vec<u8>>, AnyError> {
+  match (data, path) {
+    (Some(data), _) => Ok(Some(data.as_bytes().to_vec())),
+    (None, Some(path)) => {
+      let mut buf = Vec::new();
+      File::open(path)?.read_to_end(&mut buf)?;
+      Ok(Some(buf))
+    }
+    (None, None) => Ok(None),
+  }
+}
+
+/// Converts a timeout option from milliseconds.
+fn duration(
+  name: &str,
+  millis: Option<f64>,
+) -> Result<Option<Duration>, AnyError> {
+  match millis {
+    Some(millis) if !millis.is_finite() || millis <= 0.0 => Err(type_error(
+      format!("{} must be a positive number of milliseconds", name),
+    )),
+    Some(millis) => Ok(Some(Duration::from_secs_f64(millis / 1000.0))),
+    None => Ok(None),
+  }
+}
+
 /// Create new instance of async reqwest::Client. This client supports
 /// proxies and doesn't follow redirects.
 fn create_http_client(
-  ca_file: Option<&str>,
-  ca_data: Option<&str>,
+  options: &CreateHttpClientOptions,
 ) -> Result<Client, AnyError> {
   let mut builder = Client::builder().redirect(Policy::none()).use_rustls_tls();
-  if let Some(ca_data) = ca_data {
+  if let Some(ca_data) = &options.ca_data {
     let ca_data_vec = ca_data.as_bytes().to_vec();
     let cert = reqwest::Certificate::from_pem(&ca_data_vec)?;
     builder = builder.add_root_certificate(cert);
-  } else if let Some(ca_file) = ca_file {
+  } else if let Some(ca_file) = &options.ca_file {
     let mut buf = Vec::new();
     File::open(ca_file)?.read_to_end(&mut buf)?;
     let cert = reqwest::Certificate::from_pem(&buf)?;
     builder = builder.add_root_certificate(cert);
   }
+
+  let cert_chain = read_pem(&options.cert_chain, &options.cert_chain_file)?;
+  let private_key = read_pem(&options.private_key, &options.private_key_file)?;
+  match (cert_chain, private_key) {
+    (Some(cert_chain), Some(mut pem)) => {
+      // `Identity::from_pem()` takes the key and the chain in one input.
+      pem.push(b'\n');
+      pem.extend(cert_chain);
+      let identity = reqwest::Identity::from_pem(&pem).map_err(|err| {
+        type_error(format!("Invalid client certificate: {}", err))
+      })?;
+      builder = builder.identity(identity);
+    }
+    (None, None) => {}
+    _ => {
+      return Err(type_error(
+        "A client certificate chain needs a private key, and vice versa",
+      ))
+    }
+  }
+
+  if let Some(timeout) = duration("connectTimeout", options.connect_timeout)? {
+    builder = builder.connect_timeout(timeout);
+  }
+  if let Some(timeout) = duration("poolIdleTimeout", options.pool_idle_timeout)?
+  {
+    builder = builder.pool_idle_timeout(timeout);
+  }
+  if let Some(max_idle) = options.pool_max_idle_per_host {
+    if max_idle.fract() != 0.0 || !(0.0..=u32::MAX as f64).contains(&max_idle) {
+      return Err(type_error(
+        "poolMaxIdlePerHost must be a non-negative integer",
+      ));
+    }
+    builder = builder.pool_max_idle_per_host(max_idle as usize);
+  }
+
+  match (options.http1_only, options.http2_prior_knowledge) {
+    (true, true) => {
+      return Err(type_error(
+        "http1Only and http2PriorKnowledge can't be used together",
+      ))
+    }
+    (true, false) => builder = builder.http1_only(),
+    (false, true) => builder = builder.http2_prior_knowledge(),
+    (false, false) => {}
+  }
+
+  if let Some(user_agent) = &options.user_agent {
+    let user_agent = HeaderValue::from_str(user_agent)
+      .map_err(|_| type_error("userAgent is not a valid header value"))?;
+    builder = builder.user_agent(user_agent);
+  }
+  if options.decompress == Some(false) {
+    builder = builder.no_gzip().no_brotli();
+  }
+
   builder
     .build()
     .map_err(|_| deno_core::error::generic_error("Unable to build http client"))
@@ -0,0 +1,190 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+//! Requests over Unix domain sockets, which reqwest doesn't support, so they
+//! are sent with hyper directly.
+
+use crate::RequestBody;
+use crate::ResponseFuture;
+use deno_core::error::type_error;
+use deno_core::error::AnyError;
+use deno_core::url::Url;
+use percent_encoding::percent_decode_str;
+use reqwest::header::HeaderMap;
+use reqwest::Method;
+use std::path::PathBuf;
+
+/// The path of the socket of a `http+unix:` URL, which is given as its
+/// percent-encoded host, like in `http+unix://%2Fvar%2Frun%2Fdocker.sock/info`.
+pub fn socket_path(url: &Url) -> Result<PathBuf, AnyError> {
+  let host = url.host_str().unwrap_or("");
+  if host.is_empty() {
+    return Err(type_error(
+      "A http+unix: URL needs the percent-encoded socket path as its host",
+    ));
+  }
+  let path = percent_decode_str(host)
+    .decode_utf8()
+    .map_err(|_| type_error("The socket path is not valid UTF-8"))?;
+  Ok(PathBuf::from(path.as_ref()))
+}
+
+#[cfg(unix)]
+pub fn request(
+  socket: PathBuf,
+  method: Method,
+  url: &Url,
+  headers: HeaderMap,
+  body: Option<RequestBody>,
+) -> Result<ResponseFuture, AnyError> {
+  use connector::UnixConnector;
+  use deno_core::futures::FutureExt;
+  use deno_core::url::Position;
+  use reqwest::header::HeaderValue;
+  use reqwest::header::HOST;
+
+  let body = match body {
+    Some(RequestBody::Bytes(bytes)) => hyper::Body::from(bytes),
+    Some(RequestBody::Stream(stream)) => hyper::Body::wrap_stream(stream),
+    None => hyper::Body::empty(),
+  };
+  let mut request = hyper::Request::new(body);
+  *request.method_mut() = method;
+  // hyper's client only takes absolute-form URIs, and writes origin-form
+  // ones to the connection itself, so the authority here is only a dummy.
+  *request.uri_mut() = format!(
+    "http://localhost{}",
+    &url[Position::BeforePath..Position::AfterQuery]
+  )
+  .parse()?;
+  *request.headers_mut() = headers;
+  if !request.headers().contains_key(HOST) {
+    // The host of a `http+unix:` URL is the socket path.
+    let host = if url.scheme() == "http+unix" {
+      "localhost"
+    } else {
+      &url[Position::BeforeHost..Position::AfterPort]
+    };
+    request
+      .headers_mut()
+      .insert(HOST, HeaderValue::from_str(host)?);
+  }
+
+  let client = hyper::Client::builder().build(UnixConnector(socket));
+  let future = client.request(request).map(|result| {
+    let response = result.map_err(|err| type_error(err.to_string()))?;
+    Ok(response.map(reqwest::Body::wrap_stream).into())
+  });
+  Ok(Box::pin(future))
+}
+
+#[cfg(not(unix))]
+pub fn request(
+  _socket: PathBuf,
+  _method: Method,
+  _url: &Url,
+  _headers: HeaderMap,
+  _body: Option<RequestBody>,
+) -> Result<ResponseFuture, AnyError> {
+  Err(type_error(
+    "Unix sockets are not supported on this platform",
+  ))
+}
+
+#[cfg(unix)]
+mod connector {
+  use deno_core::futures::Future;
+  use hyper::client::connect::Connected;
+  use hyper::client::connect::Connection;
+  use hyper::service::Service;
+  use hyper::Uri;
+  use std::io;
+  use std::path::PathBuf;
+  use std::pin::Pin;
+  use std::task::Context;
+  use std::task::Poll;
+  use tokio::io::AsyncRead;
+  use tokio::io::AsyncWrite;
+  use tokio::io::ReadBuf;
+  use tokio::net::UnixStream;
+
+  /// Connects to the socket at its path, whatever the URI of the request is.
+  #[derive(Clone)]
+  pub struct UnixConnector(pub PathBuf);
+
+  impl Service<Uri> for UnixConnector {
+    type Response = UnixConnection;
+    type Error = io::Error;
+    type Future =
+      Pin<Box<dyn Future<Output = io::Result<UnixConnection>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context) -> Poll<io::Result<()>> {
+      Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, _uri: Uri) -> Self::Future {
+      let path = self.0.clone();
+      Box::pin(
+        async move { Ok(UnixConnection(UnixStream::connect(path).await?)) },
+      )
+    }
+  }
+
+  pub struct UnixConnection(UnixStream);
+
+  impl Connection for UnixConnection {
+    fn connected(&self) -> Connected {
+      Connected::new()
+    }
+  }
+
+  impl AsyncRead for UnixConnection {
+    fn poll_read(
+      mut self: Pin<&mut Self>,
+      cx: &mut Context,
+      buf: &mut ReadBuf,
+    ) -> Poll<io::Result<()>> {
+      Pin::new(&mut self.0).poll_read(cx, buf)
+    }
+  }
+
+  impl AsyncWrite for UnixConnection {
+    fn poll_write(
+      mut self: Pin<&mut Self>,
+      cx: &mut Context,
+      buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+      Pin::new(&mut self.0).poll_write(cx, buf)
+    }
+
+    fn poll_flush(
+      mut self: Pin<&mut Self>,
+      cx: &mut Context,
+    ) -> Poll<io::Result<()>> {
+      Pin::new(&mut self.0).poll_flush(cx)
+    }
+
+    fn poll_shutdown(
+      mut self: Pin<&mut Self>,
+      cx: &mut Context,
+    ) -> Poll<io::Result<()>> {
+      Pin::new(&mut self.0).poll_shutdown(cx)
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn socket_path_from_url() {
+    let url =
+      Url::parse("http+unix://%2Fvar%2Frun%2Fdocker.sock/info").unwrap();
+    assert_eq!(
+      socket_path(&url).unwrap(),
+      PathBuf::from("/var/run/docker.sock")
+    );
+    let url = Url::parse("http+unix:///info").unwrap();
+    assert!(socket_path(&url).is_err());
+  }
+}
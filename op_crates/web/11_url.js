@@ -734,12 +734,25 @@
       return this.href;
     }
 
-    static createObjectURL() {
-      throw new Error("Not implemented");
+    static createObjectURL(blob) {
+      requiredArguments("URL.createObjectURL", arguments.length, 1);
+      // provided by "deno_fetch", which is loaded after this file
+      const { Blob, getBlobBytes } = window.__bootstrap.fetch;
+      if (!(blob instanceof Blob)) {
+        throw new TypeError("Argument is not a Blob");
+      }
+      const href = window.__bootstrap.location.getLocationHref();
+      const origin = href == null ? "null" : new URL(href).origin;
+      return core.jsonOpSync(
+        "op_create_object_url",
+        { type: blob.type, origin },
+        getBlobBytes(blob),
+      );
     }
 
-    static revokeObjectURL() {
-      throw new Error("Not implemented");
+    static revokeObjectURL(url) {
+      requiredArguments("URL.revokeObjectURL", arguments.length, 1);
+      core.jsonOpSync("op_revoke_object_url", { url: String(url) });
     }
   }
 
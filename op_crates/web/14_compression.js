@@ -0,0 +1,127 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+((window) => {
+  const core = window.Deno.core;
+
+  const supportedFormats = ["gzip", "deflate", "deflate-raw"];
+
+  /** Takes the pending output of the compressor, if there is any. */
+  function take(rid, length, controller) {
+    if (length === 0) {
+      return;
+    }
+    const output = new Uint8Array(length);
+    core.jsonOpSync("op_compression_take", { rid }, output);
+    controller.enqueue(output);
+  }
+
+  /** Runs `fn` and closes the compressor if it throws, as the stream is
+   * errored then and won't be flushed. */
+  function closeOnError(close, fn) {
+    try {
+      fn();
+    } catch (err) {
+      close();
+      throw err;
+    }
+  }
+
+  // A stream that is canceled or aborted isn't flushed, and drops its
+  // transformer instead, so the compressor is closed once that is collected.
+  const compressorRegistry = new FinalizationRegistry((rid) => {
+    core.close(rid);
+  });
+
+  // The transform stream's internal streams aren't exposed, so the
+  // (de)compression streams hold one and forward its sides.
+  const _transform = Symbol("[[transform]]");
+
+  function createTransform(format, decompress) {
+    if (!supportedFormats.includes(format)) {
+      throw new TypeError(`Unsupported compression format "${format}"`);
+    }
+    const { rid } = core.jsonOpSync("op_compression_new", {
+      format,
+      decompress,
+    });
+    const { TransformStream } = window.__bootstrap.streams;
+    const close = () => {
+      compressorRegistry.unregister(transformer);
+      core.close(rid);
+    };
+    const transformer = {
+      transform(chunk, controller) {
+        closeOnError(close, () => {
+          if (!ArrayBuffer.isView(chunk) && !(chunk instanceof ArrayBuffer)) {
+            throw new TypeError("Chunk is not a BufferSource");
+          }
+          const bytes = ArrayBuffer.isView(chunk)
+            ? new Uint8Array(chunk.buffer, chunk.byteOffset, chunk.byteLength)
+            : new Uint8Array(chunk);
+          // The output of a chunk is taken in steps, so that a small chunk
+          // that decompresses to a lot isn't put in one buffer.
+          let offset = 0;
+          do {
+            const { written, length } = core.jsonOpSync(
+              "op_compression_write",
+              { rid },
+              bytes.subarray(offset),
+            );
+            offset += written;
+            take(rid, length, controller);
+          } while (offset < bytes.length);
+        });
+      },
+      flush(controller) {
+        closeOnError(close, () => {
+          const { length } = core.jsonOpSync("op_compression_finish", { rid });
+          take(rid, length, controller);
+        });
+        close();
+      },
+    };
+    compressorRegistry.register(transformer, rid, transformer);
+    return new TransformStream(transformer);
+  }
+
+  class CompressionStream {
+    constructor(format) {
+      this[_transform] = createTransform(format, false);
+    }
+
+    get readable() {
+      return this[_transform].readable;
+    }
+
+    get writable() {
+      return this[_transform].writable;
+    }
+
+    get [Symbol.toStringTag]() {
+      return "CompressionStream";
+    }
+  }
+
+  class DecompressionStream {
+    constructor(format) {
+      this[_transform] = createTransform(format, true);
+    }
+
+    get readable() {
+      return this[_transform].readable;
+    }
+
+    get writable() {
+      return this[_transform].writable;
+    }
+
+    get [Symbol.toStringTag]() {
+      return "DecompressionStream";
+    }
+  }
+
+  window.__bootstrap.compression = {
+    CompressionStream,
+    DecompressionStream,
+  };
+})(this);
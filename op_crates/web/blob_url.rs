@@ -0,0 +1,158 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::error::type_error;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_core::serde_json::json;
+use deno_core::serde_json::Value;
+use deno_core::url::Url;
+use deno_core::OpState;
+use deno_core::ZeroCopyBuf;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+use uuid::Uuid;
+
+/// The contents of a `Blob` that a `blob:` URL refers to.
+#[derive(Clone, Debug)]
+pub struct Blob {
+  pub data: Vec<u8>,
+  pub media_type: String,
+}
+
+/// The blobs of all `blob:` URLs created with `URL.createObjectURL()`. The
+/// store is shared by all workers of a program, and by the module loader, so
+/// that the URLs can be fetched, imported and used as worker scripts from
+/// anywhere.
+#[derive(Clone, Default)]
+pub struct BlobUrlStore(Arc<Mutex<HashMap<Url, Blob>>>);
+
+impl BlobUrlStore {
+  /// Returns the blob of a URL, ignoring its fragment.
+  pub fn get(&self, url: &Url) -> Option<Blob> {
+    let mut url = url.clone();
+    url.set_fragment(None);
+    self.0.lock().unwrap().get(&url).cloned()
+  }
+
+  /// Stores a blob under a new URL of the given origin, which is `"null"`
+  /// for opaque origins.
+  pub fn insert(&self, blob: Blob, origin: &str) -> Result<Url, AnyError> {
+    let url = Url::parse(&format!("blob:{}/{}", origin, Uuid::new_v4()))?;
+    self.0.lock().unwrap().insert(url.clone(), blob);
+    Ok(url)
+  }
+
+  pub fn remove(&self, url: &Url) {
+    self.0.lock().unwrap().remove(url);
+  }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CreateObjectUrlArgs {
+  r#type: String,
+  origin: String,
+}
+
+pub fn op_create_object_url(
+  state: &mut OpState,
+  args: Value,
+  zero_copy: &mut [ZeroCopyBuf],
+) -> Result<Value, AnyError> {
+  let args: CreateObjectUrlArgs = serde_json::from_value(args)?;
+  assert_eq!(zero_copy.len(), 1);
+  let blob = Blob {
+    data: zero_copy[0].to_vec(),
+    media_type: args.r#type,
+  };
+  let url = state.borrow::<BlobUrlStore>().insert(blob, &args.origin)?;
+  Ok(json!(url.to_string()))
+}
+
+#[derive(Deserialize)]
+struct ObjectUrlArgs {
+  url: String,
+}
+
+/// Parses the URL of a `blob:` URL op, or returns `None` if it can't refer
+/// to a blob.
+fn parse_object_url(url: &str) -> Option<Url> {
+  Url::parse(url).ok().filter(|url| url.scheme() == "blob")
+}
+
+pub fn op_revoke_object_url(
+  state: &mut OpState,
+  args: Value,
+  _zero_copy: &mut [ZeroCopyBuf],
+) -> Result<Value, AnyError> {
+  let args: ObjectUrlArgs = serde_json::from_value(args)?;
+  // Revoking a URL that isn't in the store does nothing.
+  if let Some(url) = parse_object_url(&args.url) {
+    state.borrow::<BlobUrlStore>().remove(&url);
+  }
+  Ok(json!({}))
+}
+
+/// Returns the type and size of the blob of a URL, or `null` if there is
+/// none.
+pub fn op_resolve_object_url(
+  state: &mut OpState,
+  args: Value,
+  _zero_copy: &mut [ZeroCopyBuf],
+) -> Result<Value, AnyError> {
+  let args: ObjectUrlArgs = serde_json::from_value(args)?;
+  let blob = parse_object_url(&args.url)
+    .and_then(|url| state.borrow::<BlobUrlStore>().get(&url));
+  Ok(match blob {
+    Some(blob) => json!({ "type": blob.media_type, "size": blob.data.len() }),
+    None => Value::Null,
+  })
+}
+
+/// Copies the blob of a URL into the buffer, which must have its size.
+pub fn op_read_object_url(
+  state: &mut OpState,
+  args: Value,
+  zero_copy: &mut [ZeroCopyBuf],
+) -> Result<Value, AnyError> {
+  let args: ObjectUrlArgs = serde_json::from_value(args)?;
+  assert_eq!(zero_copy.len(), 1);
+  let blob = parse_object_url(&args.url)
+    .and_then(|url| state.borrow::<BlobUrlStore>().get(&url))
+    .ok_or_else(|| type_error(format!("Blob URL not found: {}", args.url)))?;
+  if zero_copy[0].len() != blob.data.len() {
+    return Err(type_error("Output buffer has the wrong length"));
+  }
+  zero_copy[0].copy_from_slice(&blob.data);
+  Ok(json!({}))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn blob_url_store() {
+    let store = BlobUrlStore::default();
+    let blob = Blob {
+      data: b"export default 1;".to_vec(),
+      media_type: "application/javascript".to_string(),
+    };
+    let url = store.insert(blob, "http://localhost:4545").unwrap();
+    assert_eq!(url.scheme(), "blob");
+    assert!(url.as_str().starts_with("blob:http://localhost:4545/"));
+
+    let mut with_fragment = url.clone();
+    with_fragment.set_fragment(Some("main"));
+    let blob = store.get(&with_fragment).unwrap();
+    assert_eq!(blob.data, b"export default 1;");
+
+    let opaque = store.insert(blob, "null").unwrap();
+    assert!(opaque.as_str().starts_with("blob:null/"));
+    store.remove(&url);
+    assert!(store.get(&url).is_none());
+    assert!(store.get(&opaque).is_some());
+  }
+}
@@ -0,0 +1,274 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::error::bad_resource_id;
+use deno_core::error::type_error;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_core::serde_json::json;
+use deno_core::serde_json::Value;
+use deno_core::OpState;
+use deno_core::Resource;
+use deno_core::ZeroCopyBuf;
+use flate2::write::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::write::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::write::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use serde::Deserialize;
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::io::Write;
+
+/// A compressor or decompressor that writes its output to a buffer, from
+/// which JS takes it after each step of a chunk.
+enum Inner {
+  GzEncoder(GzEncoder<Vec<u8>>),
+  ZlibEncoder(ZlibEncoder<Vec<u8>>),
+  DeflateEncoder(DeflateEncoder<Vec<u8>>),
+  GzDecoder(GzDecoder<Vec<u8>>),
+  ZlibDecoder(ZlibDecoder<Vec<u8>>),
+  DeflateDecoder(DeflateDecoder<Vec<u8>>),
+}
+
+impl Inner {
+  fn new(format: &str, decompress: bool) -> Result<Self, AnyError> {
+    let level = Compression::default();
+    let inner = match (format, decompress) {
+      ("gzip", false) => Inner::GzEncoder(GzEncoder::new(Vec::new(), level)),
+      ("deflate", false) => {
+        Inner::ZlibEncoder(ZlibEncoder::new(Vec::new(), level))
+      }
+      ("deflate-raw", false) => {
+        Inner::DeflateEncoder(DeflateEncoder::new(Vec::new(), level))
+      }
+      ("gzip", true) => Inner::GzDecoder(GzDecoder::new(Vec::new())),
+      ("deflate", true) => Inner::ZlibDecoder(ZlibDecoder::new(Vec::new())),
+      ("deflate-raw", true) => {
+        Inner::DeflateDecoder(DeflateDecoder::new(Vec::new()))
+      }
+      (format, _) => {
+        return Err(type_error(format!(
+          "Unsupported compression format \"{}\"",
+          format
+        )))
+      }
+    };
+    Ok(inner)
+  }
+
+  fn write(&mut self, data: &[u8]) -> std::io::Result<usize> {
+    match self {
+      Inner::GzEncoder(writer) => writer.write(data),
+      Inner::ZlibEncoder(writer) => writer.write(data),
+      Inner::DeflateEncoder(writer) => writer.write(data),
+      Inner::GzDecoder(writer) => writer.write(data),
+      Inner::ZlibDecoder(writer) => writer.write(data),
+      Inner::DeflateDecoder(writer) => writer.write(data),
+    }
+  }
+
+  /// Feeds as much of `data` as it takes to have `MAX_OUTPUT_LENGTH` bytes
+  /// of output, and returns the length that was fed. Each write of flate2
+  /// outputs at most the size of its buffer, so a decompression bomb can't
+  /// make the output much longer than that.
+  fn write_bounded(&mut self, data: &[u8]) -> std::io::Result<usize> {
+    let mut written = 0;
+    while written < data.len() && self.output().len() < MAX_OUTPUT_LENGTH {
+      match self.write(&data[written..])? {
+        0 => {
+          return Err(std::io::Error::new(
+            std::io::ErrorKind::WriteZero,
+            "failed to write whole buffer",
+          ))
+        }
+        n => written += n,
+      }
+    }
+    Ok(written)
+  }
+
+  fn try_finish(&mut self) -> std::io::Result<()> {
+    match self {
+      Inner::GzEncoder(writer) => writer.try_finish(),
+      Inner::ZlibEncoder(writer) => writer.try_finish(),
+      Inner::DeflateEncoder(writer) => writer.try_finish(),
+      Inner::GzDecoder(writer) => writer.try_finish(),
+      Inner::ZlibDecoder(writer) => writer.try_finish(),
+      Inner::DeflateDecoder(writer) => writer.try_finish(),
+    }
+  }
+
+  fn output(&mut self) -> &mut Vec<u8> {
+    match self {
+      Inner::GzEncoder(writer) => writer.get_mut(),
+      Inner::ZlibEncoder(writer) => writer.get_mut(),
+      Inner::DeflateEncoder(writer) => writer.get_mut(),
+      Inner::GzDecoder(writer) => writer.get_mut(),
+      Inner::ZlibDecoder(writer) => writer.get_mut(),
+      Inner::DeflateDecoder(writer) => writer.get_mut(),
+    }
+  }
+}
+
+/// The length of the output at which a write stops feeding its chunk, so that
+/// JS takes the output in steps rather than all at once.
+const MAX_OUTPUT_LENGTH: usize = 64 * 1024;
+
+struct CompressionResource(RefCell<Inner>);
+
+impl Resource for CompressionResource {
+  fn name(&self) -> Cow<str> {
+    "compression".into()
+  }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CompressionNewArgs {
+  format: String,
+  decompress: bool,
+}
+
+/// Creates the compressor or decompressor of a `CompressionStream` or
+/// `DecompressionStream`.
+pub fn op_compression_new(
+  state: &mut OpState,
+  args: Value,
+  _zero_copy: &mut [ZeroCopyBuf],
+) -> Result<Value, AnyError> {
+  let args: CompressionNewArgs = serde_json::from_value(args)?;
+  let inner = Inner::new(&args.format, args.decompress)?;
+  let rid = state
+    .resource_table
+    .add(CompressionResource(RefCell::new(inner)));
+  Ok(json!({ "rid": rid }))
+}
+
+#[derive(Deserialize)]
+struct CompressionArgs {
+  rid: u32,
+}
+
+fn get_resource(
+  state: &OpState,
+  rid: u32,
+) -> Result<std::rc::Rc<CompressionResource>, AnyError> {
+  state
+    .resource_table
+    .get::<CompressionResource>(rid)
+    .ok_or_else(bad_resource_id)
+}
+
+fn corrupt(err: std::io::Error) -> AnyError {
+  type_error(format!("Corrupt compressed data: {}", err))
+}
+
+/// Feeds a chunk until the output reaches `MAX_OUTPUT_LENGTH`, and returns
+/// the length that was fed and the length of the output that is ready to be
+/// taken with `op_compression_take`. The rest of the chunk is fed again once
+/// that is taken.
+pub fn op_compression_write(
+  state: &mut OpState,
+  args: Value,
+  zero_copy: &mut [ZeroCopyBuf],
+) -> Result<Value, AnyError> {
+  let args: CompressionArgs = serde_json::from_value(args)?;
+  assert_eq!(zero_copy.len(), 1);
+  let resource = get_resource(state, args.rid)?;
+  let mut inner = resource.0.borrow_mut();
+  let written = inner.write_bounded(&zero_copy[0]).map_err(corrupt)?;
+  Ok(json!({ "written": written, "length": inner.output().len() }))
+}
+
+/// Ends the stream, and returns the length of the remaining output. A
+/// decompressor fails if the compressed data was cut short.
+pub fn op_compression_finish(
+  state: &mut OpState,
+  args: Value,
+  _zero_copy: &mut [ZeroCopyBuf],
+) -> Result<Value, AnyError> {
+  let args: CompressionArgs = serde_json::from_value(args)?;
+  let resource = get_resource(state, args.rid)?;
+  let mut inner = resource.0.borrow_mut();
+  inner.try_finish().map_err(corrupt)?;
+  Ok(json!({ "length": inner.output().len() }))
+}
+
+/// Moves the pending output into the buffer, which must have its length.
+pub fn op_compression_take(
+  state: &mut OpState,
+  args: Value,
+  zero_copy: &mut [ZeroCopyBuf],
+) -> Result<Value, AnyError> {
+  let args: CompressionArgs = serde_json::from_value(args)?;
+  assert_eq!(zero_copy.len(), 1);
+  let resource = get_resource(state, args.rid)?;
+  let mut inner = resource.0.borrow_mut();
+  let output = inner.output();
+  if zero_copy[0].len() != output.len() {
+    return Err(type_error("Output buffer has the wrong length"));
+  }
+  zero_copy[0].copy_from_slice(output);
+  output.clear();
+  Ok(json!({}))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn run(format: &str, decompress: bool, chunks: &[&[u8]]) -> Vec<u8> {
+    let mut inner = Inner::new(format, decompress).unwrap();
+    let mut output = Vec::new();
+    for chunk in chunks {
+      let mut written = 0;
+      while written < chunk.len() {
+        written += inner.write_bounded(&chunk[written..]).unwrap();
+        assert!(inner.output().len() < 2 * MAX_OUTPUT_LENGTH);
+        output.append(inner.output());
+      }
+    }
+    inner.try_finish().unwrap();
+    output.append(inner.output());
+    output
+  }
+
+  #[test]
+  fn round_trip() {
+    let data = b"hello hello hello hello".repeat(100);
+    for format in &["gzip", "deflate", "deflate-raw"] {
+      let compressed = run(format, false, &[&data[..1000], &data[1000..]]);
+      assert!(compressed.len() < data.len());
+      let (first, second) = compressed.split_at(compressed.len() / 2);
+      assert_eq!(run(format, true, &[first, second]), data);
+    }
+  }
+
+  #[test]
+  fn truncated_gzip() {
+    let compressed = run("gzip", false, &[b"hello"]);
+    let mut inner = Inner::new("gzip", true).unwrap();
+    inner
+      .write_bounded(&compressed[..compressed.len() - 4])
+      .unwrap();
+    assert!(inner.try_finish().is_err());
+  }
+
+  #[test]
+  fn bounded_output() {
+    let data = vec![0; 16 * MAX_OUTPUT_LENGTH];
+    let compressed = run("gzip", false, &[&data]);
+    let mut inner = Inner::new("gzip", true).unwrap();
+    let written = inner.write_bounded(&compressed).unwrap();
+    assert!(written < compressed.len());
+    assert!(inner.output().len() < 2 * MAX_OUTPUT_LENGTH);
+    assert_eq!(run("gzip", true, &[&compressed]), data);
+  }
+
+  #[test]
+  fn unsupported_format() {
+    assert!(Inner::new("brotli", false).is_err());
+  }
+}
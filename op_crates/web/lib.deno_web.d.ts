@@ -184,13 +184,28 @@ declare class TextDecoder {
   /** Returns `true` if error mode is "fatal", and `false` otherwise. */
   readonly fatal: boolean;
   /** Returns `true` if ignore BOM flag is set, and `false` otherwise. */
-  readonly ignoreBOM = false;
+  readonly ignoreBOM: boolean;
+  /** Creates a decoder for any label of the
+   * [Encoding Standard](https://encoding.spec.whatwg.org/#names-and-labels),
+   * such as `"utf-8"`, `"windows-1252"` or `"shift_jis"`. */
   constructor(
     label?: string,
     options?: { fatal?: boolean; ignoreBOM?: boolean },
   );
-  /** Returns the result of running encoding's decoder. */
-  decode(input?: BufferSource, options?: { stream?: false }): string;
+  /** Returns the result of running encoding's decoder. With `stream`, an
+   * incomplete sequence at the end of the input is kept for the next call.
+   *
+   * ```ts
+   * const decoder = new TextDecoder("shift_jis");
+   * const response = await fetch("https://example.com/sjis.txt");
+   * let text = "";
+   * for await (const chunk of response.body!) {
+   *   text += decoder.decode(chunk, { stream: true });
+   * }
+   * text += decoder.decode();
+   * ```
+   */
+  decode(input?: BufferSource, options?: { stream?: boolean }): string;
   readonly [Symbol.toStringTag]: string;
 }
 
@@ -206,6 +221,36 @@ declare class TextEncoder {
   readonly [Symbol.toStringTag]: string;
 }
 
+type CompressionFormat = "deflate" | "deflate-raw" | "gzip";
+
+/** A transform stream that compresses the chunks written to it. The
+ * `"deflate"` format is the zlib format, and `"deflate-raw"` has no header
+ * and trailer.
+ *
+ *       const compressed = new Blob(["hello"])
+ *         .stream()
+ *         .pipeThrough(new CompressionStream("gzip"));
+ */
+declare class CompressionStream {
+  constructor(format: CompressionFormat);
+  readonly readable: ReadableStream<Uint8Array>;
+  readonly writable: WritableStream<BufferSource>;
+}
+
+/** A transform stream that decompresses the chunks written to it. It errors
+ * with a `TypeError` if the data is corrupt or cut short.
+ *
+ *       const response = await fetch("https://example.com/data.txt.gz");
+ *       const text = await new Response(
+ *         response.body!.pipeThrough(new DecompressionStream("gzip")),
+ *       ).text();
+ */
+declare class DecompressionStream {
+  constructor(format: CompressionFormat);
+  readonly readable: ReadableStream<Uint8Array>;
+  readonly writable: WritableStream<BufferSource>;
+}
+
 /** A controller object that allows you to abort one or more DOM requests as and
  * when desired. */
 declare class AbortController {
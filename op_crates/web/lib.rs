@@ -12,6 +12,25 @@ use idna::domain_to_ascii_strict;
 use serde::Deserialize;
 use std::path::PathBuf;
 
+mod blob_url;
+mod compression;
+mod text_decoder;
+
+pub use blob_url::op_create_object_url;
+pub use blob_url::op_read_object_url;
+pub use blob_url::op_resolve_object_url;
+pub use blob_url::op_revoke_object_url;
+pub use blob_url::Blob;
+pub use blob_url::BlobUrlStore;
+pub use compression::op_compression_finish;
+pub use compression::op_compression_new;
+pub use compression::op_compression_take;
+pub use compression::op_compression_write;
+pub use text_decoder::op_encoding_decode;
+pub use text_decoder::op_encoding_decode_single;
+pub use text_decoder::op_encoding_new_decoder;
+pub use text_decoder::op_encoding_normalize_label;
+
 pub fn op_domain_to_ascii(
   _state: &mut deno_core::OpState,
   args: Value,
@@ -65,6 +84,10 @@ pub fn init(isolate: &mut JsRuntime) {
       "deno:op_crates/web/12_location.js",
       include_str!("12_location.js"),
     ),
+    (
+      "deno:op_crates/web/14_compression.js",
+      include_str!("14_compression.js"),
+    ),
     (
       "deno:op_crates/web/21_filereader.js",
       include_str!("21_filereader.js"),
@@ -81,6 +104,7 @@ pub fn get_declaration() -> PathBuf {
 
 #[cfg(test)]
 mod tests {
+  use deno_core::json_op_sync;
   use deno_core::JsRuntime;
   use futures::future::lazy;
   use futures::task::Context;
@@ -96,6 +120,29 @@ mod tests {
   fn setup() -> JsRuntime {
     let mut isolate = JsRuntime::new(Default::default());
     crate::init(&mut isolate);
+    isolate.register_op("op_close", json_op_sync(deno_core::op_close));
+    isolate.register_op(
+      "op_encoding_normalize_label",
+      json_op_sync(crate::op_encoding_normalize_label),
+    );
+    isolate.register_op(
+      "op_encoding_decode_single",
+      json_op_sync(crate::op_encoding_decode_single),
+    );
+    isolate.register_op(
+      "op_encoding_new_decoder",
+      json_op_sync(crate::op_encoding_new_decoder),
+    );
+    isolate.register_op(
+      "op_encoding_decode",
+      json_op_sync(crate::op_encoding_decode),
+    );
+    isolate
+      .execute(
+        "setup.js",
+        "Deno.core.registerErrorClass('TypeError', TypeError);",
+      )
+      .unwrap();
     isolate
   }
 
@@ -0,0 +1,232 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::error::bad_resource_id;
+use deno_core::error::type_error;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_core::serde_json::json;
+use deno_core::serde_json::Value;
+use deno_core::OpState;
+use deno_core::Resource;
+use deno_core::ZeroCopyBuf;
+use encoding_rs::CoderResult;
+use encoding_rs::Decoder;
+use encoding_rs::DecoderResult;
+use encoding_rs::Encoding;
+use serde::Deserialize;
+use std::borrow::Cow;
+use std::cell::RefCell;
+
+#[derive(Deserialize)]
+struct NormalizeLabelArgs {
+  label: String,
+}
+
+/// Returns the name of the encoding of a label, as `TextDecoder.encoding`
+/// reports it, or `null` if the label is invalid or refers to the
+/// replacement encoding.
+pub fn op_encoding_normalize_label(
+  _state: &mut OpState,
+  args: Value,
+  _zero_copy: &mut [ZeroCopyBuf],
+) -> Result<Value, AnyError> {
+  let args: NormalizeLabelArgs = serde_json::from_value(args)?;
+  Ok(
+    match Encoding::for_label_no_replacement(args.label.as_bytes()) {
+      Some(encoding) => json!(encoding.name().to_lowercase()),
+      None => Value::Null,
+    },
+  )
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DecoderArgs {
+  label: String,
+  fatal: bool,
+  #[serde(rename = "ignoreBOM")]
+  ignore_bom: bool,
+}
+
+impl DecoderArgs {
+  fn new_decoder(&self) -> Result<Decoder, AnyError> {
+    let encoding = Encoding::for_label_no_replacement(self.label.as_bytes())
+      .ok_or_else(|| {
+        type_error(format!(
+          "The encoding label provided ('{}') is invalid.",
+          self.label
+        ))
+      })?;
+    Ok(if self.ignore_bom {
+      encoding.new_decoder_without_bom_handling()
+    } else {
+      encoding.new_decoder_with_bom_removal()
+    })
+  }
+}
+
+/// Decodes a chunk. The decoder has to be thrown away after the `last` chunk.
+fn decode(
+  decoder: &mut Decoder,
+  fatal: bool,
+  data: &[u8],
+  last: bool,
+) -> Result<String, AnyError> {
+  let max_length = if fatal {
+    decoder.max_utf8_buffer_length_without_replacement(data.len())
+  } else {
+    decoder.max_utf8_buffer_length(data.len())
+  }
+  .ok_or_else(|| type_error("Value too large to decode"))?;
+  let mut output = String::with_capacity(max_length);
+
+  if fatal {
+    let (result, _) =
+      decoder.decode_to_string_without_replacement(data, &mut output, last);
+    match result {
+      DecoderResult::InputEmpty => Ok(output),
+      DecoderResult::OutputFull => unreachable!(),
+      DecoderResult::Malformed(_, _) => {
+        Err(type_error("The encoded data is not valid."))
+      }
+    }
+  } else {
+    let (result, _, _) = decoder.decode_to_string(data, &mut output, last);
+    match result {
+      CoderResult::InputEmpty => Ok(output),
+      CoderResult::OutputFull => unreachable!(),
+    }
+  }
+}
+
+/// Decodes a whole input, for `TextDecoder.decode()` calls that aren't part
+/// of a stream.
+pub fn op_encoding_decode_single(
+  _state: &mut OpState,
+  args: Value,
+  zero_copy: &mut [ZeroCopyBuf],
+) -> Result<Value, AnyError> {
+  let args: DecoderArgs = serde_json::from_value(args)?;
+  assert_eq!(zero_copy.len(), 1);
+  let mut decoder = args.new_decoder()?;
+  let output = decode(&mut decoder, args.fatal, &zero_copy[0], true)?;
+  Ok(json!(output))
+}
+
+struct TextDecoderResource {
+  decoder: RefCell<Decoder>,
+  fatal: bool,
+}
+
+impl Resource for TextDecoderResource {
+  fn name(&self) -> Cow<str> {
+    "textDecoder".into()
+  }
+}
+
+/// Creates the decoder of a `TextDecoder` that decodes a stream, which keeps
+/// the incomplete sequences at the end of a chunk for the next one.
+pub fn op_encoding_new_decoder(
+  state: &mut OpState,
+  args: Value,
+  _zero_copy: &mut [ZeroCopyBuf],
+) -> Result<Value, AnyError> {
+  let args: DecoderArgs = serde_json::from_value(args)?;
+  let resource = TextDecoderResource {
+    decoder: RefCell::new(args.new_decoder()?),
+    fatal: args.fatal,
+  };
+  let rid = state.resource_table.add(resource);
+  Ok(json!({ "rid": rid }))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DecodeArgs {
+  rid: u32,
+  stream: bool,
+}
+
+/// Decodes a chunk of a stream. The last chunk, which isn't decoded with
+/// `stream`, flushes the decoder, after which JS closes it.
+pub fn op_encoding_decode(
+  state: &mut OpState,
+  args: Value,
+  zero_copy: &mut [ZeroCopyBuf],
+) -> Result<Value, AnyError> {
+  let args: DecodeArgs = serde_json::from_value(args)?;
+  assert_eq!(zero_copy.len(), 1);
+  let resource = state
+    .resource_table
+    .get::<TextDecoderResource>(args.rid)
+    .ok_or_else(bad_resource_id)?;
+  let mut decoder = resource.decoder.borrow_mut();
+  let output =
+    decode(&mut decoder, resource.fatal, &zero_copy[0], !args.stream)?;
+  Ok(json!(output))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn decoder(label: &str, ignore_bom: bool) -> Decoder {
+    let args = DecoderArgs {
+      label: label.to_string(),
+      fatal: false,
+      ignore_bom,
+    };
+    args.new_decoder().unwrap()
+  }
+
+  #[test]
+  fn legacy_encodings() {
+    // "日本" in Shift_JIS, "€" in windows-1252 and "中文" in GBK.
+    let mut shift_jis = decoder("sjis", false);
+    let output = decode(&mut shift_jis, true, b"\x93\xfa\x96\x7b", true);
+    assert_eq!(output.unwrap(), "日本");
+    let mut windows_1252 = decoder("latin1", false);
+    let output = decode(&mut windows_1252, true, b"\x80", true);
+    assert_eq!(output.unwrap(), "€");
+    let mut gbk = decoder("gb2312", false);
+    let output = decode(&mut gbk, true, b"\xd6\xd0\xce\xc4", true);
+    assert_eq!(output.unwrap(), "中文");
+  }
+
+  #[test]
+  fn streaming() {
+    let mut decoder = decoder("utf-8", false);
+    let data = "\u{feff}€uro".as_bytes();
+    let first = decode(&mut decoder, true, &data[..4], false).unwrap();
+    let second = decode(&mut decoder, true, &data[4..], true).unwrap();
+    assert_eq!(first, "");
+    assert_eq!(second, "€uro");
+  }
+
+  #[test]
+  fn fatal_and_bom() {
+    let mut utf_8 = decoder("utf-8", false);
+    assert!(decode(&mut utf_8, true, b"\xe2\x82", true).is_err());
+    let mut utf_8 = decoder("utf-8", false);
+    let output = decode(&mut utf_8, false, b"\xe2\x82", true).unwrap();
+    assert_eq!(output, "\u{fffd}");
+
+    let mut utf_16le = decoder("utf-16le", true);
+    let output = decode(&mut utf_16le, true, b"\xff\xfea\x00", true);
+    assert_eq!(output.unwrap(), "\u{feff}a");
+    let mut utf_16le = decoder("utf-16", false);
+    let output = decode(&mut utf_16le, true, b"\xff\xfea\x00", true);
+    assert_eq!(output.unwrap(), "a");
+  }
+
+  #[test]
+  fn invalid_labels() {
+    let args = DecoderArgs {
+      label: "iso-2022-kr".to_string(),
+      fatal: false,
+      ignore_bom: false,
+    };
+    assert!(args.new_decoder().is_err());
+    assert!(Encoding::for_label_no_replacement(b" Shift_JIS\t").is_some());
+  }
+}
@@ -119,10 +119,10 @@ function textDecoderErrorEncoding() {
   assert(didThrow);
 }
 
-function textDecoderHandlesNotFoundInternalDecoder() {
+function textDecoderReplacementEncoding() {
   let didThrow = false;
   try {
-    new TextDecoder("gbk");
+    new TextDecoder("iso-2022-kr");
   } catch (e) {
     didThrow = true;
     assert(e instanceof RangeError);
@@ -130,6 +130,34 @@ function textDecoderHandlesNotFoundInternalDecoder() {
   assert(didThrow);
 }
 
+function textDecoderLegacyEncodings() {
+  const shiftJis = new TextDecoder(" Shift_JIS ");
+  assert(shiftJis.encoding === "shift_jis");
+  assert(shiftJis.decode(new Uint8Array([0x93, 0xfa, 0x96, 0x7b])) === "日本");
+  const gbk = new TextDecoder("gbk", { fatal: true });
+  assert(gbk.decode(new Uint8Array([0xd6, 0xd0, 0xce, 0xc4])) === "中文");
+  const utf16 = new TextDecoder("utf-16");
+  assert(utf16.encoding === "utf-16le");
+  assert(utf16.decode(new Uint8Array([0xff, 0xfe, 0x61, 0x00])) === "a");
+}
+
+function textDecoderStream() {
+  const decoder = new TextDecoder("utf-8", { fatal: true });
+  const bytes = new Uint8Array([0xe2, 0x82, 0xac, 0x61]);
+  assert(decoder.decode(bytes.subarray(0, 2), { stream: true }) === "");
+  assert(decoder.decode(bytes.subarray(2, 4), { stream: true }) === "€a");
+  let didThrow = false;
+  try {
+    decoder.decode(bytes.subarray(0, 1));
+  } catch (e) {
+    didThrow = true;
+    assert(e instanceof TypeError);
+  }
+  assert(didThrow);
+  // The stream ended with the error, so the decoder starts over.
+  assert(decoder.decode(bytes) === "€a");
+}
+
 function textDecoderHandlesUndefined() {
   const fixture = undefined;
   const decoder = new TextDecoder();
@@ -1213,7 +1241,9 @@ function main() {
   textDecoderSharedInt32Array();
   toStringShouldBeWebCompatibility();
   singleByteEncodings();
-  textDecoderHandlesNotFoundInternalDecoder();
+  textDecoderReplacementEncoding();
+  textDecoderLegacyEncodings();
+  textDecoderStream();
 }
 
 main();
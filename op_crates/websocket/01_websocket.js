@@ -60,13 +60,25 @@
     });
   }
 
+  const serverConstructorKey = Symbol("serverConstructorKey");
+
   class WebSocket extends EventTarget {
     #readyState = CONNECTING;
 
-    constructor(url, protocols = []) {
+    constructor(url, protocols = [], server = undefined) {
       super();
       requiredArguments("WebSocket", arguments.length, 1);
 
+      if (server?.key === serverConstructorKey) {
+        // The server has already done the handshake of this WebSocket.
+        this.#url = url;
+        this.#rid = server.rid;
+        this.#protocol = server.protocol;
+        this.#readyState = OPEN;
+        this.#eventLoop();
+        return;
+      }
+
       const wsURL = new URL(url);
 
       if (wsURL.protocol !== "ws:" && wsURL.protocol !== "wss:") {
@@ -341,6 +353,8 @@
 
           case "close": {
             this.#readyState = CLOSED;
+            // Flushes the close frame that echoes the one of the peer.
+            core.jsonOpAsync("op_ws_close", { rid: this.#rid }).catch(() => {});
 
             const event = new CloseEvent("close", {
               wasClean: true,
@@ -391,5 +405,11 @@
   defineEventHandler(WebSocket.prototype, "close");
   defineEventHandler(WebSocket.prototype, "open");
 
-  window.__bootstrap.webSocket = { WebSocket };
+  /** Creates the WebSocket of a connection that has been upgraded by the
+   * server, which is open from the start. */
+  function createServerWebSocket(rid, url, protocol) {
+    return new WebSocket(url, [], { key: serverConstructorKey, rid, protocol });
+  }
+
+  window.__bootstrap.webSocket = { WebSocket, createServerWebSocket };
 })(this);
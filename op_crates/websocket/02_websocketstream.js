@@ -0,0 +1,295 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+((window) => {
+  const core = window.Deno.core;
+
+  // provided by "deno_web"
+  const { URL } = window.__bootstrap.url;
+
+  /** Checks that a close code and reason can be sent to the peer. */
+  function validateCloseInfo(code, reason) {
+    if (
+      code !== undefined && code !== 1000 && !(code >= 3000 && code < 5000)
+    ) {
+      throw new DOMException(
+        "The close code must be either 1000 or in the range of 3000 to 4999.",
+        "InvalidAccessError",
+      );
+    }
+    if (new TextEncoder().encode(reason).byteLength > 123) {
+      throw new DOMException(
+        "The close reason may not be longer than 123 bytes.",
+        "SyntaxError",
+      );
+    }
+  }
+
+  /** A WebSocket whose messages are read from a `ReadableStream` and written
+   * to a `WritableStream`. A message is only read from the connection when
+   * the readable side is read from, so that a slow consumer holds the
+   * messages back in the connection instead of buffering them. */
+  class WebSocketStream {
+    #rid;
+    #url = "";
+    #connection;
+    #closed;
+    #resolveClosed;
+    #rejectClosed;
+    #controller;
+    /** Whether `close()` has sent a close frame. */
+    #closing = false;
+    /** Whether the connection has been closed, after which no more messages
+     * are read. */
+    #done = false;
+
+    constructor(url, options = {}) {
+      if (arguments.length < 1) {
+        throw new TypeError(
+          "WebSocketStream requires at least 1 argument, but only 0 present",
+        );
+      }
+
+      const wsURL = new URL(url);
+      if (wsURL.protocol !== "ws:" && wsURL.protocol !== "wss:") {
+        throw new DOMException(
+          "Only ws & wss schemes are allowed in a WebSocket URL.",
+          "SyntaxError",
+        );
+      }
+      if (wsURL.hash !== "" || wsURL.href.endsWith("#")) {
+        throw new DOMException(
+          "Fragments are not allowed in a WebSocket URL.",
+          "SyntaxError",
+        );
+      }
+      this.#url = wsURL.href;
+
+      core.jsonOpSync("op_ws_check_permission", { url: this.#url });
+
+      const { protocols = [], headers } = options;
+      if (new Set(protocols).size !== protocols.length) {
+        throw new DOMException(
+          "Can't supply multiple times the same protocol.",
+          "SyntaxError",
+        );
+      }
+
+      this.#closed = new Promise((resolve, reject) => {
+        this.#resolveClosed = resolve;
+        this.#rejectClosed = reject;
+      });
+      // Failures are reported through `connection` as well.
+      this.#closed.catch(() => {});
+
+      const { Headers } = window.__bootstrap.headers;
+      this.#connection = core.jsonOpAsync("op_ws_create", {
+        url: this.#url,
+        protocols: protocols.join(", "),
+        headers: headers === undefined ? null : [...new Headers(headers)],
+      }).then((create) => {
+        if (!create.success) {
+          throw new DOMException(
+            "Failed to connect to the WebSocket server.",
+            "NetworkError",
+          );
+        }
+        this.#rid = create.rid;
+        return {
+          readable: this.#createReadable(),
+          writable: this.#createWritable(),
+          protocol: create.protocol,
+          extensions: create.extensions,
+        };
+      });
+      this.#connection.catch((err) => {
+        this.#done = true;
+        this.#rejectClosed(err);
+      });
+    }
+
+    get url() {
+      return this.#url;
+    }
+
+    get connection() {
+      return this.#connection;
+    }
+
+    get closed() {
+      return this.#closed;
+    }
+
+    close(closeInfo = {}) {
+      const { code, reason = "" } = closeInfo;
+      validateCloseInfo(code, reason);
+      if (this.#closing) {
+        return;
+      }
+      this.#closing = true;
+
+      this.#connection.then(async () => {
+        if (this.#done) {
+          return;
+        }
+        await core.jsonOpAsync("op_ws_close", {
+          rid: this.#rid,
+          code,
+          reason,
+        });
+        // The messages that arrive before the close frame of the peer are
+        // dropped.
+        while (await this.#nextMessage() !== null);
+      }).catch(() => {});
+    }
+
+    #createReadable() {
+      const { ReadableStream } = window.__bootstrap.streams;
+      return new ReadableStream({
+        start: (controller) => {
+          this.#controller = controller;
+        },
+        pull: async (controller) => {
+          const message = await this.#nextMessage();
+          if (message !== null) {
+            controller.enqueue(message);
+          }
+        },
+        cancel: () => this.close(),
+      }, { highWaterMark: 0 });
+    }
+
+    #createWritable() {
+      const { WritableStream } = window.__bootstrap.streams;
+      return new WritableStream({
+        write: async (chunk) => {
+          let data;
+          if (ArrayBuffer.isView(chunk)) {
+            data = new Uint8Array(
+              chunk.buffer,
+              chunk.byteOffset,
+              chunk.byteLength,
+            );
+          } else if (chunk instanceof ArrayBuffer) {
+            data = new Uint8Array(chunk);
+          } else if (typeof chunk !== "string") {
+            throw new TypeError("A chunk must be a string or a BufferSource");
+          }
+          // The write is done once the message is sent, which holds back the
+          // writer while the connection is busy.
+          if (data === undefined) {
+            await core.jsonOpAsync("op_ws_send", {
+              rid: this.#rid,
+              kind: "text",
+              text: chunk,
+            });
+          } else {
+            await core.jsonOpAsync("op_ws_send", {
+              rid: this.#rid,
+              kind: "binary",
+            }, data);
+          }
+        },
+        close: () => this.close(),
+        abort: () => this.close(),
+      });
+    }
+
+    /** Reads the next message, answering pings on the way, or returns `null`
+     * once the connection is closed. */
+    async #nextMessage() {
+      while (!this.#done) {
+        let event;
+        try {
+          event = await core.jsonOpAsync("op_ws_next_event", {
+            rid: this.#rid,
+          });
+        } catch (err) {
+          this.#fail(err);
+          return null;
+        }
+
+        switch (event.kind) {
+          case "string":
+            return event.data;
+          case "binary":
+            return new Uint8Array(event.data);
+          case "ping":
+            await core.jsonOpAsync("op_ws_send", {
+              rid: this.#rid,
+              kind: "pong",
+            });
+            break;
+          case "pong":
+            break;
+          case "close":
+            await this.#finish(
+              event.data?.code ?? 1005,
+              event.data?.reason ?? "",
+            );
+            return null;
+          case "closed":
+            await this.#finish(1006, "");
+            return null;
+          case "error":
+            this.#fail(
+              new DOMException(
+                "The WebSocket connection failed.",
+                "NetworkError",
+              ),
+            );
+            return null;
+        }
+      }
+      return null;
+    }
+
+    /** Ends the connection once the close frame of the peer is received. */
+    async #finish(code, reason) {
+      if (this.#done) {
+        return;
+      }
+      this.#done = true;
+      if (!this.#closing) {
+        // Echoes the close frame of the peer.
+        this.#closing = true;
+        await core.jsonOpAsync("op_ws_close", {
+          rid: this.#rid,
+          code: code === 1005 ? undefined : code,
+        }).catch(() => {});
+      }
+      this.#closeResource();
+      try {
+        this.#controller.close();
+      } catch {
+        // The readable side was canceled.
+      }
+      this.#resolveClosed({ code, reason });
+    }
+
+    #fail(err) {
+      if (this.#done) {
+        // A read that was pending when the connection was closed.
+        return;
+      }
+      this.#done = true;
+      this.#closeResource();
+      this.#controller.error(err);
+      this.#rejectClosed(err);
+    }
+
+    #closeResource() {
+      try {
+        core.close(this.#rid);
+      } catch {
+        // The resource is gone already if the connection ended without a
+        // close frame.
+      }
+    }
+
+    get [Symbol.toStringTag]() {
+      return "WebSocketStream";
+    }
+  }
+
+  window.__bootstrap.webSocket.WebSocketStream = WebSocketStream;
+})(this);
@@ -0,0 +1,384 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+//! The receiving side of the permessage-deflate extension (RFC 7692).
+//!
+//! `tungstenite` fails on frames with the RSV1 bit set, so the connection is
+//! wrapped in a `DeflateStream` that inflates compressed messages before
+//! `tungstenite` reads them. Messages are never compressed when sending,
+//! which the extension allows, so any parameters of the peer can be
+//! accepted.
+
+use flate2::Decompress;
+use flate2::FlushDecompress;
+use std::io;
+use std::pin::Pin;
+use std::task::Context;
+use std::task::Poll;
+use tokio::io::AsyncRead;
+use tokio::io::AsyncWrite;
+use tokio::io::ReadBuf;
+
+pub const EXTENSION: &str = "permessage-deflate";
+
+/// The largest message that is inflated, which matches the default maximum
+/// message size of `tungstenite`.
+const MAX_MESSAGE_SIZE: usize = 64 << 20;
+
+const FIN: u8 = 0x80;
+const RSV1: u8 = 0x40;
+const MASK: u8 = 0x80;
+
+/// Returns whether a `Sec-WebSocket-Extensions` header value contains the
+/// permessage-deflate extension.
+pub fn has_extension(value: &str) -> bool {
+  value.split(',').any(|offer| {
+    offer
+      .split(';')
+      .next()
+      .map_or(false, |name| name.trim().eq_ignore_ascii_case(EXTENSION))
+  })
+}
+
+/// Returns the `Sec-WebSocket-Extensions` header value with which a server
+/// accepts the first permessage-deflate offer of a client. The parameters
+/// about the server are echoed, as they only restrict how the server
+/// compresses.
+pub fn accept_offer(value: &str) -> Option<String> {
+  let offer = value.split(',').find(|offer| has_extension(offer))?;
+  let mut response = EXTENSION.to_string();
+  for param in offer.split(';').skip(1) {
+    let param = param.trim();
+    if param.to_ascii_lowercase().starts_with("server_") {
+      response.push_str("; ");
+      response.push_str(param);
+    }
+  }
+  Some(response)
+}
+
+/// A frame header, as far as it needs to be known to rewrite the frame.
+struct Header {
+  first: u8,
+  masked: bool,
+  header_len: usize,
+  payload_len: usize,
+}
+
+impl Header {
+  /// Parses the header at the start of `data`, or returns `None` if it isn't
+  /// complete yet.
+  fn parse(data: &[u8]) -> Option<Header> {
+    if data.len() < 2 {
+      return None;
+    }
+    let masked = data[1] & MASK != 0;
+    let (payload_len, mut header_len) = match data[1] & 0x7f {
+      126 => {
+        let bytes = data.get(2..4)?;
+        (u16::from_be_bytes([bytes[0], bytes[1]]) as usize, 4)
+      }
+      127 => {
+        let bytes = data.get(2..10)?;
+        let mut len = [0; 8];
+        len.copy_from_slice(bytes);
+        (u64::from_be_bytes(len) as usize, 10)
+      }
+      len => (len as usize, 2),
+    };
+    if masked {
+      header_len += 4;
+    }
+    if data.len() < header_len {
+      return None;
+    }
+    Some(Header {
+      first: data[0],
+      masked,
+      header_len,
+      payload_len,
+    })
+  }
+
+  fn opcode(&self) -> u8 {
+    self.first & 0x0f
+  }
+
+  fn is_control(&self) -> bool {
+    self.opcode() & 0x08 != 0
+  }
+}
+
+enum State {
+  /// The HTTP head of the handshake is passed through until its end, after
+  /// which the frames start.
+  Handshake,
+  Frames,
+}
+
+/// A connection that inflates the compressed messages it reads, and passes
+/// everything else through.
+pub struct DeflateStream<S> {
+  inner: S,
+  state: State,
+  /// Whether the extension was negotiated. Without it, compressed frames are
+  /// passed through for `tungstenite` to reject.
+  enabled: bool,
+  decompress: Decompress,
+  /// The bytes that have been read but not processed yet.
+  input: Vec<u8>,
+  /// The processed bytes that are ready to be read.
+  output: Vec<u8>,
+  /// The opcode, masking and payload of the compressed message whose frames
+  /// are being received.
+  message: Option<(u8, bool, Vec<u8>)>,
+}
+
+impl<S> DeflateStream<S> {
+  /// Wraps a connection whose handshake hasn't been read yet. The extension
+  /// is enabled if the handshake request or response has it.
+  pub fn new(inner: S) -> Self {
+    Self::with_state(inner, State::Handshake, false)
+  }
+
+  /// Wraps a connection whose handshake has already been done.
+  pub fn after_handshake(inner: S, enabled: bool) -> Self {
+    Self::with_state(inner, State::Frames, enabled)
+  }
+
+  fn with_state(inner: S, state: State, enabled: bool) -> Self {
+    Self {
+      inner,
+      state,
+      enabled,
+      decompress: Decompress::new(false),
+      input: Vec::new(),
+      output: Vec::new(),
+      message: None,
+    }
+  }
+
+  /// Moves the processed part of `input` to `output`.
+  fn process(&mut self) -> io::Result<()> {
+    if let State::Handshake = self.state {
+      let end = match self.input.windows(4).position(|w| w == b"\r\n\r\n") {
+        Some(position) => position + 4,
+        None => return Ok(()),
+      };
+      let head = String::from_utf8_lossy(&self.input[..end]);
+      self.enabled = head.lines().any(|line| {
+        let mut parts = line.splitn(2, ':');
+        let name = parts.next().unwrap_or("").trim();
+        name.eq_ignore_ascii_case("sec-websocket-extensions")
+          && parts.next().map_or(false, has_extension)
+      });
+      self.output.extend(self.input.drain(..end));
+      self.state = State::Frames;
+    }
+
+    while let Some(header) = Header::parse(&self.input) {
+      if header.payload_len > MAX_MESSAGE_SIZE {
+        return Err(invalid_data("WebSocket frame is too large"));
+      }
+      let frame_len = header.header_len + header.payload_len;
+      if self.input.len() < frame_len {
+        break;
+      }
+      let frame: Vec<u8> = self.input.drain(..frame_len).collect();
+      let compressed = match header.opcode() {
+        0 => self.message.is_some(),
+        1 | 2 => self.enabled && header.first & RSV1 != 0,
+        _ => false,
+      };
+      if header.is_control() || !compressed {
+        self.output.extend(frame);
+        continue;
+      }
+
+      let mut payload = frame[header.header_len..].to_vec();
+      if header.masked {
+        let key = &frame[header.header_len - 4..header.header_len];
+        for (i, byte) in payload.iter_mut().enumerate() {
+          *byte ^= key[i % 4];
+        }
+      }
+      let (_, _, data) = self
+        .message
+        .get_or_insert_with(|| (header.opcode(), header.masked, Vec::new()));
+      if data.len() + payload.len() > MAX_MESSAGE_SIZE {
+        return Err(invalid_data("WebSocket message is too large"));
+      }
+      data.extend(payload);
+      if header.first & FIN != 0 {
+        let (opcode, masked, mut data) = self.message.take().unwrap();
+        data.extend_from_slice(&[0x00, 0x00, 0xff, 0xff]);
+        let data = self.inflate(&data)?;
+        write_frame(&mut self.output, opcode, masked, &data);
+      }
+    }
+    Ok(())
+  }
+
+  fn inflate(&mut self, mut input: &[u8]) -> io::Result<Vec<u8>> {
+    let mut output = Vec::with_capacity(input.len() * 2);
+    loop {
+      if output.len() == output.capacity() {
+        output.reserve(output.len().max(1024));
+      }
+      let total_in = self.decompress.total_in();
+      self
+        .decompress
+        .decompress_vec(input, &mut output, FlushDecompress::Sync)
+        .map_err(|err| invalid_data(&err.to_string()))?;
+      input = &input[(self.decompress.total_in() - total_in) as usize..];
+      if output.len() > MAX_MESSAGE_SIZE {
+        return Err(invalid_data("WebSocket message is too large"));
+      }
+      // The output has room left only once all input has been inflated.
+      if output.len() < output.capacity() {
+        return Ok(output);
+      }
+    }
+  }
+}
+
+fn invalid_data(message: &str) -> io::Error {
+  io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+/// Writes an uncompressed frame. A masked frame keeps the mask bit, with an
+/// all-zero key that leaves the payload as it is.
+fn write_frame(output: &mut Vec<u8>, opcode: u8, masked: bool, data: &[u8]) {
+  output.push(FIN | opcode);
+  let mask = if masked { MASK } else { 0 };
+  if data.len() < 126 {
+    output.push(mask | data.len() as u8);
+  } else if data.len() <= u16::MAX as usize {
+    output.push(mask | 126);
+    output.extend_from_slice(&(data.len() as u16).to_be_bytes());
+  } else {
+    output.push(mask | 127);
+    output.extend_from_slice(&(data.len() as u64).to_be_bytes());
+  }
+  if masked {
+    output.extend_from_slice(&[0; 4]);
+  }
+  output.extend_from_slice(data);
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for DeflateStream<S> {
+  fn poll_read(
+    self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+    buf: &mut ReadBuf<'_>,
+  ) -> Poll<io::Result<()>> {
+    let this = self.get_mut();
+    loop {
+      if !this.output.is_empty() {
+        let len = this.output.len().min(buf.remaining());
+        buf.put_slice(&this.output[..len]);
+        this.output.drain(..len);
+        return Poll::Ready(Ok(()));
+      }
+
+      let mut chunk = [0; 8192];
+      let mut chunk = ReadBuf::new(&mut chunk);
+      match Pin::new(&mut this.inner).poll_read(cx, &mut chunk) {
+        Poll::Ready(Ok(())) if chunk.filled().is_empty() => {
+          // The connection was closed. Any incomplete frame is left for
+          // `tungstenite` to notice as a truncated stream.
+          return Poll::Ready(Ok(()));
+        }
+        Poll::Ready(Ok(())) => {
+          this.input.extend_from_slice(chunk.filled());
+          this.process()?;
+        }
+        Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+        Poll::Pending => return Poll::Pending,
+      }
+    }
+  }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for DeflateStream<S> {
+  fn poll_write(
+    self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+    buf: &[u8],
+  ) -> Poll<io::Result<usize>> {
+    Pin::new(&mut self.get_mut().inner).poll_write(cx, buf)
+  }
+
+  fn poll_flush(
+    self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+  ) -> Poll<io::Result<()>> {
+    Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+  }
+
+  fn poll_shutdown(
+    self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+  ) -> Poll<io::Result<()>> {
+    Pin::new(&mut self.get_mut().inner).poll_shutdown(cx)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn process(stream: &mut DeflateStream<()>, input: &[u8]) -> Vec<u8> {
+    stream.input.extend_from_slice(input);
+    stream.process().unwrap();
+    std::mem::take(&mut stream.output)
+  }
+
+  #[test]
+  fn negotiation() {
+    assert!(has_extension(
+      "foo, permessage-deflate; client_max_window_bits"
+    ));
+    assert!(!has_extension("x-webkit-deflate-frame"));
+    assert_eq!(
+      accept_offer(
+        "permessage-deflate; client_max_window_bits; \
+         server_no_context_takeover"
+      )
+      .as_deref(),
+      Some("permessage-deflate; server_no_context_takeover")
+    );
+    assert_eq!(accept_offer("foo"), None);
+  }
+
+  #[test]
+  fn inflate_messages() {
+    let mut stream = DeflateStream::new(());
+    let head = b"HTTP/1.1 101 Switching Protocols\r\n\
+      Sec-WebSocket-Extensions: permessage-deflate\r\n\r\n";
+    assert_eq!(process(&mut stream, head), head.to_vec());
+    assert!(stream.enabled);
+
+    // The "Hello" examples of RFC 7692, section 7.2.3.
+    let compressed = b"\xc1\x07\xf2\x48\xcd\xc9\xc9\x07\x00";
+    assert_eq!(process(&mut stream, compressed), b"\x81\x05Hello");
+    // The same message, fragmented, in two reads, with the context of the
+    // first message.
+    let fragments = b"\x41\x03\xf2\x00\x11\x80\x02\x00\x00";
+    assert_eq!(process(&mut stream, &fragments[..4]), b"");
+    assert_eq!(process(&mut stream, &fragments[4..]), b"\x81\x05Hello");
+    // Uncompressed and control frames are passed through.
+    assert_eq!(process(&mut stream, b"\x81\x02hi"), b"\x81\x02hi");
+    assert_eq!(process(&mut stream, b"\x89\x00"), b"\x89\x00");
+  }
+
+  #[test]
+  fn masked_message() {
+    let mut stream = DeflateStream::after_handshake((), true);
+    let key = [1, 2, 3, 4];
+    let mut frame = vec![0xc1, 0x87];
+    frame.extend_from_slice(&key);
+    let payload = b"\xf2\x48\xcd\xc9\xc9\x07\x00";
+    frame.extend(payload.iter().enumerate().map(|(i, b)| b ^ key[i % 4]));
+    assert_eq!(process(&mut stream, &frame), b"\x81\x85\0\0\0\0Hello");
+  }
+}
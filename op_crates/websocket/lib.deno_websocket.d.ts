@@ -110,3 +110,51 @@ declare class WebSocket extends EventTarget {
 }
 
 type BinaryType = "arraybuffer" | "blob";
+
+interface WebSocketStreamOptions {
+  protocols?: string[];
+  /** Extra headers of the handshake request, such as `Authorization`. The
+   * headers that the handshake sets itself can't be set. */
+  headers?: HeadersInit;
+}
+
+interface WebSocketConnection {
+  readable: ReadableStream<string | Uint8Array>;
+  writable: WritableStream<string | BufferSource>;
+  /** The subprotocol selected by the server, if any. */
+  protocol: string;
+  /** The extensions selected by the server, if any. */
+  extensions: string;
+}
+
+interface WebSocketCloseInfo {
+  code?: number;
+  reason?: string;
+}
+
+/** A WebSocket whose messages are read from and written to streams. Messages
+ * are only read from the connection when `readable` is read from, so a slow
+ * consumer doesn't buffer an unbounded amount of messages.
+ *
+ * ```ts
+ * const wss = new WebSocketStream("wss://example.com/chat");
+ * const { readable, writable } = await wss.connection;
+ * const writer = writable.getWriter();
+ * await writer.write("hello");
+ * for await (const message of readable) {
+ *   console.log(message);
+ * }
+ * ```
+ */
+declare class WebSocketStream {
+  constructor(url: string, options?: WebSocketStreamOptions);
+  readonly url: string;
+  /** Resolves once the handshake is done. */
+  readonly connection: Promise<WebSocketConnection>;
+  /** Resolves with the close code and reason of the peer once the
+   * connection is closed. */
+  readonly closed: Promise<WebSocketCloseInfo>;
+  /** Sends a close frame. The messages that arrive until the peer's close
+   * frame are dropped. */
+  close(closeInfo?: WebSocketCloseInfo): void;
+}
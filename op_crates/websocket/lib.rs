@@ -22,6 +22,8 @@ use deno_core::{serde_json, ZeroCopyBuf};
 
 use http::{Method, Request, Uri};
 use serde::Deserialize;
+use sha1::Digest;
+use sha1::Sha1;
 use std::borrow::Cow;
 use std::cell::RefCell;
 use std::io::BufReader;
@@ -29,17 +31,27 @@ use std::io::Cursor;
 use std::path::PathBuf;
 use std::rc::Rc;
 use std::sync::Arc;
+use tokio::io::AsyncRead;
+use tokio::io::AsyncWrite;
+use tokio::io::AsyncWriteExt;
 use tokio::net::TcpStream;
 use tokio_rustls::{rustls::ClientConfig, TlsConnector};
 use tokio_tungstenite::stream::Stream as StreamSwitcher;
+use tokio_tungstenite::tungstenite::handshake::server::ErrorResponse;
+use tokio_tungstenite::tungstenite::handshake::server::Response as HandshakeResponse;
+use tokio_tungstenite::tungstenite::protocol::Role;
 use tokio_tungstenite::tungstenite::Error as TungsteniteError;
 use tokio_tungstenite::tungstenite::{
   handshake::client::Response, protocol::frame::coding::CloseCode,
   protocol::CloseFrame, Message,
 };
-use tokio_tungstenite::{client_async, WebSocketStream};
+use tokio_tungstenite::{accept_hdr_async, client_async, WebSocketStream};
 use webpki::DNSNameRef;
 
+mod deflate;
+
+use deflate::DeflateStream;
+
 pub use tokio_tungstenite; // Re-export tokio_tungstenite
 
 #[derive(Clone)]
@@ -63,7 +75,14 @@ impl WebSocketPermissions for NoWebSocketPermissions {
 type MaybeTlsStream =
   StreamSwitcher<TcpStream, tokio_rustls::client::TlsStream<TcpStream>>;
 
-type WsStream = WebSocketStream<MaybeTlsStream>;
+/// A connection that a WebSocket can run over. Client WebSockets run over the
+/// TCP or TLS streams they connect, and server WebSockets over the accepted
+/// connections that are upgraded with `accept_web_socket()`.
+pub trait WsIo: AsyncRead + AsyncWrite + Unpin {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin> WsIo for T {}
+
+type WsStream = WebSocketStream<Box<dyn WsIo>>;
 struct WsStreamResource {
   tx: AsyncRefCell<SplitSink<WsStream, Message>>,
   rx: AsyncRefCell<SplitStream<WsStream>>,
@@ -79,7 +98,16 @@ impl Resource for WsStreamResource {
   }
 }
 
-impl WsStreamResource {}
+impl WsStreamResource {
+  fn new(stream: WsStream) -> Self {
+    let (ws_tx, ws_rx) = stream.split();
+    Self {
+      rx: AsyncRefCell::new(ws_rx),
+      tx: AsyncRefCell::new(ws_tx),
+      cancel: Default::default(),
+    }
+  }
+}
 
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -112,8 +140,22 @@ where
 struct CreateArgs {
   url: String,
   protocols: String,
+  /// Extra headers of the handshake request, such as `Authorization`.
+  headers: Option<Vec<(String, String)>>,
 }
 
+/// The handshake request headers that are set by the handshake itself, and
+/// can't be set as extra headers.
+const HANDSHAKE_HEADERS: [&str; 7] = [
+  "connection",
+  "host",
+  "sec-websocket-extensions",
+  "sec-websocket-key",
+  "sec-websocket-protocol",
+  "sec-websocket-version",
+  "upgrade",
+];
+
 pub async fn op_ws_create<WP>(
   state: Rc<RefCell<OpState>>,
   args: Value,
@@ -144,6 +186,20 @@ where
     request = request.header("Sec-WebSocket-Protocol", args.protocols);
   }
 
+  for (key, value) in args.headers.unwrap_or_default() {
+    if HANDSHAKE_HEADERS.contains(&key.to_ascii_lowercase().as_str()) {
+      return Err(type_error(format!(
+        "The \"{}\" header can't be set on a WebSocket handshake",
+        key
+      )));
+    }
+    request = request.header(key.as_str(), value.as_str());
+  }
+
+  // Compressed messages are inflated by `DeflateStream` if the server
+  // accepts the extension.
+  request = request.header("Sec-WebSocket-Extensions", deflate::EXTENSION);
+
   let request = request.body(())?;
   let domain = &uri.host().unwrap().to_string();
   let port = &uri.port_u16().unwrap_or(match uri.scheme_str() {
@@ -180,6 +236,7 @@ where
     _ => unreachable!(),
   };
 
+  let socket: Box<dyn WsIo> = Box::new(DeflateStream::new(socket));
   let (stream, response): (WsStream, Response) =
     client_async(request, socket).await.map_err(|err| {
       type_error(format!(
@@ -188,14 +245,8 @@ where
       ))
     })?;
 
-  let (ws_tx, ws_rx) = stream.split();
-  let resource = WsStreamResource {
-    rx: AsyncRefCell::new(ws_rx),
-    tx: AsyncRefCell::new(ws_tx),
-    cancel: Default::default(),
-  };
   let mut state = state.borrow_mut();
-  let rid = state.resource_table.add(resource);
+  let rid = state.resource_table.add(WsStreamResource::new(stream));
 
   let protocol = match response.headers().get("Sec-WebSocket-Protocol") {
     Some(header) => header.to_str().unwrap(),
@@ -215,6 +266,181 @@ where
   }))
 }
 
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AcceptArgs {
+  /// The id of the connection resource.
+  pub rid: u32,
+  /// The subprotocols that the server supports, in order of preference.
+  protocols: Vec<String>,
+  /// The headers of the handshake request, if it has already been read from
+  /// the connection.
+  headers: Option<Vec<(String, String)>>,
+}
+
+/// Picks the first protocol of the server that the client requested.
+fn select_protocol(
+  supported: &[String],
+  requested: Option<&str>,
+) -> Option<String> {
+  let requested: Vec<&str> = requested
+    .unwrap_or("")
+    .split(',')
+    .map(|protocol| protocol.trim())
+    .collect();
+  supported
+    .iter()
+    .find(|protocol| requested.contains(&protocol.as_str()))
+    .cloned()
+}
+
+/// Computes the `Sec-WebSocket-Accept` header of a handshake response.
+fn accept_key(key: &str) -> String {
+  const GUID: &[u8] = b"258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+  let mut sha1 = Sha1::new();
+  sha1.update(key.trim().as_bytes());
+  sha1.update(GUID);
+  base64::encode(sha1.finalize())
+}
+
+/// Validates a handshake request whose headers have already been read, and
+/// returns the response to it.
+fn handshake_response(
+  headers: &[(String, String)],
+  protocol: Option<&str>,
+  extensions: Option<&str>,
+) -> Result<String, AnyError> {
+  let header = |name: &str| {
+    headers
+      .iter()
+      .find(|(key, _)| key.eq_ignore_ascii_case(name))
+      .map(|(_, value)| value.as_str())
+  };
+  let has_token = |name: &str, token: &str| {
+    header(name).map_or(false, |value| {
+      value
+        .split(',')
+        .any(|part| part.trim().eq_ignore_ascii_case(token))
+    })
+  };
+  if !has_token("upgrade", "websocket") {
+    return Err(type_error("Missing \"Upgrade: websocket\" header"));
+  }
+  if !has_token("connection", "upgrade") {
+    return Err(type_error("Missing \"Connection: Upgrade\" header"));
+  }
+  if header("sec-websocket-version") != Some("13") {
+    return Err(type_error("Unsupported \"Sec-WebSocket-Version\" header"));
+  }
+  let key = header("sec-websocket-key")
+    .ok_or_else(|| type_error("Missing \"Sec-WebSocket-Key\" header"))?;
+
+  let mut response = format!(
+    "HTTP/1.1 101 Switching Protocols\r\n\
+     Upgrade: websocket\r\n\
+     Connection: Upgrade\r\n\
+     Sec-WebSocket-Accept: {}\r\n",
+    accept_key(key)
+  );
+  if let Some(protocol) = protocol {
+    response.push_str(&format!("Sec-WebSocket-Protocol: {}\r\n", protocol));
+  }
+  if let Some(extensions) = extensions {
+    response.push_str(&format!("Sec-WebSocket-Extensions: {}\r\n", extensions));
+  }
+  response.push_str("\r\n");
+  Ok(response)
+}
+
+/// Performs the server side of the WebSocket handshake over an accepted
+/// connection, which the embedder has taken out of its resource table. If
+/// `args.headers` is set, the request has already been read and only the
+/// response is sent. The WebSocket is then driven by the same `op_ws_*` ops
+/// as client WebSockets.
+pub async fn accept_web_socket(
+  state: Rc<RefCell<OpState>>,
+  mut stream: Box<dyn WsIo>,
+  args: AcceptArgs,
+) -> Result<Value, AnyError> {
+  let (stream, protocol, extensions, path) = match args.headers {
+    Some(headers) => {
+      let protocol = {
+        let requested = headers
+          .iter()
+          .find(|(key, _)| key.eq_ignore_ascii_case("sec-websocket-protocol"))
+          .map(|(_, value)| value.as_str());
+        select_protocol(&args.protocols, requested)
+      };
+      let extensions = headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case("sec-websocket-extensions"))
+        .and_then(|(_, value)| deflate::accept_offer(value));
+      let response = handshake_response(
+        &headers,
+        protocol.as_deref(),
+        extensions.as_deref(),
+      )?;
+      stream.write_all(response.as_bytes()).await?;
+      stream.flush().await?;
+      let stream: Box<dyn WsIo> =
+        Box::new(DeflateStream::after_handshake(stream, extensions.is_some()));
+      let stream =
+        WebSocketStream::from_raw_socket(stream, Role::Server, None).await;
+      (stream, protocol, extensions, None)
+    }
+    None => {
+      let mut selected = None;
+      let mut extensions = None;
+      let mut path = None;
+      let callback = |request: &Request<()>,
+                      mut response: HandshakeResponse|
+       -> Result<HandshakeResponse, ErrorResponse> {
+        path = Some(request.uri().to_string());
+        let requested = request
+          .headers()
+          .get("Sec-WebSocket-Protocol")
+          .and_then(|value| value.to_str().ok());
+        selected = select_protocol(&args.protocols, requested);
+        if let Some(protocol) = &selected {
+          // The protocol is one of the requested ones, so it's a valid
+          // header value.
+          response
+            .headers_mut()
+            .insert("Sec-WebSocket-Protocol", protocol.parse().unwrap());
+        }
+        extensions = request
+          .headers()
+          .get("Sec-WebSocket-Extensions")
+          .and_then(|value| value.to_str().ok())
+          .and_then(deflate::accept_offer);
+        if let Some(extensions) = &extensions {
+          // The parameters are taken from a valid header value.
+          response
+            .headers_mut()
+            .insert("Sec-WebSocket-Extensions", extensions.parse().unwrap());
+        }
+        Ok(response)
+      };
+      let stream: Box<dyn WsIo> = Box::new(DeflateStream::new(stream));
+      let stream = accept_hdr_async(stream, callback).await.map_err(|err| {
+        type_error(format!("failed to accept WebSocket: {}", err))
+      })?;
+      (stream, selected, extensions, path)
+    }
+  };
+
+  let rid = state
+    .borrow_mut()
+    .resource_table
+    .add(WsStreamResource::new(stream));
+  Ok(json!({
+    "rid": rid,
+    "protocol": protocol.unwrap_or_default(),
+    "extensions": extensions.unwrap_or_default(),
+    "path": path,
+  }))
+}
+
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct SendArgs {
@@ -342,8 +568,69 @@ pub fn init(isolate: &mut JsRuntime) {
       include_str!("01_websocket.js"),
     )
     .unwrap();
+  isolate
+    .execute(
+      "deno:op_crates/websocket/02_websocketstream.js",
+      include_str!("02_websocketstream.js"),
+    )
+    .unwrap();
 }
 
 pub fn get_declaration() -> PathBuf {
   PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("lib.deno_websocket.d.ts")
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn request_headers() -> Vec<(String, String)> {
+    vec![
+      ("Host".to_string(), "localhost".to_string()),
+      ("Upgrade".to_string(), "websocket".to_string()),
+      ("Connection".to_string(), "keep-alive, Upgrade".to_string()),
+      (
+        "Sec-WebSocket-Key".to_string(),
+        "dGhlIHNhbXBsZSBub25jZQ==".to_string(),
+      ),
+      ("Sec-WebSocket-Version".to_string(), "13".to_string()),
+      (
+        "Sec-WebSocket-Protocol".to_string(),
+        "chat, superchat".to_string(),
+      ),
+    ]
+  }
+
+  #[test]
+  fn handshake() {
+    let headers = request_headers();
+    let protocols = vec!["superchat".to_string(), "chat".to_string()];
+    let requested = Some("chat, superchat");
+    let protocol = select_protocol(&protocols, requested);
+    assert_eq!(protocol.as_deref(), Some("superchat"));
+    assert_eq!(select_protocol(&protocols, None), None);
+
+    // The example of RFC 6455, section 1.3.
+    let response =
+      handshake_response(&headers, protocol.as_deref(), None).unwrap();
+    assert_eq!(
+      response,
+      "HTTP/1.1 101 Switching Protocols\r\n\
+       Upgrade: websocket\r\n\
+       Connection: Upgrade\r\n\
+       Sec-WebSocket-Accept: s3pPLMBiTxaQ9kYGzzhZRbK+xOo=\r\n\
+       Sec-WebSocket-Protocol: superchat\r\n\r\n"
+    );
+  }
+
+  #[test]
+  fn invalid_handshake() {
+    let mut headers = request_headers();
+    headers.retain(|(key, _)| key != "Sec-WebSocket-Key");
+    assert!(handshake_response(&headers, None, None).is_err());
+
+    let mut headers = request_headers();
+    headers[1].1 = "h2c".to_string();
+    assert!(handshake_response(&headers, None, None).is_err());
+  }
+}
@@ -365,6 +365,91 @@
     await core.jsonOpAsync("op_fdatasync_async", { rid });
   }
 
+  async function flock(rid, { exclusive = false, range = undefined } = {}) {
+    await core.jsonOpAsync("op_flock_async", { rid, exclusive, range });
+  }
+
+  function tryFlock(rid, { exclusive = false, range = undefined } = {}) {
+    return core.jsonOpSync("op_try_flock", { rid, exclusive, range });
+  }
+
+  function funlock(rid, { range = undefined } = {}) {
+    core.jsonOpSync("op_funlock", { rid, range });
+  }
+
+  function xattrTarget(target) {
+    if (typeof target === "number") {
+      return { rid: target };
+    }
+    return { path: pathFromURL(target) };
+  }
+
+  function xattrValue(value) {
+    if (typeof value === "string") {
+      return new TextEncoder().encode(value);
+    }
+    return value;
+  }
+
+  function getXattrSync(target, name) {
+    const res = core.jsonOpSync("op_get_xattr_sync", {
+      ...xattrTarget(target),
+      name,
+    });
+    return res === null ? null : new Uint8Array(res);
+  }
+
+  async function getXattr(target, name) {
+    const res = await core.jsonOpAsync("op_get_xattr_async", {
+      ...xattrTarget(target),
+      name,
+    });
+    return res === null ? null : new Uint8Array(res);
+  }
+
+  function setXattrSync(target, name, value) {
+    core.jsonOpSync(
+      "op_set_xattr_sync",
+      { ...xattrTarget(target), name },
+      xattrValue(value),
+    );
+  }
+
+  async function setXattr(target, name, value) {
+    await core.jsonOpAsync(
+      "op_set_xattr_async",
+      { ...xattrTarget(target), name },
+      xattrValue(value),
+    );
+  }
+
+  function listXattrSync(target) {
+    return core.jsonOpSync("op_list_xattr_sync", xattrTarget(target));
+  }
+
+  function listXattr(target) {
+    return core.jsonOpAsync("op_list_xattr_async", xattrTarget(target));
+  }
+
+  function removeXattrSync(target, name) {
+    core.jsonOpSync("op_remove_xattr_sync", { ...xattrTarget(target), name });
+  }
+
+  async function removeXattr(target, name) {
+    await core.jsonOpAsync("op_remove_xattr_async", {
+      ...xattrTarget(target),
+      name,
+    });
+  }
+
+  function statfsSync(path) {
+    return core.jsonOpSync("op_statfs_sync", { path: pathFromURL(path) });
+  }
+
+  function statfs(path) {
+    return core.jsonOpAsync("op_statfs_async", { path: pathFromURL(path) });
+  }
+
   function fsyncSync(rid) {
     core.jsonOpSync("op_fsync_sync", { rid });
   }
@@ -421,5 +506,18 @@
     fdatasyncSync,
     fsync,
     fsyncSync,
+    flock,
+    tryFlock,
+    funlock,
+    getXattr,
+    getXattrSync,
+    setXattr,
+    setXattrSync,
+    listXattr,
+    listXattrSync,
+    removeXattr,
+    removeXattrSync,
+    statfs,
+    statfsSync,
   };
 })(this);
@@ -82,6 +82,76 @@
     closeWrite() {
       shutdown(this.rid, ShutdownMode.Write);
     }
+
+    async setNoDelay(noDelay = true) {
+      await core.jsonOpAsync("op_set_nodelay", { rid: this.rid, noDelay });
+    }
+
+    async setKeepAlive(keepAlive = true, initialDelay = undefined) {
+      await core.jsonOpAsync("op_set_keepalive", {
+        rid: this.rid,
+        keepAlive,
+        initialDelay,
+      });
+    }
+  }
+
+  class UnixConn extends Conn {
+    #peerCredentials = null;
+
+    constructor(rid, remoteAddr, localAddr, peerCredentials) {
+      super(rid, remoteAddr, localAddr);
+      this.#peerCredentials = peerCredentials ?? null;
+    }
+
+    get peerCredentials() {
+      return this.#peerCredentials;
+    }
+
+    sendFds(resources, data = new Uint8Array(1)) {
+      return core.jsonOpAsync("op_unix_send_fds", {
+        rid: this.rid,
+        rids: resources.map((resource) => resource.rid),
+      }, data);
+    }
+
+    async receiveFds(p) {
+      const { nread, resources } = await core.jsonOpAsync(
+        "op_unix_receive_fds",
+        { rid: this.rid },
+        p,
+      );
+      return {
+        nread: nread === 0 && resources.length === 0 ? null : nread,
+        resources: resources.map(createReceivedResource),
+      };
+    }
+  }
+
+  function createConn(res) {
+    if (res.localAddr.transport === "unix") {
+      return new UnixConn(
+        res.rid,
+        res.remoteAddr,
+        res.localAddr,
+        res.peerCredentials,
+      );
+    }
+    return new Conn(res.rid, res.remoteAddr, res.localAddr);
+  }
+
+  /** Wraps a resource that `UnixConn.receiveFds()` received. */
+  function createReceivedResource(res) {
+    switch (res.kind) {
+      case "file":
+        return new window.__bootstrap.files.File(res.rid);
+      case "conn":
+        return createConn(res);
+      case "listener":
+        return new Listener(res.rid, res.localAddr);
+      case "datagram":
+        return new Datagram(res.rid, res.localAddr);
+    }
   }
 
   class Listener {
@@ -103,7 +173,7 @@
 
     async accept() {
       const res = await opAccept(this.rid, this.addr.transport);
-      return new Conn(res.rid, res.remoteAddr, res.localAddr);
+      return createConn(res);
     }
 
     async next() {
@@ -169,6 +239,44 @@
       return opSend(args, p);
     }
 
+    #setOption(option, args) {
+      core.jsonOpSync("op_datagram_set_option", {
+        rid: this.rid,
+        option,
+        ...args,
+      });
+    }
+
+    setBroadcast(broadcast = true) {
+      this.#setOption("broadcast", { value: broadcast });
+    }
+
+    setMulticastLoopback(loopback = true) {
+      this.#setOption("multicastLoopback", { value: loopback });
+    }
+
+    setTTL(ttl) {
+      this.#setOption("ttl", { value: ttl });
+    }
+
+    setMulticastTTL(ttl) {
+      this.#setOption("multicastTtl", { value: ttl });
+    }
+
+    joinMulticast(address, networkInterface = undefined) {
+      this.#setOption("joinMulticast", {
+        address,
+        interface: networkInterface,
+      });
+    }
+
+    leaveMulticast(address, networkInterface = undefined) {
+      this.#setOption("leaveMulticast", {
+        address,
+        interface: networkInterface,
+      });
+    }
+
     close() {
       core.close(this.rid);
     }
@@ -216,6 +324,8 @@
   window.__bootstrap.net = {
     connect,
     Conn,
+    UnixConn,
+    createConn,
     opConnect,
     listen,
     opListen,
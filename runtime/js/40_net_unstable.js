@@ -1,7 +1,9 @@
 // Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
 
 ((window) => {
+  const core = window.Deno.core;
   const net = window.__bootstrap.net;
+  const { Headers } = window.__bootstrap.headers;
 
   function listen(options) {
     if (options.transport === "unix") {
@@ -34,15 +36,40 @@
   ) {
     if (options.transport === "unix") {
       const res = await net.opConnect(options);
-      return new net.Conn(res.rid, res.remoteAddr, res.localAddr);
+      return net.createConn(res);
     } else {
       return net.connect(options);
     }
   }
 
+  async function upgradeWebSocket(conn, options = {}) {
+    const { protocols = [], headers } = options;
+    const res = await core.jsonOpAsync("op_ws_upgrade", {
+      rid: conn.rid,
+      protocols,
+      headers: headers == null ? null : [...new Headers(headers)],
+    });
+    const url = res.path ?? options.url ?? "";
+    return window.__bootstrap.webSocket.createServerWebSocket(
+      res.rid,
+      url,
+      res.protocol,
+    );
+  }
+
+  function resolveDns(query, recordType, options = {}) {
+    return core.jsonOpAsync("op_dns_resolve", {
+      query,
+      recordType,
+      nameServer: options.nameServer,
+    });
+  }
+
   window.__bootstrap.netUnstable = {
     connect,
     listenDatagram,
     listen,
+    upgradeWebSocket,
+    resolveDns,
   };
 })(this);
@@ -20,8 +20,10 @@
   }
 
   async function runStatus(rid) {
-    const res = await opRunStatus(rid);
+    return processStatus(await opRunStatus(rid));
+  }
 
+  function processStatus(res) {
     if (res.gotSignal) {
       const signal = res.exitSignal;
       return { success: false, code: 128 + signal, signal };
@@ -32,6 +34,12 @@
     }
   }
 
+  class Pty extends File {
+    resize(columns, rows) {
+      core.jsonOpSync("op_pty_resize", { rid: this.rid, columns, rows });
+    }
+  }
+
   class Process {
     constructor(res) {
       this.rid = res.rid;
@@ -48,6 +56,10 @@
       if (res.stderrRid && res.stderrRid > 0) {
         this.stderr = new File(res.stderrRid);
       }
+
+      if (res.ptyRid != null) {
+        this.pty = new Pty(res.ptyRid);
+      }
     }
 
     status() {
@@ -96,10 +108,22 @@
     stdout = "inherit",
     stderr = "inherit",
     stdin = "inherit",
+    uid = undefined,
+    gid = undefined,
+    detached = undefined,
+    clearEnv = false,
+    killOnDrop = undefined,
+    timeout = undefined,
+    pty = false,
   }) {
     if (cmd[0] != null) {
       cmd[0] = pathFromURL(cmd[0]);
     }
+    if (detached === true) {
+      detached = "group";
+    } else if (detached === false) {
+      detached = undefined;
+    }
     const res = opRun({
       cmd: cmd.map(String),
       cwd,
@@ -110,13 +134,68 @@
       stdinRid: isRid(stdin) ? stdin : 0,
       stdoutRid: isRid(stdout) ? stdout : 0,
       stderrRid: isRid(stderr) ? stderr : 0,
+      uid,
+      gid,
+      detached,
+      clearEnv,
+      killOnDrop,
+      timeout,
+      pty,
     });
     return new Process(res);
   }
 
+  function execArgs({
+    cmd,
+    cwd = undefined,
+    env = {},
+    clearEnv = false,
+    timeout = undefined,
+    maxOutput = undefined,
+    input = undefined,
+  }) {
+    assert(cmd.length > 0);
+    if (cmd[0] != null) {
+      cmd[0] = pathFromURL(cmd[0]);
+    }
+    const args = {
+      cmd: cmd.map(String),
+      cwd,
+      env: Object.entries(env),
+      clearEnv,
+      timeout,
+      maxOutput,
+    };
+    if (input === undefined) {
+      return [args];
+    }
+    if (typeof input === "string") {
+      input = new TextEncoder().encode(input);
+    }
+    return [args, input];
+  }
+
+  function execOutput(res) {
+    const stdout = new Uint8Array(res.stdoutLength);
+    const stderr = new Uint8Array(res.stderrLength);
+    core.jsonOpSync("op_exec_output", { rid: res.rid }, stdout, stderr);
+    return { status: processStatus(res.status), stdout, stderr };
+  }
+
+  function execSync(options) {
+    return execOutput(core.jsonOpSync("op_exec_sync", ...execArgs(options)));
+  }
+
+  async function exec(options) {
+    const res = await core.jsonOpAsync("op_exec_async", ...execArgs(options));
+    return execOutput(res);
+  }
+
   window.__bootstrap.process = {
     run,
     Process,
+    exec,
+    execSync,
     kill: opKill,
   };
 })(this);
@@ -22,25 +22,64 @@
     return core.jsonOpAsync("op_start_tls", args);
   }
 
+  class TlsConn extends Conn {
+    #peerCertificate = null;
+    #alpnProtocol = null;
+
+    constructor(rid, remoteAddr, localAddr, peerCertificate, alpnProtocol) {
+      super(rid, remoteAddr, localAddr);
+      this.#peerCertificate = peerCertificate ?? null;
+      this.#alpnProtocol = alpnProtocol ?? null;
+    }
+
+    get peerCertificate() {
+      return this.#peerCertificate;
+    }
+
+    get alpnProtocol() {
+      return this.#alpnProtocol;
+    }
+  }
+
+  function createTlsConn(res) {
+    return new TlsConn(
+      res.rid,
+      res.remoteAddr,
+      res.localAddr,
+      res.peerCertificate,
+      res.alpnProtocol,
+    );
+  }
+
   async function connectTls({
     port,
     hostname = "127.0.0.1",
     transport = "tcp",
     certFile = undefined,
+    certChain = undefined,
+    certChainFile = undefined,
+    privateKey = undefined,
+    privateKeyFile = undefined,
+    alpnProtocols = undefined,
   }) {
     const res = await opConnectTls({
       port,
       hostname,
       transport,
       certFile,
+      certChain,
+      certChainFile,
+      privateKey,
+      privateKeyFile,
+      alpnProtocols,
     });
-    return new Conn(res.rid, res.remoteAddr, res.localAddr);
+    return createTlsConn(res);
   }
 
   class TLSListener extends Listener {
     async accept() {
       const res = await opAcceptTLS(this.rid);
-      return new Conn(res.rid, res.remoteAddr, res.localAddr);
+      return createTlsConn(res);
     }
   }
 
@@ -50,6 +89,15 @@
     keyFile,
     hostname = "0.0.0.0",
     transport = "tcp",
+    clientCa = undefined,
+    clientCaFile = undefined,
+    requireClientCert = undefined,
+    certChain = undefined,
+    certChainFile = undefined,
+    privateKey = undefined,
+    privateKeyFile = undefined,
+    certificates = undefined,
+    alpnProtocols = undefined,
   }) {
     const res = opListenTls({
       port,
@@ -57,20 +105,42 @@
       keyFile,
       hostname,
       transport,
+      clientCa,
+      clientCaFile,
+      requireClientCert,
+      certChain,
+      certChainFile,
+      privateKey,
+      privateKeyFile,
+      certificates,
+      alpnProtocols,
     });
     return new TLSListener(res.rid, res.localAddr);
   }
 
   async function startTls(
     conn,
-    { hostname = "127.0.0.1", certFile } = {},
+    {
+      hostname = "127.0.0.1",
+      certFile,
+      certChain,
+      certChainFile,
+      privateKey,
+      privateKeyFile,
+      alpnProtocols,
+    } = {},
   ) {
     const res = await opStartTls({
       rid: conn.rid,
       hostname,
       certFile,
+      certChain,
+      certChainFile,
+      privateKey,
+      privateKeyFile,
+      alpnProtocols,
     });
-    return new Conn(res.rid, res.remoteAddr, res.localAddr);
+    return createTlsConn(res);
   }
 
   window.__bootstrap.tls = {
@@ -78,5 +148,6 @@
     listenTls,
     connectTls,
     TLSListener,
+    TlsConn,
   };
 })(this);
@@ -100,6 +100,8 @@
     PermissionStatus: __bootstrap.permissions.PermissionStatus,
     openPlugin: __bootstrap.plugins.openPlugin,
     kill: __bootstrap.process.kill,
+    exec: __bootstrap.process.exec,
+    execSync: __bootstrap.process.execSync,
     setRaw: __bootstrap.tty.setRaw,
     consoleSize: __bootstrap.tty.consoleSize,
     DiagnosticCategory: __bootstrap.diagnostics.DiagnosticCategory,
@@ -116,10 +118,25 @@
     connect: __bootstrap.netUnstable.connect,
     listenDatagram: __bootstrap.netUnstable.listenDatagram,
     startTls: __bootstrap.tls.startTls,
+    upgradeWebSocket: __bootstrap.netUnstable.upgradeWebSocket,
+    resolveDns: __bootstrap.netUnstable.resolveDns,
     fstatSync: __bootstrap.fs.fstatSync,
     fstat: __bootstrap.fs.fstat,
     ftruncateSync: __bootstrap.fs.ftruncateSync,
     ftruncate: __bootstrap.fs.ftruncate,
+    flock: __bootstrap.fs.flock,
+    tryFlock: __bootstrap.fs.tryFlock,
+    funlock: __bootstrap.fs.funlock,
+    getXattr: __bootstrap.fs.getXattr,
+    getXattrSync: __bootstrap.fs.getXattrSync,
+    setXattr: __bootstrap.fs.setXattr,
+    setXattrSync: __bootstrap.fs.setXattrSync,
+    listXattr: __bootstrap.fs.listXattr,
+    listXattrSync: __bootstrap.fs.listXattrSync,
+    removeXattr: __bootstrap.fs.removeXattr,
+    removeXattrSync: __bootstrap.fs.removeXattrSync,
+    statfs: __bootstrap.fs.statfs,
+    statfsSync: __bootstrap.fs.statfsSync,
     umask: __bootstrap.fs.umask,
     link: __bootstrap.fs.link,
     linkSync: __bootstrap.fs.linkSync,
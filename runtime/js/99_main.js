@@ -26,6 +26,7 @@ delete Object.prototype.__proto__;
   const headers = window.__bootstrap.headers;
   const streams = window.__bootstrap.streams;
   const fileReader = window.__bootstrap.fileReader;
+  const compression = window.__bootstrap.compression;
   const webSocket = window.__bootstrap.webSocket;
   const fetch = window.__bootstrap.fetch;
   const prompt = window.__bootstrap.prompt;
@@ -193,6 +194,31 @@ delete Object.prototype.__proto__;
     core.registerErrorClass("SyntaxError", SyntaxError);
     core.registerErrorClass("TypeError", TypeError);
     core.registerErrorClass("URIError", URIError);
+    // Web Crypto ops fail with DOMExceptions of a given name.
+    core.registerErrorClass(
+      "DOMExceptionOperationError",
+      function DOMExceptionOperationError(msg) {
+        return new DOMException(msg, "OperationError");
+      },
+    );
+    core.registerErrorClass(
+      "DOMExceptionNotSupportedError",
+      function DOMExceptionNotSupportedError(msg) {
+        return new DOMException(msg, "NotSupportedError");
+      },
+    );
+    core.registerErrorClass(
+      "DOMExceptionDataError",
+      function DOMExceptionDataError(msg) {
+        return new DOMException(msg, "DataError");
+      },
+    );
+    core.registerErrorClass(
+      "DOMExceptionInvalidAccessError",
+      function DOMExceptionInvalidAccessError(msg) {
+        return new DOMException(msg, "InvalidAccessError");
+      },
+    );
   }
 
   // https://developer.mozilla.org/en-US/docs/Web/API/WindowOrWorkerGlobalScope
@@ -204,11 +230,14 @@ delete Object.prototype.__proto__;
       streams.ByteLengthQueuingStrategy,
     ),
     CloseEvent: util.nonEnumerable(CloseEvent),
+    CompressionStream: util.nonEnumerable(compression.CompressionStream),
     CountQueuingStrategy: util.nonEnumerable(
       streams.CountQueuingStrategy,
     ),
+    CryptoKey: util.nonEnumerable(crypto.CryptoKey),
     CustomEvent: util.nonEnumerable(CustomEvent),
     DOMException: util.nonEnumerable(DOMException),
+    DecompressionStream: util.nonEnumerable(compression.DecompressionStream),
     ErrorEvent: util.nonEnumerable(ErrorEvent),
     Event: util.nonEnumerable(Event),
     EventTarget: util.nonEnumerable(EventTarget),
@@ -225,12 +254,14 @@ delete Object.prototype.__proto__;
     ReadableStream: util.nonEnumerable(streams.ReadableStream),
     Request: util.nonEnumerable(fetch.Request),
     Response: util.nonEnumerable(fetch.Response),
+    SubtleCrypto: util.nonEnumerable(crypto.SubtleCrypto),
     TextDecoder: util.nonEnumerable(TextDecoder),
     TextEncoder: util.nonEnumerable(TextEncoder),
     TransformStream: util.nonEnumerable(streams.TransformStream),
     URL: util.nonEnumerable(url.URL),
     URLSearchParams: util.nonEnumerable(url.URLSearchParams),
     WebSocket: util.nonEnumerable(webSocket.WebSocket),
+    WebSocketStream: util.nonEnumerable(webSocket.WebSocketStream),
     Worker: util.nonEnumerable(worker.Worker),
     WritableStream: util.nonEnumerable(streams.WritableStream),
     atob: util.writable(atob),
@@ -238,7 +269,7 @@ delete Object.prototype.__proto__;
     clearInterval: util.writable(timers.clearInterval),
     clearTimeout: util.writable(timers.clearTimeout),
     console: util.writable(new Console(core.print)),
-    crypto: util.readOnly(crypto),
+    crypto: util.readOnly(crypto.crypto),
     fetch: util.writable(fetch.fetch),
     performance: util.writable(performance.performance),
     setInterval: util.writable(timers.setInterval),
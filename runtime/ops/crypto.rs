@@ -1,7 +1,18 @@
 // Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+use deno_crypto::op_crypto_decrypt;
+use deno_crypto::op_crypto_derive_bits;
+use deno_crypto::op_crypto_drop_key;
+use deno_crypto::op_crypto_encrypt;
+use deno_crypto::op_crypto_export_key;
+use deno_crypto::op_crypto_generate_key;
+use deno_crypto::op_crypto_import_key;
+use deno_crypto::op_crypto_sign;
+use deno_crypto::op_crypto_subtle_digest;
+use deno_crypto::op_crypto_verify;
 use deno_crypto::op_get_random_values;
 use deno_crypto::rand::rngs::StdRng;
 use deno_crypto::rand::SeedableRng;
+use deno_crypto::CryptoKeyTable;
 
 pub fn init(rt: &mut deno_core::JsRuntime, maybe_seed: Option<u64>) {
   if let Some(seed) = maybe_seed {
@@ -10,5 +21,18 @@ pub fn init(rt: &mut deno_core::JsRuntime, maybe_seed: Option<u64>) {
     let mut state = op_state.borrow_mut();
     state.put::<StdRng>(rng);
   }
+  rt.op_state()
+    .borrow_mut()
+    .put::<CryptoKeyTable>(CryptoKeyTable::default());
   super::reg_json_sync(rt, "op_get_random_values", op_get_random_values);
+  super::reg_json_async(rt, "op_crypto_subtle_digest", op_crypto_subtle_digest);
+  super::reg_json_async(rt, "op_crypto_generate_key", op_crypto_generate_key);
+  super::reg_json_async(rt, "op_crypto_sign", op_crypto_sign);
+  super::reg_json_async(rt, "op_crypto_verify", op_crypto_verify);
+  super::reg_json_async(rt, "op_crypto_encrypt", op_crypto_encrypt);
+  super::reg_json_async(rt, "op_crypto_decrypt", op_crypto_decrypt);
+  super::reg_json_sync(rt, "op_crypto_import_key", op_crypto_import_key);
+  super::reg_json_sync(rt, "op_crypto_export_key", op_crypto_export_key);
+  super::reg_json_async(rt, "op_crypto_derive_bits", op_crypto_derive_bits);
+  super::reg_json_sync(rt, "op_crypto_drop_key", op_crypto_drop_key);
 }
@@ -1,6 +1,6 @@
 // Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
 use crate::http_util;
-use crate::permissions::Permissions;
+use crate::permissions::PermissionsContainer;
 use deno_fetch::reqwest;
 
 pub fn init(
@@ -15,7 +15,11 @@ pub fn init(
       http_util::create_http_client(user_agent, ca_data).unwrap()
     });
   }
-  super::reg_json_sync(rt, "op_fetch", deno_fetch::op_fetch::<Permissions>);
+  super::reg_json_sync(
+    rt,
+    "op_fetch",
+    deno_fetch::op_fetch::<PermissionsContainer>,
+  );
   super::reg_json_async(rt, "op_fetch_send", deno_fetch::op_fetch_send);
   super::reg_json_async(
     rt,
@@ -30,6 +34,6 @@ pub fn init(
   super::reg_json_sync(
     rt,
     "op_create_http_client",
-    deno_fetch::op_create_http_client::<Permissions>,
+    deno_fetch::op_create_http_client::<PermissionsContainer>,
   );
 }
@@ -3,7 +3,7 @@
 use super::io::std_file_resource;
 use super::io::StreamResource;
 use crate::fs_util::canonicalize_path;
-use crate::permissions::Permissions;
+use crate::permissions::PermissionsContainer;
 use deno_core::error::bad_resource_id;
 use deno_core::error::custom_error;
 use deno_core::error::type_error;
@@ -12,6 +12,7 @@ use deno_core::serde_json;
 use deno_core::serde_json::json;
 use deno_core::serde_json::Value;
 use deno_core::BufVec;
+use deno_core::CancelTryFuture;
 use deno_core::OpState;
 use deno_core::RcRef;
 use deno_core::ZeroCopyBuf;
@@ -25,6 +26,8 @@ use std::io;
 use std::io::{Seek, SeekFrom};
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
+#[cfg(unix)]
+use std::time::Duration;
 use std::time::SystemTime;
 use std::time::UNIX_EPOCH;
 use tokio::io::AsyncSeekExt;
@@ -108,6 +111,25 @@ pub fn init(rt: &mut deno_core::JsRuntime) {
 
   super::reg_json_sync(rt, "op_utime_sync", op_utime_sync);
   super::reg_json_async(rt, "op_utime_async", op_utime_async);
+
+  super::reg_json_sync(rt, "op_try_flock", op_try_flock);
+  super::reg_json_async(rt, "op_flock_async", op_flock_async);
+  super::reg_json_sync(rt, "op_funlock", op_funlock);
+
+  super::reg_json_sync(rt, "op_get_xattr_sync", op_get_xattr_sync);
+  super::reg_json_async(rt, "op_get_xattr_async", op_get_xattr_async);
+
+  super::reg_json_sync(rt, "op_set_xattr_sync", op_set_xattr_sync);
+  super::reg_json_async(rt, "op_set_xattr_async", op_set_xattr_async);
+
+  super::reg_json_sync(rt, "op_list_xattr_sync", op_list_xattr_sync);
+  super::reg_json_async(rt, "op_list_xattr_async", op_list_xattr_async);
+
+  super::reg_json_sync(rt, "op_remove_xattr_sync", op_remove_xattr_sync);
+  super::reg_json_async(rt, "op_remove_xattr_async", op_remove_xattr_async);
+
+  super::reg_json_sync(rt, "op_statfs_sync", op_statfs_sync);
+  super::reg_json_async(rt, "op_statfs_async", op_statfs_async);
 }
 
 fn into_string(s: std::ffi::OsString) -> Result<String, AnyError> {
@@ -158,7 +180,7 @@ fn open_helper(
     let _ = mode; // avoid unused warning
   }
 
-  let permissions = state.borrow::<Permissions>();
+  let permissions = state.borrow::<PermissionsContainer>();
   let options = args.options;
 
   if options.read {
@@ -464,7 +486,7 @@ fn op_chdir(
 ) -> Result<Value, AnyError> {
   let args: ChdirArgs = serde_json::from_value(args)?;
   let d = PathBuf::from(&args.directory);
-  state.borrow::<Permissions>().check_read(&d)?;
+  state.borrow::<PermissionsContainer>().check_read(&d)?;
   set_current_dir(&d)?;
   Ok(json!({}))
 }
@@ -485,7 +507,7 @@ fn op_mkdir_sync(
   let args: MkdirArgs = serde_json::from_value(args)?;
   let path = Path::new(&args.path).to_path_buf();
   let mode = args.mode.unwrap_or(0o777) & 0o777;
-  state.borrow::<Permissions>().check_write(&path)?;
+  state.borrow::<PermissionsContainer>().check_write(&path)?;
   debug!("op_mkdir {} {:o} {}", path.display(), mode, args.recursive);
   let mut builder = std::fs::DirBuilder::new();
   builder.recursive(args.recursive);
@@ -509,7 +531,7 @@ async fn op_mkdir_async(
 
   {
     let state = state.borrow();
-    state.borrow::<Permissions>().check_write(&path)?;
+    state.borrow::<PermissionsContainer>().check_write(&path)?;
   }
 
   tokio::task::spawn_blocking(move || {
@@ -544,7 +566,7 @@ fn op_chmod_sync(
   let path = Path::new(&args.path).to_path_buf();
   let mode = args.mode & 0o777;
 
-  state.borrow::<Permissions>().check_write(&path)?;
+  state.borrow::<PermissionsContainer>().check_write(&path)?;
   debug!("op_chmod_sync {} {:o}", path.display(), mode);
   #[cfg(unix)]
   {
@@ -573,7 +595,7 @@ async fn op_chmod_async(
 
   {
     let state = state.borrow();
-    state.borrow::<Permissions>().check_write(&path)?;
+    state.borrow::<PermissionsContainer>().check_write(&path)?;
   }
 
   tokio::task::spawn_blocking(move || {
@@ -612,7 +634,7 @@ fn op_chown_sync(
 ) -> Result<Value, AnyError> {
   let args: ChownArgs = serde_json::from_value(args)?;
   let path = Path::new(&args.path).to_path_buf();
-  state.borrow::<Permissions>().check_write(&path)?;
+  state.borrow::<PermissionsContainer>().check_write(&path)?;
   debug!(
     "op_chown_sync {} {:?} {:?}",
     path.display(),
@@ -644,7 +666,7 @@ async fn op_chown_async(
 
   {
     let state = state.borrow();
-    state.borrow::<Permissions>().check_write(&path)?;
+    state.borrow::<PermissionsContainer>().check_write(&path)?;
   }
 
   tokio::task::spawn_blocking(move || {
@@ -686,7 +708,7 @@ fn op_remove_sync(
   let path = PathBuf::from(&args.path);
   let recursive = args.recursive;
 
-  state.borrow::<Permissions>().check_write(&path)?;
+  state.borrow::<PermissionsContainer>().check_write(&path)?;
 
   #[cfg(not(unix))]
   use std::os::windows::prelude::MetadataExt;
@@ -731,7 +753,7 @@ async fn op_remove_async(
 
   {
     let state = state.borrow();
-    state.borrow::<Permissions>().check_write(&path)?;
+    state.borrow::<PermissionsContainer>().check_write(&path)?;
   }
 
   tokio::task::spawn_blocking(move || {
@@ -786,7 +808,7 @@ fn op_copy_file_sync(
   let from = PathBuf::from(&args.from);
   let to = PathBuf::from(&args.to);
 
-  let permissions = state.borrow::<Permissions>();
+  let permissions = state.borrow::<PermissionsContainer>();
   permissions.check_read(&from)?;
   permissions.check_write(&to)?;
 
@@ -814,7 +836,7 @@ async fn op_copy_file_async(
 
   {
     let state = state.borrow();
-    let permissions = state.borrow::<Permissions>();
+    let permissions = state.borrow::<PermissionsContainer>();
     permissions.check_read(&from)?;
     permissions.check_write(&to)?;
   }
@@ -909,7 +931,7 @@ fn op_stat_sync(
   let args: StatArgs = serde_json::from_value(args)?;
   let path = PathBuf::from(&args.path);
   let lstat = args.lstat;
-  state.borrow::<Permissions>().check_read(&path)?;
+  state.borrow::<PermissionsContainer>().check_read(&path)?;
   debug!("op_stat_sync {} {}", path.display(), lstat);
   let metadata = if lstat {
     std::fs::symlink_metadata(&path)?
@@ -930,7 +952,7 @@ async fn op_stat_async(
 
   {
     let state = state.borrow();
-    state.borrow::<Permissions>().check_read(&path)?;
+    state.borrow::<PermissionsContainer>().check_read(&path)?;
   }
 
   tokio::task::spawn_blocking(move || {
@@ -960,7 +982,7 @@ fn op_realpath_sync(
   let args: RealpathArgs = serde_json::from_value(args)?;
   let path = PathBuf::from(&args.path);
 
-  let permissions = state.borrow::<Permissions>();
+  let permissions = state.borrow::<PermissionsContainer>();
   permissions.check_read(&path)?;
   if path.is_relative() {
     permissions.check_read_blind(&current_dir()?, "CWD")?;
@@ -984,7 +1006,7 @@ async fn op_realpath_async(
 
   {
     let state = state.borrow();
-    let permissions = state.borrow::<Permissions>();
+    let permissions = state.borrow::<PermissionsContainer>();
     permissions.check_read(&path)?;
     if path.is_relative() {
       permissions.check_read_blind(&current_dir()?, "CWD")?;
@@ -1017,7 +1039,7 @@ fn op_read_dir_sync(
   let args: ReadDirArgs = serde_json::from_value(args)?;
   let path = PathBuf::from(&args.path);
 
-  state.borrow::<Permissions>().check_read(&path)?;
+  state.borrow::<PermissionsContainer>().check_read(&path)?;
 
   debug!("op_read_dir_sync {}", path.display());
   let entries: Vec<_> = std::fs::read_dir(path)?
@@ -1050,7 +1072,7 @@ async fn op_read_dir_async(
   let path = PathBuf::from(&args.path);
   {
     let state = state.borrow();
-    state.borrow::<Permissions>().check_read(&path)?;
+    state.borrow::<PermissionsContainer>().check_read(&path)?;
   }
   tokio::task::spawn_blocking(move || {
     debug!("op_read_dir_async {}", path.display());
@@ -1094,7 +1116,7 @@ fn op_rename_sync(
   let oldpath = PathBuf::from(&args.oldpath);
   let newpath = PathBuf::from(&args.newpath);
 
-  let permissions = state.borrow::<Permissions>();
+  let permissions = state.borrow::<PermissionsContainer>();
   permissions.check_read(&oldpath)?;
   permissions.check_write(&oldpath)?;
   permissions.check_write(&newpath)?;
@@ -1113,7 +1135,7 @@ async fn op_rename_async(
   let newpath = PathBuf::from(&args.newpath);
   {
     let state = state.borrow();
-    let permissions = state.borrow::<Permissions>();
+    let permissions = state.borrow::<PermissionsContainer>();
     permissions.check_read(&oldpath)?;
     permissions.check_write(&oldpath)?;
     permissions.check_write(&newpath)?;
@@ -1148,7 +1170,7 @@ fn op_link_sync(
   let oldpath = PathBuf::from(&args.oldpath);
   let newpath = PathBuf::from(&args.newpath);
 
-  let permissions = state.borrow::<Permissions>();
+  let permissions = state.borrow::<PermissionsContainer>();
   permissions.check_read(&oldpath)?;
   permissions.check_write(&newpath)?;
 
@@ -1170,7 +1192,7 @@ async fn op_link_async(
 
   {
     let state = state.borrow();
-    let permissions = state.borrow::<Permissions>();
+    let permissions = state.borrow::<PermissionsContainer>();
     permissions.check_read(&oldpath)?;
     permissions.check_write(&newpath)?;
   }
@@ -1210,7 +1232,9 @@ fn op_symlink_sync(
   let oldpath = PathBuf::from(&args.oldpath);
   let newpath = PathBuf::from(&args.newpath);
 
-  state.borrow::<Permissions>().check_write(&newpath)?;
+  state
+    .borrow::<PermissionsContainer>()
+    .check_write(&newpath)?;
 
   debug!(
     "op_symlink_sync {} {}",
@@ -1264,7 +1288,9 @@ async fn op_symlink_async(
 
   {
     let state = state.borrow();
-    state.borrow::<Permissions>().check_write(&newpath)?;
+    state
+      .borrow::<PermissionsContainer>()
+      .check_write(&newpath)?;
   }
 
   tokio::task::spawn_blocking(move || {
@@ -1320,7 +1346,7 @@ fn op_read_link_sync(
   let args: ReadLinkArgs = serde_json::from_value(args)?;
   let path = PathBuf::from(&args.path);
 
-  state.borrow::<Permissions>().check_read(&path)?;
+  state.borrow::<PermissionsContainer>().check_read(&path)?;
 
   debug!("op_read_link_value {}", path.display());
   let target = std::fs::read_link(&path)?.into_os_string();
@@ -1337,7 +1363,7 @@ async fn op_read_link_async(
   let path = PathBuf::from(&args.path);
   {
     let state = state.borrow();
-    state.borrow::<Permissions>().check_read(&path)?;
+    state.borrow::<PermissionsContainer>().check_read(&path)?;
   }
   tokio::task::spawn_blocking(move || {
     debug!("op_read_link_async {}", path.display());
@@ -1416,7 +1442,7 @@ fn op_truncate_sync(
   let path = PathBuf::from(&args.path);
   let len = args.len;
 
-  state.borrow::<Permissions>().check_write(&path)?;
+  state.borrow::<PermissionsContainer>().check_write(&path)?;
 
   debug!("op_truncate_sync {} {}", path.display(), len);
   let f = std::fs::OpenOptions::new().write(true).open(&path)?;
@@ -1434,7 +1460,7 @@ async fn op_truncate_async(
   let len = args.len;
   {
     let state = state.borrow();
-    state.borrow::<Permissions>().check_write(&path)?;
+    state.borrow::<PermissionsContainer>().check_write(&path)?;
   }
   tokio::task::spawn_blocking(move || {
     debug!("op_truncate_async {} {}", path.display(), len);
@@ -1511,7 +1537,7 @@ fn op_make_temp_dir_sync(
   let suffix = args.suffix.map(String::from);
 
   state
-    .borrow::<Permissions>()
+    .borrow::<PermissionsContainer>()
     .check_write(dir.clone().unwrap_or_else(temp_dir).as_path())?;
 
   // TODO(piscisaureus): use byte vector for paths, not a string.
@@ -1542,7 +1568,7 @@ async fn op_make_temp_dir_async(
   {
     let state = state.borrow();
     state
-      .borrow::<Permissions>()
+      .borrow::<PermissionsContainer>()
       .check_write(dir.clone().unwrap_or_else(temp_dir).as_path())?;
   }
   tokio::task::spawn_blocking(move || {
@@ -1576,7 +1602,7 @@ fn op_make_temp_file_sync(
   let suffix = args.suffix.map(String::from);
 
   state
-    .borrow::<Permissions>()
+    .borrow::<PermissionsContainer>()
     .check_write(dir.clone().unwrap_or_else(temp_dir).as_path())?;
 
   // TODO(piscisaureus): use byte vector for paths, not a string.
@@ -1607,7 +1633,7 @@ async fn op_make_temp_file_async(
   {
     let state = state.borrow();
     state
-      .borrow::<Permissions>()
+      .borrow::<PermissionsContainer>()
       .check_write(dir.clone().unwrap_or_else(temp_dir).as_path())?;
   }
   tokio::task::spawn_blocking(move || {
@@ -1723,7 +1749,7 @@ fn op_utime_sync(
   let atime = filetime::FileTime::from_unix_time(args.atime.0, args.atime.1);
   let mtime = filetime::FileTime::from_unix_time(args.mtime.0, args.mtime.1);
 
-  state.borrow::<Permissions>().check_write(&path)?;
+  state.borrow::<PermissionsContainer>().check_write(&path)?;
   filetime::set_file_times(path, atime, mtime)?;
   Ok(json!({}))
 }
@@ -1740,7 +1766,10 @@ async fn op_utime_async(
   let atime = filetime::FileTime::from_unix_time(args.atime.0, args.atime.1);
   let mtime = filetime::FileTime::from_unix_time(args.mtime.0, args.mtime.1);
 
-  state.borrow().borrow::<Permissions>().check_write(&path)?;
+  state
+    .borrow()
+    .borrow::<PermissionsContainer>()
+    .check_write(&path)?;
 
   tokio::task::spawn_blocking(move || {
     filetime::set_file_times(path, atime, mtime)?;
@@ -1750,6 +1779,794 @@ async fn op_utime_async(
   .unwrap()
 }
 
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FlockArgs {
+  rid: i32,
+  #[serde(default)]
+  exclusive: bool,
+  /// A byte range to lock with `fcntl()`, rather than the whole file with
+  /// `flock()`.
+  range: Option<LockRange>,
+}
+
+#[derive(Clone, Copy, Deserialize)]
+struct LockRange {
+  start: u64,
+  /// `0` means up to the end of the file, however far it grows.
+  #[serde(default)]
+  length: u64,
+}
+
+#[derive(Clone, Copy)]
+enum LockOp {
+  Shared,
+  Exclusive,
+  Unlock,
+}
+
+/// Takes or releases an advisory lock, and returns whether that succeeded. A
+/// conflicting lock is waited for if `wait` is set, and makes this fail
+/// otherwise.
+#[cfg(unix)]
+fn lock_file(
+  file: &std::fs::File,
+  op: LockOp,
+  range: Option<LockRange>,
+  wait: bool,
+) -> Result<bool, AnyError> {
+  use std::convert::TryFrom;
+  use std::os::unix::io::AsRawFd;
+
+  let fd = file.as_raw_fd();
+  let mut lock_range: Option<libc::flock> = None;
+  if let Some(range) = range {
+    let invalid = |_| type_error("The lock range is too large");
+    // Safety: `libc::flock` is a plain C struct.
+    let mut lock: libc::flock = unsafe { std::mem::zeroed() };
+    lock.l_type = match op {
+      LockOp::Shared => libc::F_RDLCK,
+      LockOp::Exclusive => libc::F_WRLCK,
+      LockOp::Unlock => libc::F_UNLCK,
+    } as _;
+    lock.l_whence = libc::SEEK_SET as _;
+    lock.l_start = libc::off_t::try_from(range.start).map_err(invalid)?;
+    lock.l_len = libc::off_t::try_from(range.length).map_err(invalid)?;
+    lock_range = Some(lock);
+  }
+  loop {
+    let result = match &lock_range {
+      None => {
+        let operation = match op {
+          LockOp::Shared => libc::LOCK_SH,
+          LockOp::Exclusive => libc::LOCK_EX,
+          LockOp::Unlock => libc::LOCK_UN,
+        };
+        let flags = if wait { 0 } else { libc::LOCK_NB };
+        unsafe { libc::flock(fd, operation | flags) }
+      }
+      Some(lock) => {
+        let cmd = if wait { libc::F_SETLKW } else { libc::F_SETLK };
+        unsafe { libc::fcntl(fd, cmd, lock) }
+      }
+    };
+    if result == 0 {
+      return Ok(true);
+    }
+
+    let err = io::Error::last_os_error();
+    match err.raw_os_error() {
+      Some(code) if code == libc::EINTR => {}
+      Some(code)
+        if code == libc::EWOULDBLOCK
+          || code == libc::EAGAIN
+          || code == libc::EACCES =>
+      {
+        return Ok(false)
+      }
+      _ => return Err(err.into()),
+    }
+  }
+}
+
+#[cfg(windows)]
+fn lock_file(
+  file: &std::fs::File,
+  op: LockOp,
+  range: Option<LockRange>,
+  wait: bool,
+) -> Result<bool, AnyError> {
+  use std::os::windows::io::AsRawHandle;
+  use winapi::shared::winerror::ERROR_LOCK_VIOLATION;
+  use winapi::um::fileapi::LockFileEx;
+  use winapi::um::fileapi::UnlockFileEx;
+  use winapi::um::minwinbase::LOCKFILE_EXCLUSIVE_LOCK;
+  use winapi::um::minwinbase::LOCKFILE_FAIL_IMMEDIATELY;
+  use winapi::um::minwinbase::OVERLAPPED;
+
+  // The whole file is locked as the largest possible range.
+  let (start, length) = match range {
+    Some(LockRange { start, length }) if length > 0 => (start, length),
+    Some(LockRange { start, .. }) => (start, u64::MAX - start),
+    None => (0, u64::MAX),
+  };
+  let handle = file.as_raw_handle();
+  // Safety: `OVERLAPPED` is a plain C struct, which only gives the offset of
+  // the range here.
+  let result = unsafe {
+    let mut overlapped: OVERLAPPED = std::mem::zeroed();
+    overlapped.u.s_mut().Offset = start as u32;
+    overlapped.u.s_mut().OffsetHigh = (start >> 32) as u32;
+    let (low, high) = (length as u32, (length >> 32) as u32);
+    match op {
+      LockOp::Unlock => UnlockFileEx(handle, 0, low, high, &mut overlapped),
+      LockOp::Shared | LockOp::Exclusive => {
+        let mut flags = 0;
+        if !wait {
+          flags |= LOCKFILE_FAIL_IMMEDIATELY;
+        }
+        if let LockOp::Exclusive = op {
+          flags |= LOCKFILE_EXCLUSIVE_LOCK;
+        }
+        LockFileEx(handle, flags, 0, low, high, &mut overlapped)
+      }
+    }
+  };
+  if result != 0 {
+    return Ok(true);
+  }
+
+  let err = io::Error::last_os_error();
+  match err.raw_os_error() {
+    Some(code) if code == ERROR_LOCK_VIOLATION as i32 => Ok(false),
+    _ => Err(err.into()),
+  }
+}
+
+fn try_lock_resource(
+  state: &mut OpState,
+  rid: u32,
+  op: LockOp,
+  range: Option<LockRange>,
+) -> Result<bool, AnyError> {
+  std_file_resource(state, rid, |r| match r {
+    Ok(std_file) => lock_file(std_file, op, range, false),
+    Err(_) => Err(type_error("cannot lock this type of resource")),
+  })
+}
+
+fn lock_op(exclusive: bool) -> LockOp {
+  if exclusive {
+    LockOp::Exclusive
+  } else {
+    LockOp::Shared
+  }
+}
+
+fn op_try_flock(
+  state: &mut OpState,
+  args: Value,
+  _zero_copy: &mut [ZeroCopyBuf],
+) -> Result<Value, AnyError> {
+  super::check_unstable(state, "Deno.tryFlock");
+  let args: FlockArgs = serde_json::from_value(args)?;
+  let op = lock_op(args.exclusive);
+  let locked = try_lock_resource(state, args.rid as u32, op, args.range)?;
+  Ok(json!(locked))
+}
+
+/// The longest time to wait before trying to take a byte-range lock again.
+#[cfg(unix)]
+const MAX_LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Waits for a lock on a thread of its own, which blocks on a duplicate of
+/// the descriptor of the file, so that the lock is granted in turn with the
+/// waiters of other processes. Closing the file rejects the wait, but can't
+/// interrupt the thread, which keeps the duplicate open until then.
+///
+/// Byte-range locks on Unix are held by the process, and closing any
+/// descriptor of the file releases them, duplicates included, so those are
+/// waited for by trying to take them again and again instead.
+async fn op_flock_async(
+  state: Rc<RefCell<OpState>>,
+  args: Value,
+  _zero_copy: BufVec,
+) -> Result<Value, AnyError> {
+  super::check_unstable2(&state, "Deno.flock");
+  let args: FlockArgs = serde_json::from_value(args)?;
+  let rid = args.rid as u32;
+  let op = lock_op(args.exclusive);
+
+  let resource = state
+    .borrow()
+    .resource_table
+    .get::<StreamResource>(rid)
+    .ok_or_else(bad_resource_id)?;
+  let cancel = resource.cancel_handle();
+
+  #[cfg(unix)]
+  if args.range.is_some() {
+    let lock = async {
+      let mut interval = Duration::from_millis(1);
+      loop {
+        if try_lock_resource(&mut state.borrow_mut(), rid, op, args.range)? {
+          return Ok::<_, AnyError>(());
+        }
+        tokio::time::sleep(interval).await;
+        interval = (interval * 2).min(MAX_LOCK_RETRY_INTERVAL);
+      }
+    };
+    lock.try_or_cancel(cancel).await?;
+    return Ok(json!({}));
+  }
+
+  let file = std_file_resource(&mut state.borrow_mut(), rid, |r| match r {
+    Ok(std_file) => std_file.try_clone().map_err(AnyError::from),
+    Err(_) => Err(type_error("cannot lock this type of resource")),
+  })?;
+  let (tx, rx) = tokio::sync::oneshot::channel();
+  // Not a blocking task of the runtime, which would wait for it to finish
+  // when shutting down.
+  std::thread::spawn(move || {
+    let _ = tx.send(lock_file(&file, op, args.range, true));
+  });
+  let lock = async { rx.await?.map(drop) };
+  lock.try_or_cancel(cancel).await?;
+  Ok(json!({}))
+}
+
+fn op_funlock(
+  state: &mut OpState,
+  args: Value,
+  _zero_copy: &mut [ZeroCopyBuf],
+) -> Result<Value, AnyError> {
+  super::check_unstable(state, "Deno.funlock");
+  let args: FlockArgs = serde_json::from_value(args)?;
+  try_lock_resource(state, args.rid as u32, LockOp::Unlock, args.range)?;
+  Ok(json!({}))
+}
+
+/// Extended attributes, which are only supported on Linux and macOS.
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+mod xattr {
+  use std::ffi::CStr;
+  use std::ffi::CString;
+  use std::io;
+  use std::os::raw::c_char;
+  use std::os::raw::c_int;
+  use std::os::raw::c_void;
+  use std::os::unix::io::RawFd;
+
+  /// The file whose attributes are accessed, which is given by its path, or
+  /// by an open descriptor.
+  pub enum Target {
+    Path(CString),
+    Fd(RawFd),
+  }
+
+  #[cfg(target_os = "linux")]
+  const ENOATTR: c_int = libc::ENODATA;
+  #[cfg(target_os = "macos")]
+  const ENOATTR: c_int = libc::ENOATTR;
+
+  #[cfg(target_os = "linux")]
+  unsafe fn getxattr(
+    target: &Target,
+    name: *const c_char,
+    value: *mut c_void,
+    size: usize,
+  ) -> isize {
+    match target {
+      Target::Path(path) => libc::getxattr(path.as_ptr(), name, value, size),
+      Target::Fd(fd) => libc::fgetxattr(*fd, name, value, size),
+    }
+  }
+
+  #[cfg(target_os = "macos")]
+  unsafe fn getxattr(
+    target: &Target,
+    name: *const c_char,
+    value: *mut c_void,
+    size: usize,
+  ) -> isize {
+    match target {
+      Target::Path(path) => {
+        libc::getxattr(path.as_ptr(), name, value, size, 0, 0)
+      }
+      Target::Fd(fd) => libc::fgetxattr(*fd, name, value, size, 0, 0),
+    }
+  }
+
+  #[cfg(target_os = "linux")]
+  unsafe fn setxattr(
+    target: &Target,
+    name: *const c_char,
+    value: *const c_void,
+    size: usize,
+  ) -> c_int {
+    match target {
+      Target::Path(path) => libc::setxattr(path.as_ptr(), name, value, size, 0),
+      Target::Fd(fd) => libc::fsetxattr(*fd, name, value, size, 0),
+    }
+  }
+
+  #[cfg(target_os = "macos")]
+  unsafe fn setxattr(
+    target: &Target,
+    name: *const c_char,
+    value: *const c_void,
+    size: usize,
+  ) -> c_int {
+    match target {
+      Target::Path(path) => {
+        libc::setxattr(path.as_ptr(), name, value, size, 0, 0)
+      }
+      Target::Fd(fd) => libc::fsetxattr(*fd, name, value, size, 0, 0),
+    }
+  }
+
+  #[cfg(target_os = "linux")]
+  unsafe fn listxattr(
+    target: &Target,
+    list: *mut c_char,
+    size: usize,
+  ) -> isize {
+    match target {
+      Target::Path(path) => libc::listxattr(path.as_ptr(), list, size),
+      Target::Fd(fd) => libc::flistxattr(*fd, list, size),
+    }
+  }
+
+  #[cfg(target_os = "macos")]
+  unsafe fn listxattr(
+    target: &Target,
+    list: *mut c_char,
+    size: usize,
+  ) -> isize {
+    match target {
+      Target::Path(path) => libc::listxattr(path.as_ptr(), list, size, 0),
+      Target::Fd(fd) => libc::flistxattr(*fd, list, size, 0),
+    }
+  }
+
+  #[cfg(target_os = "linux")]
+  unsafe fn removexattr(target: &Target, name: *const c_char) -> c_int {
+    match target {
+      Target::Path(path) => libc::removexattr(path.as_ptr(), name),
+      Target::Fd(fd) => libc::fremovexattr(*fd, name),
+    }
+  }
+
+  #[cfg(target_os = "macos")]
+  unsafe fn removexattr(target: &Target, name: *const c_char) -> c_int {
+    match target {
+      Target::Path(path) => libc::removexattr(path.as_ptr(), name, 0),
+      Target::Fd(fd) => libc::fremovexattr(*fd, name, 0),
+    }
+  }
+
+  fn check(result: isize) -> io::Result<usize> {
+    if result < 0 {
+      Err(io::Error::last_os_error())
+    } else {
+      Ok(result as usize)
+    }
+  }
+
+  /// Reads a value of unknown size, which `read` first returns the size of
+  /// when given an empty buffer.
+  fn read_sized(
+    read: impl Fn(*mut c_void, usize) -> isize,
+  ) -> io::Result<Vec<u8>> {
+    loop {
+      let size = check(read(std::ptr::null_mut(), 0))?;
+      let mut buf = vec![0u8; size];
+      match check(read(buf.as_mut_ptr() as *mut c_void, size)) {
+        Ok(len) => {
+          buf.truncate(len);
+          return Ok(buf);
+        }
+        // The value grew in between.
+        Err(err) if err.raw_os_error() == Some(libc::ERANGE) => continue,
+        Err(err) => return Err(err),
+      }
+    }
+  }
+
+  pub fn get(target: &Target, name: &CStr) -> io::Result<Option<Vec<u8>>> {
+    let value = read_sized(|value, size| unsafe {
+      getxattr(target, name.as_ptr(), value, size)
+    });
+    match value {
+      Ok(value) => Ok(Some(value)),
+      Err(err) if err.raw_os_error() == Some(ENOATTR) => Ok(None),
+      Err(err) => Err(err),
+    }
+  }
+
+  pub fn set(target: &Target, name: &CStr, value: &[u8]) -> io::Result<()> {
+    let value_ptr = value.as_ptr() as *const c_void;
+    let result =
+      unsafe { setxattr(target, name.as_ptr(), value_ptr, value.len()) };
+    check(result as isize).map(drop)
+  }
+
+  pub fn list(target: &Target) -> io::Result<Vec<String>> {
+    let list = read_sized(|list, size| unsafe {
+      listxattr(target, list as *mut c_char, size)
+    })?;
+    // The names are each terminated by a NUL byte.
+    let names = list
+      .split(|&byte| byte == 0)
+      .filter(|name| !name.is_empty())
+      .map(|name| String::from_utf8_lossy(name).into_owned())
+      .collect();
+    Ok(names)
+  }
+
+  pub fn remove(target: &Target, name: &CStr) -> io::Result<()> {
+    let result = unsafe { removexattr(target, name.as_ptr()) };
+    check(result as isize).map(drop)
+  }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct XattrArgs {
+  path: Option<String>,
+  rid: Option<i32>,
+  #[serde(default)]
+  name: String,
+}
+
+#[derive(Clone, Copy)]
+enum XattrOp {
+  Get,
+  Set,
+  List,
+  Remove,
+}
+
+impl XattrOp {
+  fn api_name(self) -> &'static str {
+    match self {
+      XattrOp::Get => "Deno.getXattr",
+      XattrOp::Set => "Deno.setXattr",
+      XattrOp::List => "Deno.listXattr",
+      XattrOp::Remove => "Deno.removeXattr",
+    }
+  }
+
+  /// Reading attributes of a path needs read permission, and changing them
+  /// needs write permission.
+  fn check_permission(
+    self,
+    permissions: &PermissionsContainer,
+    path: &Path,
+  ) -> Result<(), AnyError> {
+    match self {
+      XattrOp::Get | XattrOp::List => permissions.check_read(path),
+      XattrOp::Set | XattrOp::Remove => permissions.check_write(path),
+    }
+  }
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn xattr_path(path: &Path) -> Result<xattr::Target, AnyError> {
+  use std::ffi::CString;
+  use std::os::unix::ffi::OsStrExt;
+  let path = CString::new(path.as_os_str().as_bytes())
+    .map_err(|_| type_error("The path contains a NUL byte"))?;
+  Ok(xattr::Target::Path(path))
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn xattr(
+  target: &xattr::Target,
+  op: XattrOp,
+  name: &str,
+  value: Option<&[u8]>,
+) -> Result<Value, AnyError> {
+  use std::ffi::CString;
+  let name = || {
+    CString::new(name)
+      .map_err(|_| type_error("The attribute name contains a NUL byte"))
+  };
+  match op {
+    XattrOp::Get => Ok(json!(xattr::get(target, &name()?)?)),
+    XattrOp::Set => {
+      let value = value.ok_or_else(|| type_error("No value given"))?;
+      xattr::set(target, &name()?, value)?;
+      Ok(json!({}))
+    }
+    XattrOp::List => Ok(json!(xattr::list(target)?)),
+    XattrOp::Remove => {
+      xattr::remove(target, &name()?)?;
+      Ok(json!({}))
+    }
+  }
+}
+
+/// Runs an extended attribute operation on a file, which is given by its
+/// path or by the rid of an open file.
+fn xattr_sync(
+  state: &mut OpState,
+  args: Value,
+  value: Option<&[u8]>,
+  op: XattrOp,
+) -> Result<Value, AnyError> {
+  super::check_unstable(state, op.api_name());
+  let args: XattrArgs = serde_json::from_value(args)?;
+  if let Some(path) = &args.path {
+    op.check_permission(
+      state.borrow::<PermissionsContainer>(),
+      Path::new(path),
+    )?;
+  }
+
+  #[cfg(any(target_os = "linux", target_os = "macos"))]
+  {
+    use std::os::unix::io::AsRawFd;
+    match (args.path, args.rid) {
+      (Some(path), _) => {
+        xattr(&xattr_path(Path::new(&path))?, op, &args.name, value)
+      }
+      (None, Some(rid)) => std_file_resource(state, rid as u32, |r| match r {
+        Ok(std_file) => {
+          let target = xattr::Target::Fd(std_file.as_raw_fd());
+          xattr(&target, op, &args.name, value)
+        }
+        Err(_) => Err(type_error("cannot access attributes of this resource")),
+      }),
+      (None, None) => Err(type_error("A path or rid is required")),
+    }
+  }
+  #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+  {
+    let _ = (args, value);
+    Err(deno_core::error::not_supported())
+  }
+}
+
+async fn xattr_async(
+  state: Rc<RefCell<OpState>>,
+  args: Value,
+  value: Option<Vec<u8>>,
+  op: XattrOp,
+) -> Result<Value, AnyError> {
+  super::check_unstable2(&state, op.api_name());
+  let args: XattrArgs = serde_json::from_value(args)?;
+  if let Some(path) = &args.path {
+    let state = state.borrow();
+    op.check_permission(
+      state.borrow::<PermissionsContainer>(),
+      Path::new(path),
+    )?;
+  }
+
+  #[cfg(any(target_os = "linux", target_os = "macos"))]
+  {
+    use std::os::unix::io::AsRawFd;
+    let file = match (&args.path, args.rid) {
+      (Some(_), _) => None,
+      (None, Some(rid)) => Some(clone_std_file(&state, rid as u32).await?),
+      (None, None) => return Err(type_error("A path or rid is required")),
+    };
+
+    tokio::task::spawn_blocking(move || {
+      let target = match (&file, &args.path) {
+        (Some(file), _) => xattr::Target::Fd(file.as_raw_fd()),
+        (None, path) => xattr_path(Path::new(path.as_ref().unwrap()))?,
+      };
+      xattr(&target, op, &args.name, value.as_deref())
+    })
+    .await
+    .unwrap()
+  }
+  #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+  {
+    let _ = (args, value);
+    Err(deno_core::error::not_supported())
+  }
+}
+
+/// Returns a duplicate of an open file, which can be used on another thread.
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+async fn clone_std_file(
+  state: &Rc<RefCell<OpState>>,
+  rid: u32,
+) -> Result<std::fs::File, AnyError> {
+  let resource = state
+    .borrow_mut()
+    .resource_table
+    .get::<StreamResource>(rid)
+    .ok_or_else(bad_resource_id)?;
+
+  if resource.fs_file.is_none() {
+    return Err(bad_resource_id());
+  }
+
+  let mut fs_file = RcRef::map(&resource, |r| r.fs_file.as_ref().unwrap())
+    .borrow_mut()
+    .await;
+  let std_file = (*fs_file)
+    .0
+    .as_mut()
+    .unwrap()
+    .try_clone()
+    .await?
+    .into_std()
+    .await;
+  Ok(std_file)
+}
+
+fn op_get_xattr_sync(
+  state: &mut OpState,
+  args: Value,
+  _zero_copy: &mut [ZeroCopyBuf],
+) -> Result<Value, AnyError> {
+  xattr_sync(state, args, None, XattrOp::Get)
+}
+
+async fn op_get_xattr_async(
+  state: Rc<RefCell<OpState>>,
+  args: Value,
+  _zero_copy: BufVec,
+) -> Result<Value, AnyError> {
+  xattr_async(state, args, None, XattrOp::Get).await
+}
+
+fn op_set_xattr_sync(
+  state: &mut OpState,
+  args: Value,
+  zero_copy: &mut [ZeroCopyBuf],
+) -> Result<Value, AnyError> {
+  let value = zero_copy.first().map(|buf| &buf[..]);
+  xattr_sync(state, args, value, XattrOp::Set)
+}
+
+async fn op_set_xattr_async(
+  state: Rc<RefCell<OpState>>,
+  args: Value,
+  zero_copy: BufVec,
+) -> Result<Value, AnyError> {
+  let value = zero_copy.first().map(|buf| buf.to_vec());
+  xattr_async(state, args, value, XattrOp::Set).await
+}
+
+fn op_list_xattr_sync(
+  state: &mut OpState,
+  args: Value,
+  _zero_copy: &mut [ZeroCopyBuf],
+) -> Result<Value, AnyError> {
+  xattr_sync(state, args, None, XattrOp::List)
+}
+
+async fn op_list_xattr_async(
+  state: Rc<RefCell<OpState>>,
+  args: Value,
+  _zero_copy: BufVec,
+) -> Result<Value, AnyError> {
+  xattr_async(state, args, None, XattrOp::List).await
+}
+
+fn op_remove_xattr_sync(
+  state: &mut OpState,
+  args: Value,
+  _zero_copy: &mut [ZeroCopyBuf],
+) -> Result<Value, AnyError> {
+  xattr_sync(state, args, None, XattrOp::Remove)
+}
+
+async fn op_remove_xattr_async(
+  state: Rc<RefCell<OpState>>,
+  args: Value,
+  _zero_copy: BufVec,
+) -> Result<Value, AnyError> {
+  xattr_async(state, args, None, XattrOp::Remove).await
+}
+
+#[derive(Deserialize)]
+struct StatfsArgs {
+  path: String,
+}
+
+/// The name of a filesystem, from the magic number of its type on Linux.
+/// ext2, ext3 and ext4 share theirs.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn filesystem_type(stat: &nix::sys::statfs::Statfs) -> String {
+  let magic = stat.filesystem_type().0 as u32;
+  let name = match magic {
+    0x9123683e => "btrfs",
+    0xef53 => "ext2/ext3/ext4",
+    0x65735546 => "fuse",
+    0x4d44 => "vfat",
+    0x6969 => "nfs",
+    0x5346544e => "ntfs",
+    0x794c7630 => "overlay",
+    0x9fa0 => "proc",
+    0x73717368 => "squashfs",
+    0x62656572 => "sysfs",
+    0x1021994 => "tmpfs",
+    0x58465342 => "xfs",
+    0x2fc12fc1 => "zfs",
+    _ => return format!("{:#x}", magic),
+  };
+  name.to_string()
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+fn filesystem_type(stat: &nix::sys::statfs::Statfs) -> String {
+  stat.filesystem_type_name().to_string()
+}
+
+#[cfg(all(
+  unix,
+  not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    target_os = "ios"
+  ))
+))]
+fn filesystem_type(_stat: &nix::sys::statfs::Statfs) -> String {
+  "unknown".to_string()
+}
+
+/// The sizes are from `statfs()`, whose counts are 64-bit on macOS unlike
+/// the ones of `statvfs()`, which is only used for the inodes available to
+/// unprivileged users, as `statfs()` doesn't have their number.
+#[cfg(unix)]
+fn statfs(path: &Path) -> Result<Value, AnyError> {
+  let stat = nix::sys::statfs::statfs(path)?;
+  let vfs_stat = nix::sys::statvfs::statvfs(path)?;
+  Ok(json!({
+    "type": filesystem_type(&stat),
+    "blockSize": stat.block_size(),
+    "blocks": stat.blocks(),
+    "blocksFree": stat.blocks_free(),
+    "blocksAvailable": stat.blocks_available(),
+    "files": stat.files(),
+    "filesFree": stat.files_free(),
+    "filesAvailable": vfs_stat.files_available(),
+  }))
+}
+
+#[cfg(not(unix))]
+fn statfs(_path: &Path) -> Result<Value, AnyError> {
+  Err(not_supported())
+}
+
+fn op_statfs_sync(
+  state: &mut OpState,
+  args: Value,
+  _zero_copy: &mut [ZeroCopyBuf],
+) -> Result<Value, AnyError> {
+  super::check_unstable(state, "Deno.statfs");
+  let args: StatfsArgs = serde_json::from_value(args)?;
+  let path = PathBuf::from(&args.path);
+  state.borrow::<PermissionsContainer>().check_read(&path)?;
+  statfs(&path)
+}
+
+async fn op_statfs_async(
+  state: Rc<RefCell<OpState>>,
+  args: Value,
+  _zero_copy: BufVec,
+) -> Result<Value, AnyError> {
+  super::check_unstable2(&state, "Deno.statfs");
+  let args: StatfsArgs = serde_json::from_value(args)?;
+  let path = PathBuf::from(&args.path);
+  state
+    .borrow()
+    .borrow::<PermissionsContainer>()
+    .check_read(&path)?;
+
+  tokio::task::spawn_blocking(move || statfs(&path))
+    .await
+    .unwrap()
+}
+
 fn op_cwd(
   state: &mut OpState,
   _args: Value,
@@ -1757,7 +2574,7 @@ fn op_cwd(
 ) -> Result<Value, AnyError> {
   let path = current_dir()?;
   state
-    .borrow::<Permissions>()
+    .borrow::<PermissionsContainer>()
     .check_read_blind(&path, "CWD")?;
   let path_str = into_string(path.into_os_string())?;
   Ok(json!(path_str))
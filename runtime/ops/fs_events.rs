@@ -1,6 +1,6 @@
 // Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
 
-use crate::permissions::Permissions;
+use crate::permissions::PermissionsContainer;
 use deno_core::error::bad_resource_id;
 use deno_core::error::AnyError;
 use deno_core::serde_json;
@@ -111,7 +111,7 @@ fn op_fs_events_open(
   };
   for path in &args.paths {
     state
-      .borrow::<Permissions>()
+      .borrow::<PermissionsContainer>()
       .check_read(&PathBuf::from(path))?;
     watcher.watch(path, recursive_mode)?;
   }
@@ -231,6 +231,9 @@ pub struct StreamResource {
 
   server_tls_stream: Option<AsyncRefCell<ServerTlsStream<TcpStream>>>,
 
+  /// Whether `fs_file` is the master of a pseudo-terminal.
+  pty: bool,
+
   cancel: CancelHandle,
   name: String,
 }
@@ -264,6 +267,20 @@ impl StreamResource {
     }
   }
 
+  /// The master of the pseudo-terminal of a subprocess, which is read and
+  /// written like a file.
+  pub fn pty(master: tokio::fs::File) -> Self {
+    Self {
+      fs_file: Some(AsyncRefCell::new((
+        Some(master),
+        Some(FileMetadata::default()),
+      ))),
+      pty: true,
+      name: "pty".to_string(),
+      ..Default::default()
+    }
+  }
+
   #[cfg(unix)]
   pub fn unix_stream(unix_stream: tokio::net::UnixStream) -> Self {
     Self {
@@ -273,6 +290,10 @@ impl StreamResource {
     }
   }
 
+  pub fn cancel_handle(self: &Rc<Self>) -> RcRef<CancelHandle> {
+    RcRef::map(self, |r| &r.cancel)
+  }
+
   pub fn child_stdout(child: tokio::process::ChildStdout) -> Self {
     Self {
       child_stdout: Some(AsyncRefCell::new(child)),
@@ -313,6 +334,16 @@ impl StreamResource {
     }
   }
 
+  pub fn is_server_tls_stream(&self) -> bool {
+    self.server_tls_stream.is_some()
+  }
+
+  /// Returns the stream of a connection accepted by `Deno.listenTls()`, so
+  /// that it can be handed over to another protocol.
+  pub fn into_server_tls_stream(self) -> Option<ServerTlsStream<TcpStream>> {
+    self.server_tls_stream.map(AsyncRefCell::into_inner)
+  }
+
   async fn read(self: Rc<Self>, buf: &mut [u8]) -> Result<usize, AnyError> {
     // TODO(bartlomieju): in the future, it would be better for `StreamResource`
     // to be an enum instead a struct with many `Option` fields, however I
@@ -326,8 +357,13 @@ impl StreamResource {
       let mut fs_file = RcRef::map(&self, |r| r.fs_file.as_ref().unwrap())
         .borrow_mut()
         .await;
-      let nwritten = (*fs_file).0.as_mut().unwrap().read(buf).await?;
-      return Ok(nwritten);
+      let nread = match (*fs_file).0.as_mut().unwrap().read(buf).await {
+        // The master of a pseudo-terminal fails with `EIO` instead of
+        // reaching the end once the subprocess has closed the terminal.
+        Err(err) if self.pty && err.raw_os_error() == Some(libc::EIO) => 0,
+        result => result?,
+      };
+      return Ok(nread);
     } else if self.child_stdout.is_some() {
       debug_assert!(self.child_stdin.is_none());
       debug_assert!(self.child_stderr.is_none());
@@ -20,6 +20,7 @@ pub mod signal;
 pub mod timers;
 pub mod tls;
 pub mod tty;
+pub mod web;
 pub mod web_worker;
 pub mod websocket;
 pub mod worker_host;
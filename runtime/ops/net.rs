@@ -1,7 +1,7 @@
 // Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
 
 use crate::ops::io::TcpStreamResource;
-use crate::permissions::Permissions;
+use crate::permissions::PermissionsContainer;
 use crate::resolve_addr::resolve_addr;
 use crate::resolve_addr::resolve_addr_sync;
 use deno_core::error::bad_resource;
@@ -22,22 +22,45 @@ use deno_core::RcRef;
 use deno_core::Resource;
 use deno_core::ZeroCopyBuf;
 use serde::Deserialize;
+use socket2::Domain;
+use socket2::Protocol;
+use socket2::Socket;
+use socket2::Type;
 use std::borrow::Cow;
 use std::cell::RefCell;
+use std::io;
+use std::mem::ManuallyDrop;
+use std::net::IpAddr;
+use std::net::Ipv4Addr;
 use std::net::Shutdown;
 use std::net::SocketAddr;
 use std::rc::Rc;
+use std::time::Duration;
 use tokio::io::AsyncWriteExt;
 use tokio::net::TcpListener;
 use tokio::net::TcpStream;
 use tokio::net::UdpSocket;
+use trust_dns_resolver::config::NameServerConfigGroup;
+use trust_dns_resolver::config::ResolverConfig;
+use trust_dns_resolver::config::ResolverOpts;
+use trust_dns_resolver::error::ResolveErrorKind;
+use trust_dns_resolver::proto::rr::RData;
+use trust_dns_resolver::proto::rr::RecordType;
+use trust_dns_resolver::system_conf;
+use trust_dns_resolver::TokioAsyncResolver;
 
 #[cfg(unix)]
 use super::net_unix;
 #[cfg(unix)]
 use crate::ops::io::StreamResource;
 #[cfg(unix)]
-use std::path::Path;
+use std::os::unix::io::AsRawFd;
+#[cfg(unix)]
+use std::os::unix::io::FromRawFd;
+#[cfg(windows)]
+use std::os::windows::io::AsRawSocket;
+#[cfg(windows)]
+use std::os::windows::io::FromRawSocket;
 
 pub fn init(rt: &mut deno_core::JsRuntime) {
   super::reg_json_async(rt, "op_accept", op_accept);
@@ -46,6 +69,41 @@ pub fn init(rt: &mut deno_core::JsRuntime) {
   super::reg_json_sync(rt, "op_listen", op_listen);
   super::reg_json_async(rt, "op_datagram_receive", op_datagram_receive);
   super::reg_json_async(rt, "op_datagram_send", op_datagram_send);
+  super::reg_json_async(rt, "op_dns_resolve", op_dns_resolve);
+  super::reg_json_async(rt, "op_set_nodelay", op_set_nodelay);
+  super::reg_json_async(rt, "op_set_keepalive", op_set_keepalive);
+  super::reg_json_sync(rt, "op_datagram_set_option", op_datagram_set_option);
+  #[cfg(unix)]
+  super::reg_json_async(rt, "op_unix_send_fds", net_unix::op_unix_send_fds);
+  #[cfg(unix)]
+  super::reg_json_async(
+    rt,
+    "op_unix_receive_fds",
+    net_unix::op_unix_receive_fds,
+  );
+}
+
+/// Calls `f` with a `socket2::Socket` for the socket of `socket`, which keeps
+/// owning it.
+#[cfg(unix)]
+fn with_socket2<S: AsRawFd, T>(
+  socket: &S,
+  f: impl FnOnce(&Socket) -> io::Result<T>,
+) -> io::Result<T> {
+  let socket =
+    ManuallyDrop::new(unsafe { Socket::from_raw_fd(socket.as_raw_fd()) });
+  f(&socket)
+}
+
+#[cfg(windows)]
+fn with_socket2<S: AsRawSocket, T>(
+  socket: &S,
+  f: impl FnOnce(&Socket) -> io::Result<T>,
+) -> io::Result<T> {
+  let socket = ManuallyDrop::new(unsafe {
+    Socket::from_raw_socket(socket.as_raw_socket())
+  });
+  f(&socket)
 }
 
 #[derive(Deserialize)]
@@ -198,7 +256,7 @@ async fn op_datagram_send(
     } if transport == "udp" => {
       {
         let s = state.borrow();
-        s.borrow::<Permissions>()
+        s.borrow::<PermissionsContainer>()
           .check_net(&(&args.hostname, Some(args.port)))?;
       }
       let addr = resolve_addr(&args.hostname, args.port)
@@ -221,10 +279,10 @@ async fn op_datagram_send(
       transport,
       transport_args: ArgsEnum::Unix(args),
     } if transport == "unixpacket" => {
-      let address_path = Path::new(&args.path);
       {
         let s = state.borrow();
-        s.borrow::<Permissions>().check_write(&address_path)?;
+        let permissions = s.borrow::<PermissionsContainer>();
+        net_unix::check_unix_address(permissions, &args.path, false)?;
       }
       let resource = state
         .borrow()
@@ -236,7 +294,8 @@ async fn op_datagram_send(
       let socket = RcRef::map(&resource, |r| &r.socket)
         .try_borrow_mut()
         .ok_or_else(|| custom_error("Busy", "Socket already in use"))?;
-      let byte_length = socket.send_to(&zero_copy, address_path).await?;
+      let byte_length =
+        net_unix::send_unix_packet(&socket, &zero_copy, &args.path).await?;
       Ok(json!(byte_length))
     }
     _ => Err(type_error("Wrong argument format!")),
@@ -263,7 +322,7 @@ async fn op_connect(
       {
         let state_ = state.borrow();
         state_
-          .borrow::<Permissions>()
+          .borrow::<PermissionsContainer>()
           .check_net(&(&args.hostname, Some(args.port)))?;
       }
       let addr = resolve_addr(&args.hostname, args.port)
@@ -297,17 +356,15 @@ async fn op_connect(
       transport,
       transport_args: ArgsEnum::Unix(args),
     } if transport == "unix" => {
-      let address_path = Path::new(&args.path);
       super::check_unstable2(&state, "Deno.connect");
       {
         let state_ = state.borrow();
-        state_.borrow::<Permissions>().check_read(&address_path)?;
-        state_.borrow::<Permissions>().check_write(&address_path)?;
+        let permissions = state_.borrow::<PermissionsContainer>();
+        net_unix::check_unix_address(permissions, &args.path, true)?;
       }
-      let path = args.path;
-      let unix_stream = net_unix::UnixStream::connect(Path::new(&path)).await?;
+      let unix_stream = net_unix::connect_unix(&args.path).await?;
       let local_addr = unix_stream.local_addr()?;
-      let remote_addr = unix_stream.peer_addr()?;
+      let peer_credentials = net_unix::peer_credentials(&unix_stream).ok();
 
       let mut state_ = state.borrow_mut();
       let resource = StreamResource::unix_stream(unix_stream);
@@ -319,9 +376,10 @@ async fn op_connect(
           "transport": transport,
         },
         "remoteAddr": {
-          "path": remote_addr.as_pathname(),
+          "path": args.path,
           "transport": transport,
-        }
+        },
+        "peerCredentials": peer_credentials,
       }))
     }
     _ => Err(type_error("Wrong argument format!")),
@@ -378,9 +436,13 @@ async fn op_shutdown(
   Err(bad_resource_id())
 }
 
-struct TcpListenerResource {
+pub(crate) struct TcpListenerResource {
   listener: AsyncRefCell<TcpListener>,
   cancel: CancelHandle,
+  /// The descriptor of the listener, which can be read while an accept is
+  /// pending.
+  #[cfg(unix)]
+  pub fd: std::os::unix::io::RawFd,
 }
 
 impl Resource for TcpListenerResource {
@@ -393,8 +455,8 @@ impl Resource for TcpListenerResource {
   }
 }
 
-struct UdpSocketResource {
-  socket: AsyncRefCell<UdpSocket>,
+pub(crate) struct UdpSocketResource {
+  pub socket: AsyncRefCell<UdpSocket>,
   cancel: CancelHandle,
 }
 
@@ -409,9 +471,15 @@ impl Resource for UdpSocketResource {
 }
 
 #[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
 struct IpListenArgs {
   hostname: String,
   port: u16,
+  /// Whether other sockets may bind the same address, to balance the load
+  /// between them. Only supported on Unix.
+  reuse_port: Option<bool>,
+  /// The length of the queue of pending connections of a TCP listener.
+  backlog: Option<u32>,
 }
 
 #[derive(Deserialize)]
@@ -429,15 +497,57 @@ struct ListenArgs {
   transport_args: ArgsEnum,
 }
 
+/// Creates a socket for `addr` that has `SO_REUSEPORT` set if `reuse_port`
+/// is, but isn't bound yet.
+fn new_socket(
+  addr: SocketAddr,
+  socket_type: Type,
+  protocol: Protocol,
+  reuse_port: bool,
+) -> Result<Socket, AnyError> {
+  let domain = if addr.is_ipv4() {
+    Domain::ipv4()
+  } else {
+    Domain::ipv6()
+  };
+  let socket = Socket::new(domain, socket_type, Some(protocol))?;
+  if reuse_port {
+    #[cfg(unix)]
+    socket.set_reuse_port(true)?;
+    #[cfg(not(unix))]
+    return Err(type_error("reusePort is only supported on Unix"));
+  }
+  Ok(socket)
+}
+
 fn listen_tcp(
   state: &mut OpState,
   addr: SocketAddr,
+  reuse_port: bool,
+  backlog: u32,
+) -> Result<(u32, SocketAddr), AnyError> {
+  let socket = new_socket(addr, Type::stream(), Protocol::tcp(), reuse_port)?;
+  // Like the listeners of std, so that a restarted server can bind the
+  // address of connections that are still closing.
+  #[cfg(unix)]
+  socket.set_reuse_address(true)?;
+  socket.bind(&addr.into())?;
+  socket.listen(backlog.min(i32::MAX as u32) as i32)?;
+  add_tcp_listener(state, socket.into_tcp_listener())
+}
+
+/// Adds a resource for a listening socket, like one that was received from
+/// another process.
+pub(crate) fn add_tcp_listener(
+  state: &mut OpState,
+  std_listener: std::net::TcpListener,
 ) -> Result<(u32, SocketAddr), AnyError> {
-  let std_listener = std::net::TcpListener::bind(&addr)?;
   std_listener.set_nonblocking(true)?;
   let listener = TcpListener::from_std(std_listener)?;
   let local_addr = listener.local_addr()?;
   let listener_resource = TcpListenerResource {
+    #[cfg(unix)]
+    fd: listener.as_raw_fd(),
     listener: AsyncRefCell::new(listener),
     cancel: Default::default(),
   };
@@ -449,8 +559,17 @@ fn listen_tcp(
 fn listen_udp(
   state: &mut OpState,
   addr: SocketAddr,
+  reuse_port: bool,
+) -> Result<(u32, SocketAddr), AnyError> {
+  let socket = new_socket(addr, Type::dgram(), Protocol::udp(), reuse_port)?;
+  socket.bind(&addr.into())?;
+  add_udp_socket(state, socket.into_udp_socket())
+}
+
+pub(crate) fn add_udp_socket(
+  state: &mut OpState,
+  std_socket: std::net::UdpSocket,
 ) -> Result<(u32, SocketAddr), AnyError> {
-  let std_socket = std::net::UdpSocket::bind(&addr)?;
   std_socket.set_nonblocking(true)?;
   let socket = UdpSocket::from_std(std_socket)?;
   let local_addr = socket.local_addr()?;
@@ -468,7 +587,7 @@ fn op_listen(
   args: Value,
   _zero_copy: &mut [ZeroCopyBuf],
 ) -> Result<Value, AnyError> {
-  let permissions = state.borrow::<Permissions>();
+  let permissions = state.borrow::<PermissionsContainer>();
   match serde_json::from_value(args)? {
     ListenArgs {
       transport,
@@ -480,13 +599,21 @@ fn op_listen(
         }
         permissions.check_net(&(&args.hostname, Some(args.port)))?;
       }
+      if args.reuse_port.is_some() {
+        super::check_unstable(state, "ListenOptions.reusePort");
+      }
+      if args.backlog.is_some() {
+        super::check_unstable(state, "ListenOptions.backlog");
+      }
       let addr = resolve_addr_sync(&args.hostname, args.port)?
         .next()
         .ok_or_else(|| generic_error("No resolved address found"))?;
+      let reuse_port = args.reuse_port.unwrap_or(false);
       let (rid, local_addr) = if transport == "tcp" {
-        listen_tcp(state, addr)?
+        // The backlog that std uses.
+        listen_tcp(state, addr, reuse_port, args.backlog.unwrap_or(128))?
       } else {
-        listen_udp(state, addr)?
+        listen_udp(state, addr, reuse_port)?
       };
       debug!(
         "New listener {} {}:{}",
@@ -508,7 +635,6 @@ fn op_listen(
       transport,
       transport_args: ArgsEnum::Unix(args),
     } if transport == "unix" || transport == "unixpacket" => {
-      let address_path = Path::new(&args.path);
       {
         if transport == "unix" {
           super::check_unstable(state, "Deno.listen");
@@ -516,23 +642,18 @@ fn op_listen(
         if transport == "unixpacket" {
           super::check_unstable(state, "Deno.listenDatagram");
         }
-        permissions.check_read(&address_path)?;
-        permissions.check_write(&address_path)?;
+        net_unix::check_unix_address(permissions, &args.path, true)?;
       }
-      let (rid, local_addr) = if transport == "unix" {
-        net_unix::listen_unix(state, &address_path)?
+      let rid = if transport == "unix" {
+        net_unix::listen_unix(state, &args.path)?
       } else {
-        net_unix::listen_unix_packet(state, &address_path)?
+        net_unix::listen_unix_packet(state, &args.path)?
       };
-      debug!(
-        "New listener {} {}",
-        rid,
-        local_addr.as_pathname().unwrap().display(),
-      );
+      debug!("New listener {} {:?}", rid, args.path);
       Ok(json!({
       "rid": rid,
       "localAddr": {
-        "path": local_addr.as_pathname(),
+        "path": args.path,
         "transport": transport,
       },
       }))
@@ -541,3 +662,320 @@ fn op_listen(
     _ => Err(type_error("Wrong argument format!")),
   }
 }
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SetNoDelayArgs {
+  rid: u32,
+  no_delay: bool,
+}
+
+async fn op_set_nodelay(
+  state: Rc<RefCell<OpState>>,
+  args: Value,
+  _zero_copy: BufVec,
+) -> Result<Value, AnyError> {
+  super::check_unstable2(&state, "Deno.Conn.setNoDelay");
+  let args: SetNoDelayArgs = serde_json::from_value(args)?;
+  let resource = state
+    .borrow()
+    .resource_table
+    .get::<TcpStreamResource>(args.rid)
+    .ok_or_else(bad_resource_id)?;
+  // Waits for a pending write instead of a read, which can take forever.
+  let wr = resource.wr_borrow_mut().await;
+  let stream: &TcpStream = (*wr).as_ref();
+  stream.set_nodelay(args.no_delay)?;
+  Ok(json!({}))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SetKeepAliveArgs {
+  rid: u32,
+  keep_alive: bool,
+  /// The idle time before the first probe, in milliseconds.
+  initial_delay: Option<f64>,
+}
+
+async fn op_set_keepalive(
+  state: Rc<RefCell<OpState>>,
+  args: Value,
+  _zero_copy: BufVec,
+) -> Result<Value, AnyError> {
+  super::check_unstable2(&state, "Deno.Conn.setKeepAlive");
+  let args: SetKeepAliveArgs = serde_json::from_value(args)?;
+  let keepalive = if args.keep_alive {
+    // The default of Linux and Windows. The delay is set in whole seconds.
+    let delay = args.initial_delay.unwrap_or(7_200_000.0);
+    if !delay.is_finite() || delay < 0.0 {
+      return Err(type_error("initialDelay must be a positive number"));
+    }
+    Some(Duration::from_secs(((delay / 1000.0).ceil() as u64).max(1)))
+  } else {
+    None
+  };
+  let resource = state
+    .borrow()
+    .resource_table
+    .get::<TcpStreamResource>(args.rid)
+    .ok_or_else(bad_resource_id)?;
+  let wr = resource.wr_borrow_mut().await;
+  let stream: &TcpStream = (*wr).as_ref();
+  with_socket2(stream, |socket| socket.set_keepalive(keepalive))?;
+  Ok(json!({}))
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum MulticastInterface {
+  /// The index of an interface, for IPv6 groups.
+  Index(u32),
+  /// The address of an interface, for IPv4 groups.
+  Address(String),
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "option", rename_all = "camelCase")]
+enum DatagramOption {
+  Broadcast {
+    value: bool,
+  },
+  MulticastLoopback {
+    value: bool,
+  },
+  /// The TTL of IPv4 packets, or the hop limit of IPv6 ones.
+  Ttl {
+    value: u32,
+  },
+  MulticastTtl {
+    value: u32,
+  },
+  JoinMulticast {
+    address: String,
+    interface: Option<MulticastInterface>,
+  },
+  LeaveMulticast {
+    address: String,
+    interface: Option<MulticastInterface>,
+  },
+}
+
+#[derive(Deserialize)]
+struct DatagramSetOptionArgs {
+  rid: u32,
+  #[serde(flatten)]
+  option: DatagramOption,
+}
+
+/// Joins or leaves the multicast group at `address`.
+fn set_membership(
+  socket: &UdpSocket,
+  address: &str,
+  interface: Option<MulticastInterface>,
+  join: bool,
+) -> Result<(), AnyError> {
+  let address: IpAddr = address
+    .parse()
+    .map_err(|_| type_error(format!("Invalid IP address: {}", address)))?;
+  match address {
+    IpAddr::V4(group) => {
+      let interface = match interface {
+        Some(MulticastInterface::Address(interface)) => {
+          interface.parse().map_err(|_| {
+            type_error(format!("Invalid IPv4 address: {}", interface))
+          })?
+        }
+        Some(MulticastInterface::Index(_)) => {
+          return Err(type_error(
+            "The interface of an IPv4 group is given by its address",
+          ))
+        }
+        None => Ipv4Addr::UNSPECIFIED,
+      };
+      if join {
+        socket.join_multicast_v4(group, interface)?;
+      } else {
+        socket.leave_multicast_v4(group, interface)?;
+      }
+    }
+    IpAddr::V6(group) => {
+      let interface = match interface {
+        Some(MulticastInterface::Index(index)) => index,
+        Some(MulticastInterface::Address(_)) => {
+          return Err(type_error(
+            "The interface of an IPv6 group is given by its index",
+          ))
+        }
+        None => 0,
+      };
+      if join {
+        socket.join_multicast_v6(&group, interface)?;
+      } else {
+        socket.leave_multicast_v6(&group, interface)?;
+      }
+    }
+  }
+  Ok(())
+}
+
+fn op_datagram_set_option(
+  state: &mut OpState,
+  args: Value,
+  _zero_copy: &mut [ZeroCopyBuf],
+) -> Result<Value, AnyError> {
+  let args: DatagramSetOptionArgs = serde_json::from_value(args)?;
+  let resource = state
+    .resource_table
+    .get::<UdpSocketResource>(args.rid)
+    .ok_or_else(bad_resource_id)?;
+  // Receives only take a shared borrow of the socket.
+  let socket = RcRef::map(&resource, |r| &r.socket)
+    .try_borrow()
+    .ok_or_else(|| generic_error("The socket is busy"))?;
+  let is_ipv4 = socket.local_addr()?.is_ipv4();
+  match args.option {
+    DatagramOption::Broadcast { value } => socket.set_broadcast(value)?,
+    DatagramOption::MulticastLoopback { value } if is_ipv4 => {
+      socket.set_multicast_loop_v4(value)?
+    }
+    DatagramOption::MulticastLoopback { value } => {
+      socket.set_multicast_loop_v6(value)?
+    }
+    DatagramOption::Ttl { value } if is_ipv4 => socket.set_ttl(value)?,
+    DatagramOption::Ttl { value } => {
+      with_socket2(&*socket, |socket| socket.set_unicast_hops_v6(value))?
+    }
+    DatagramOption::MulticastTtl { value } if is_ipv4 => {
+      socket.set_multicast_ttl_v4(value)?
+    }
+    DatagramOption::MulticastTtl { value } => {
+      with_socket2(&*socket, |socket| socket.set_multicast_hops_v6(value))?
+    }
+    DatagramOption::JoinMulticast { address, interface } => {
+      set_membership(&socket, &address, interface, true)?
+    }
+    DatagramOption::LeaveMulticast { address, interface } => {
+      set_membership(&socket, &address, interface, false)?
+    }
+  }
+  Ok(json!({}))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ResolveDnsArgs {
+  query: String,
+  record_type: String,
+  name_server: Option<NameServerArgs>,
+}
+
+fn default_dns_port() -> u16 {
+  53
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NameServerArgs {
+  ip_addr: String,
+  #[serde(default = "default_dns_port")]
+  port: u16,
+}
+
+fn record_type(name: &str) -> Result<RecordType, AnyError> {
+  match name {
+    "A" => Ok(RecordType::A),
+    "AAAA" => Ok(RecordType::AAAA),
+    "CNAME" => Ok(RecordType::CNAME),
+    "MX" => Ok(RecordType::MX),
+    "SRV" => Ok(RecordType::SRV),
+    "TXT" => Ok(RecordType::TXT),
+    _ => Err(type_error(format!("Unsupported record type: {}", name))),
+  }
+}
+
+/// The JSON of a record, in the shape that `Deno.resolveDns` returns for its
+/// record type.
+fn dns_record(rdata: &RData) -> Option<Value> {
+  match rdata {
+    RData::A(addr) => Some(json!(addr.to_string())),
+    RData::AAAA(addr) => Some(json!(addr.to_string())),
+    RData::CNAME(name) => Some(json!(name.to_string())),
+    RData::MX(mx) => Some(json!({
+      "preference": mx.preference(),
+      "exchange": mx.exchange().to_string(),
+    })),
+    RData::SRV(srv) => Some(json!({
+      "priority": srv.priority(),
+      "weight": srv.weight(),
+      "port": srv.port(),
+      "target": srv.target().to_string(),
+    })),
+    RData::TXT(txt) => Some(json!(txt
+      .iter()
+      .map(|data| String::from_utf8_lossy(data).into_owned())
+      .collect::<Vec<_>>())),
+    _ => None,
+  }
+}
+
+async fn op_dns_resolve(
+  state: Rc<RefCell<OpState>>,
+  args: Value,
+  _zero_copy: BufVec,
+) -> Result<Value, AnyError> {
+  super::check_unstable2(&state, "Deno.resolveDns");
+  let args: ResolveDnsArgs = serde_json::from_value(args)?;
+  let record_type = record_type(&args.record_type)?;
+
+  let name_server = match &args.name_server {
+    Some(name_server) => {
+      let ip_addr: IpAddr = name_server.ip_addr.parse().map_err(|_| {
+        type_error(format!("Invalid IP address: {}", name_server.ip_addr))
+      })?;
+      Some((ip_addr, name_server.port))
+    }
+    None => None,
+  };
+  {
+    let state = state.borrow();
+    let permissions = state.borrow::<PermissionsContainer>();
+    permissions.check_net(&(&args.query, None))?;
+    // A custom name server is contacted directly.
+    if let Some(name_server) = &args.name_server {
+      permissions.check_net(&(&name_server.ip_addr, Some(name_server.port)))?;
+    }
+  }
+
+  let (config, opts) = match name_server {
+    Some((ip_addr, port)) => {
+      let group = NameServerConfigGroup::from_ips_clear(&[ip_addr], port, true);
+      (
+        ResolverConfig::from_parts(None, vec![], group),
+        ResolverOpts::default(),
+      )
+    }
+    None => system_conf::read_system_conf()?,
+  };
+  let resolver = TokioAsyncResolver::tokio(config, opts)
+    .map_err(|err| generic_error(err.to_string()))?;
+  let lookup = resolver
+    .lookup(args.query.as_str(), record_type, Default::default())
+    .await
+    .map_err(|err| match err.kind() {
+      ResolveErrorKind::NoRecordsFound { .. } => {
+        custom_error("NotFound", err.to_string())
+      }
+      ResolveErrorKind::Timeout => custom_error("TimedOut", err.to_string()),
+      _ => generic_error(err.to_string()),
+    })?;
+
+  // The answer of a lookup includes the CNAME records that lead to the
+  // records of the queried type.
+  let records: Vec<Value> = lookup
+    .iter()
+    .filter(|rdata| rdata.to_record_type() == record_type)
+    .filter_map(dns_record)
+    .collect();
+  Ok(json!(records))
+}
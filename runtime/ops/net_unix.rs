@@ -1,11 +1,21 @@
 // Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
 
 use crate::ops::io::StreamResource;
+use crate::ops::io::TcpStreamResource;
+use crate::ops::net::add_tcp_listener;
+use crate::ops::net::add_udp_socket;
 use crate::ops::net::AcceptArgs;
 use crate::ops::net::ReceiveArgs;
+use crate::ops::net::TcpListenerResource;
+use crate::ops::net::UdpSocketResource;
+use crate::permissions::PermissionsContainer;
 use deno_core::error::bad_resource;
+use deno_core::error::bad_resource_id;
 use deno_core::error::custom_error;
+use deno_core::error::type_error;
 use deno_core::error::AnyError;
+use deno_core::futures::FutureExt;
+use deno_core::serde_json;
 use deno_core::serde_json::json;
 use deno_core::serde_json::Value;
 use deno_core::AsyncRefCell;
@@ -15,19 +25,51 @@ use deno_core::CancelTryFuture;
 use deno_core::OpState;
 use deno_core::RcRef;
 use deno_core::Resource;
+use nix::errno::Errno;
+use nix::fcntl::fcntl;
+use nix::fcntl::FcntlArg;
+use nix::fcntl::FdFlag;
+use nix::sys::socket::getsockname;
+use nix::sys::socket::getsockopt;
+use nix::sys::socket::recvmsg;
+use nix::sys::socket::sendmsg;
+use nix::sys::socket::sockopt;
+use nix::sys::socket::ControlMessage;
+use nix::sys::socket::ControlMessageOwned;
+use nix::sys::socket::MsgFlags;
+use nix::sys::socket::SockAddr;
+use nix::sys::socket::SockType;
+use nix::sys::uio::IoVec;
 use serde::Deserialize;
 use std::borrow::Cow;
 use std::cell::RefCell;
 use std::fs::remove_file;
+use std::fs::File;
+use std::io;
+use std::os::unix::fs::FileTypeExt;
+use std::os::unix::io::AsRawFd;
+use std::os::unix::io::FromRawFd;
+use std::os::unix::io::IntoRawFd;
+use std::os::unix::io::RawFd;
+use std::os::unix::net;
 use std::path::Path;
 use std::rc::Rc;
+use tokio::io::unix::AsyncFd;
+use tokio::net::TcpStream;
+use tokio::net::UdpSocket;
 use tokio::net::UnixDatagram;
 use tokio::net::UnixListener;
 pub use tokio::net::UnixStream;
 
+/// The most descriptors that are received with one message.
+const MAX_FDS: usize = 64;
+
 struct UnixListenerResource {
   listener: AsyncRefCell<UnixListener>,
   cancel: CancelHandle,
+  /// The descriptor of the listener, which can be read while an accept is
+  /// pending.
+  fd: RawFd,
 }
 
 impl Resource for UnixListenerResource {
@@ -60,6 +102,165 @@ pub struct UnixListenArgs {
   pub path: String,
 }
 
+/// The name of an address in the Linux abstract namespace, which is given as
+/// a path that starts with a NUL byte.
+fn abstract_name(path: &str) -> Option<&str> {
+  path.strip_prefix('\0')
+}
+
+/// Checks the permissions to use the socket at `path`, which needs write
+/// permission, and read permission as well if `read` is set. Addresses in
+/// the abstract namespace aren't files, and need net permission instead.
+pub fn check_unix_address(
+  permissions: &PermissionsContainer,
+  path: &str,
+  read: bool,
+) -> Result<(), AnyError> {
+  if abstract_name(path).is_some() {
+    return permissions.check_net(&("localhost", None));
+  }
+  let path = Path::new(path);
+  if read {
+    permissions.check_read(path)?;
+  }
+  permissions.check_write(path)
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod abstract_namespace {
+  use deno_core::error::AnyError;
+  use nix::sys::socket::bind;
+  use nix::sys::socket::connect;
+  use nix::sys::socket::listen;
+  use nix::sys::socket::sendto;
+  use nix::sys::socket::socket;
+  use nix::sys::socket::AddressFamily;
+  use nix::sys::socket::MsgFlags;
+  use nix::sys::socket::SockAddr;
+  use nix::sys::socket::SockFlag;
+  use nix::sys::socket::SockType;
+  use nix::sys::socket::UnixAddr;
+  use std::os::unix::io::FromRawFd;
+  use std::os::unix::io::RawFd;
+  use std::os::unix::net;
+
+  fn address(name: &str) -> Result<SockAddr, AnyError> {
+    Ok(SockAddr::Unix(UnixAddr::new_abstract(name.as_bytes())?))
+  }
+
+  fn new_socket(socket_type: SockType) -> Result<RawFd, AnyError> {
+    let flags = SockFlag::SOCK_CLOEXEC;
+    Ok(socket(AddressFamily::Unix, socket_type, flags, None)?)
+  }
+
+  pub fn listen_unix(name: &str) -> Result<net::UnixListener, AnyError> {
+    let fd = new_socket(SockType::Stream)?;
+    let listener = unsafe { net::UnixListener::from_raw_fd(fd) };
+    bind(fd, &address(name)?)?;
+    // The backlog that std uses.
+    listen(fd, 128)?;
+    Ok(listener)
+  }
+
+  pub fn bind_unix_packet(name: &str) -> Result<net::UnixDatagram, AnyError> {
+    let fd = new_socket(SockType::Datagram)?;
+    let socket = unsafe { net::UnixDatagram::from_raw_fd(fd) };
+    bind(fd, &address(name)?)?;
+    Ok(socket)
+  }
+
+  /// Connects a blocking socket, which doesn't wait for the connection to be
+  /// accepted, unless the backlog of the listener is full.
+  pub fn connect_unix(name: &str) -> Result<net::UnixStream, AnyError> {
+    let fd = new_socket(SockType::Stream)?;
+    let stream = unsafe { net::UnixStream::from_raw_fd(fd) };
+    connect(fd, &address(name)?)?;
+    Ok(stream)
+  }
+
+  pub fn send_to(fd: RawFd, buf: &[u8], name: &str) -> Result<usize, AnyError> {
+    Ok(sendto(fd, buf, &address(name)?, MsgFlags::empty())?)
+  }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+mod abstract_namespace {
+  use deno_core::error::type_error;
+  use deno_core::error::AnyError;
+  use std::os::unix::io::RawFd;
+  use std::os::unix::net;
+
+  fn unsupported() -> AnyError {
+    type_error("Abstract Unix socket addresses are only supported on Linux")
+  }
+
+  pub fn listen_unix(_name: &str) -> Result<net::UnixListener, AnyError> {
+    Err(unsupported())
+  }
+
+  pub fn bind_unix_packet(_name: &str) -> Result<net::UnixDatagram, AnyError> {
+    Err(unsupported())
+  }
+
+  pub fn connect_unix(_name: &str) -> Result<net::UnixStream, AnyError> {
+    Err(unsupported())
+  }
+
+  pub fn send_to(
+    _fd: RawFd,
+    _buf: &[u8],
+    _name: &str,
+  ) -> Result<usize, AnyError> {
+    Err(unsupported())
+  }
+}
+
+pub async fn connect_unix(path: &str) -> Result<UnixStream, AnyError> {
+  match abstract_name(path) {
+    Some(name) => {
+      let stream = abstract_namespace::connect_unix(name)?;
+      stream.set_nonblocking(true)?;
+      Ok(UnixStream::from_std(stream)?)
+    }
+    None => Ok(UnixStream::connect(path).await?),
+  }
+}
+
+pub async fn send_unix_packet(
+  socket: &UnixDatagram,
+  buf: &[u8],
+  path: &str,
+) -> Result<usize, AnyError> {
+  match abstract_name(path) {
+    // Datagrams to local sockets are queued at once, or fail if the queue
+    // of the receiver is full.
+    Some(name) => abstract_namespace::send_to(socket.as_raw_fd(), buf, name),
+    None => Ok(socket.send_to(buf, path).await?),
+  }
+}
+
+/// The process and user of the peer of `stream`, which are the ones that
+/// connected or listened. The pid is only known on Linux.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn peer_credentials(stream: &UnixStream) -> Result<Value, AnyError> {
+  let credentials = getsockopt(stream.as_raw_fd(), sockopt::PeerCredentials)?;
+  Ok(json!({
+    "pid": credentials.pid(),
+    "uid": credentials.uid(),
+    "gid": credentials.gid(),
+  }))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+pub fn peer_credentials(stream: &UnixStream) -> Result<Value, AnyError> {
+  let credentials = stream.peer_cred()?;
+  Ok(json!({
+    "pid": null,
+    "uid": credentials.uid(),
+    "gid": credentials.gid(),
+  }))
+}
+
 pub(crate) async fn accept_unix(
   state: Rc<RefCell<OpState>>,
   args: AcceptArgs,
@@ -81,6 +282,7 @@ pub(crate) async fn accept_unix(
 
   let local_addr = unix_stream.local_addr()?;
   let remote_addr = unix_stream.peer_addr()?;
+  let peer_credentials = peer_credentials(&unix_stream).ok();
   let resource = StreamResource::unix_stream(unix_stream);
   let mut state = state.borrow_mut();
   let rid = state.resource_table.add(resource);
@@ -93,7 +295,8 @@ pub(crate) async fn accept_unix(
     "remoteAddr": {
       "path": remote_addr.as_pathname(),
       "transport": "unix",
-    }
+    },
+    "peerCredentials": peer_credentials,
   }))
 }
 
@@ -127,38 +330,411 @@ pub(crate) async fn receive_unix_packet(
   }))
 }
 
-pub fn listen_unix(
+pub fn listen_unix(state: &mut OpState, path: &str) -> Result<u32, AnyError> {
+  let listener = match abstract_name(path) {
+    Some(name) => abstract_namespace::listen_unix(name)?,
+    None => {
+      let addr = Path::new(path);
+      if addr.exists() {
+        remove_file(&addr).unwrap();
+      }
+      net::UnixListener::bind(&addr)?
+    }
+  };
+  add_unix_listener(state, listener)
+}
+
+fn add_unix_listener(
   state: &mut OpState,
-  addr: &Path,
-) -> Result<(u32, tokio::net::unix::SocketAddr), AnyError> {
-  if addr.exists() {
-    remove_file(&addr).unwrap();
-  }
-  let listener = UnixListener::bind(&addr)?;
-  let local_addr = listener.local_addr()?;
+  listener: net::UnixListener,
+) -> Result<u32, AnyError> {
+  listener.set_nonblocking(true)?;
+  let listener = UnixListener::from_std(listener)?;
   let listener_resource = UnixListenerResource {
+    fd: listener.as_raw_fd(),
     listener: AsyncRefCell::new(listener),
     cancel: Default::default(),
   };
-  let rid = state.resource_table.add(listener_resource);
-
-  Ok((rid, local_addr))
+  Ok(state.resource_table.add(listener_resource))
 }
 
 pub fn listen_unix_packet(
   state: &mut OpState,
-  addr: &Path,
-) -> Result<(u32, tokio::net::unix::SocketAddr), AnyError> {
-  if addr.exists() {
-    remove_file(&addr).unwrap();
-  }
-  let socket = UnixDatagram::bind(&addr)?;
-  let local_addr = socket.local_addr()?;
+  path: &str,
+) -> Result<u32, AnyError> {
+  let socket = match abstract_name(path) {
+    Some(name) => abstract_namespace::bind_unix_packet(name)?,
+    None => {
+      let addr = Path::new(path);
+      if addr.exists() {
+        remove_file(&addr).unwrap();
+      }
+      net::UnixDatagram::bind(&addr)?
+    }
+  };
+  socket.set_nonblocking(true)?;
   let datagram_resource = UnixDatagramResource {
-    socket: AsyncRefCell::new(socket),
+    socket: AsyncRefCell::new(UnixDatagram::from_std(socket)?),
     cancel: Default::default(),
   };
-  let rid = state.resource_table.add(datagram_resource);
+  Ok(state.resource_table.add(datagram_resource))
+}
+
+fn nix_io_error(err: nix::Error) -> io::Error {
+  match err {
+    nix::Error::Sys(errno) => io::Error::from_raw_os_error(errno as i32),
+    err => io::Error::new(io::ErrorKind::Other, err),
+  }
+}
+
+/// Duplicates `fd` into a descriptor that is closed on exec, and owned by
+/// the returned file.
+fn dup_fd(fd: RawFd) -> Result<File, AnyError> {
+  let fd = fcntl(fd, FcntlArg::F_DUPFD_CLOEXEC(0))?;
+  Ok(unsafe { File::from_raw_fd(fd) })
+}
+
+fn busy() -> AnyError {
+  custom_error("Busy", "Resource is in use")
+}
 
-  Ok((rid, local_addr))
+/// Duplicates the descriptor of the resource `rid`, so that it stays open
+/// while it's sent, even if the resource is closed.
+fn dup_resource_fd(state: &OpState, rid: u32) -> Result<File, AnyError> {
+  let table = &state.resource_table;
+  if let Some(resource) = table.get::<TcpListenerResource>(rid) {
+    return dup_fd(resource.fd);
+  }
+  if let Some(resource) = table.get::<UnixListenerResource>(rid) {
+    return dup_fd(resource.fd);
+  }
+  if let Some(resource) = table.get::<TcpStreamResource>(rid) {
+    let wr = resource.wr_borrow_mut().now_or_never().ok_or_else(busy)?;
+    let stream: &TcpStream = (*wr).as_ref();
+    return dup_fd(stream.as_raw_fd());
+  }
+  if let Some(resource) = table.get::<UdpSocketResource>(rid) {
+    let socket = RcRef::map(&resource, |r| &r.socket)
+      .try_borrow()
+      .ok_or_else(busy)?;
+    return dup_fd(socket.as_raw_fd());
+  }
+  if let Some(resource) = table.get::<UnixDatagramResource>(rid) {
+    let socket = RcRef::map(&resource, |r| &r.socket)
+      .try_borrow()
+      .ok_or_else(busy)?;
+    return dup_fd(socket.as_raw_fd());
+  }
+  if let Some(resource) = table.get::<StreamResource>(rid) {
+    if resource.fs_file.is_some() {
+      let fs_file = RcRef::map(&resource, |r| r.fs_file.as_ref().unwrap())
+        .try_borrow()
+        .ok_or_else(busy)?;
+      if let Some(file) = &fs_file.0 {
+        return dup_fd(file.as_raw_fd());
+      }
+    }
+    if resource.unix_stream.is_some() {
+      let stream = RcRef::map(&resource, |r| r.unix_stream.as_ref().unwrap())
+        .try_borrow()
+        .ok_or_else(busy)?;
+      return dup_fd(stream.as_raw_fd());
+    }
+    return Err(type_error("The descriptor of this resource can't be sent"));
+  }
+  Err(bad_resource_id())
+}
+
+/// Adds a resource for a descriptor that was received from another process,
+/// and returns it as JSON for `UnixConn.receiveFds()`.
+fn add_fd_resource(state: &mut OpState, fd: RawFd) -> Result<Value, AnyError> {
+  let file = unsafe { File::from_raw_fd(fd) };
+  fcntl(fd, FcntlArg::F_SETFD(FdFlag::FD_CLOEXEC))?;
+  if !file.metadata()?.file_type().is_socket() {
+    let rid = state
+      .resource_table
+      .add(StreamResource::fs_file(tokio::fs::File::from_std(file)));
+    return Ok(json!({ "kind": "file", "rid": rid }));
+  }
+
+  let socket_type = getsockopt(fd, sockopt::SockType)?;
+  let listening = getsockopt(fd, sockopt::AcceptConn)?;
+  match (getsockname(fd)?, socket_type) {
+    (SockAddr::Inet(_), SockType::Stream) if listening => {
+      let listener =
+        unsafe { std::net::TcpListener::from_raw_fd(file.into_raw_fd()) };
+      let (rid, local_addr) = add_tcp_listener(state, listener)?;
+      Ok(json!({
+        "kind": "listener",
+        "rid": rid,
+        "localAddr": {
+          "hostname": local_addr.ip().to_string(),
+          "port": local_addr.port(),
+          "transport": "tcp",
+        },
+      }))
+    }
+    (SockAddr::Inet(_), SockType::Stream) => {
+      let stream =
+        unsafe { std::net::TcpStream::from_raw_fd(file.into_raw_fd()) };
+      stream.set_nonblocking(true)?;
+      let stream = TcpStream::from_std(stream)?;
+      let local_addr = stream.local_addr()?;
+      let remote_addr = stream.peer_addr()?;
+      let rid = state
+        .resource_table
+        .add(TcpStreamResource::new(stream.into_split()));
+      Ok(json!({
+        "kind": "conn",
+        "rid": rid,
+        "localAddr": {
+          "hostname": local_addr.ip().to_string(),
+          "port": local_addr.port(),
+          "transport": "tcp",
+        },
+        "remoteAddr": {
+          "hostname": remote_addr.ip().to_string(),
+          "port": remote_addr.port(),
+          "transport": "tcp",
+        },
+      }))
+    }
+    (SockAddr::Inet(_), SockType::Datagram) => {
+      let socket =
+        unsafe { std::net::UdpSocket::from_raw_fd(file.into_raw_fd()) };
+      let (rid, local_addr) = add_udp_socket(state, socket)?;
+      Ok(json!({
+        "kind": "datagram",
+        "rid": rid,
+        "localAddr": {
+          "hostname": local_addr.ip().to_string(),
+          "port": local_addr.port(),
+          "transport": "udp",
+        },
+      }))
+    }
+    (SockAddr::Unix(addr), SockType::Stream) if listening => {
+      let listener =
+        unsafe { net::UnixListener::from_raw_fd(file.into_raw_fd()) };
+      let rid = add_unix_listener(state, listener)?;
+      Ok(json!({
+        "kind": "listener",
+        "rid": rid,
+        "localAddr": {
+          "path": addr.path(),
+          "transport": "unix",
+        },
+      }))
+    }
+    (SockAddr::Unix(_), SockType::Stream) => {
+      let stream = unsafe { net::UnixStream::from_raw_fd(file.into_raw_fd()) };
+      stream.set_nonblocking(true)?;
+      let stream = UnixStream::from_std(stream)?;
+      let local_addr = stream.local_addr()?;
+      let remote_addr = stream.peer_addr()?;
+      let peer_credentials = peer_credentials(&stream).ok();
+      let rid = state
+        .resource_table
+        .add(StreamResource::unix_stream(stream));
+      Ok(json!({
+        "kind": "conn",
+        "rid": rid,
+        "localAddr": {
+          "path": local_addr.as_pathname(),
+          "transport": "unix",
+        },
+        "remoteAddr": {
+          "path": remote_addr.as_pathname(),
+          "transport": "unix",
+        },
+        "peerCredentials": peer_credentials,
+      }))
+    }
+    (SockAddr::Unix(addr), SockType::Datagram) => {
+      let socket =
+        unsafe { net::UnixDatagram::from_raw_fd(file.into_raw_fd()) };
+      socket.set_nonblocking(true)?;
+      let rid = state.resource_table.add(UnixDatagramResource {
+        socket: AsyncRefCell::new(UnixDatagram::from_std(socket)?),
+        cancel: Default::default(),
+      });
+      Ok(json!({
+        "kind": "datagram",
+        "rid": rid,
+        "localAddr": {
+          "path": addr.path(),
+          "transport": "unixpacket",
+        },
+      }))
+    }
+    // Other sockets can still be read from and written to like files.
+    _ => {
+      let rid = state
+        .resource_table
+        .add(StreamResource::fs_file(tokio::fs::File::from_std(file)));
+      Ok(json!({ "kind": "file", "rid": rid }))
+    }
+  }
+}
+
+/// The Unix stream connection `rid`, whose descriptors are sent or received.
+fn unix_stream_resource(
+  state: &RefCell<OpState>,
+  rid: u32,
+) -> Result<Rc<StreamResource>, AnyError> {
+  let resource = state
+    .borrow()
+    .resource_table
+    .get::<StreamResource>(rid)
+    .ok_or_else(bad_resource_id)?;
+  if resource.unix_stream.is_none() {
+    return Err(type_error("Not a Unix stream connection"));
+  }
+  Ok(resource)
+}
+
+#[derive(Deserialize)]
+pub struct SendFdsArgs {
+  rid: u32,
+  rids: Vec<u32>,
+}
+
+pub async fn op_unix_send_fds(
+  state: Rc<RefCell<OpState>>,
+  args: Value,
+  bufs: BufVec,
+) -> Result<Value, AnyError> {
+  assert_eq!(bufs.len(), 1, "Invalid number of arguments");
+  super::check_unstable2(&state, "Deno.UnixConn.sendFds");
+  let args: SendFdsArgs = serde_json::from_value(args)?;
+  if bufs[0].is_empty() {
+    return Err(type_error(
+      "At least one byte must be sent with descriptors",
+    ));
+  }
+  let files = {
+    let state = state.borrow();
+    args
+      .rids
+      .iter()
+      .map(|rid| dup_resource_fd(&state, *rid))
+      .collect::<Result<Vec<_>, _>>()?
+  };
+  let fds: Vec<RawFd> = files.iter().map(|file| file.as_raw_fd()).collect();
+
+  let resource = unix_stream_resource(&state, args.rid)?;
+  // Messages with descriptors are sent in between the writes of the stream.
+  let stream = RcRef::map(&resource, |r| r.unix_stream.as_ref().unwrap())
+    .borrow_mut()
+    .await;
+  // The readiness of the socket is tracked separately from the one of the
+  // stream, so that it can be cleared when the socket isn't ready.
+  let socket = AsyncFd::new(dup_fd(stream.as_raw_fd())?)?;
+  loop {
+    let mut guard = socket.writable().await?;
+    let iov = [IoVec::from_slice(&bufs[0][..])];
+    let cmsgs = [ControlMessage::ScmRights(&fds)];
+    let fd = socket.get_ref().as_raw_fd();
+    match sendmsg(fd, &iov, &cmsgs, MsgFlags::empty(), None) {
+      Err(nix::Error::Sys(Errno::EAGAIN)) => guard.clear_ready(),
+      result => return Ok(json!(result?)),
+    }
+  }
+}
+
+/// Received descriptors are close-on-exec right away where the platform allows
+/// it, so that a process spawned by another thread doesn't inherit them.
+#[cfg(target_os = "linux")]
+fn receive_flags() -> MsgFlags {
+  MsgFlags::MSG_CMSG_CLOEXEC
+}
+
+#[cfg(not(target_os = "linux"))]
+fn receive_flags() -> MsgFlags {
+  MsgFlags::empty()
+}
+
+fn close_fds(fds: impl IntoIterator<Item = RawFd>) {
+  for fd in fds {
+    drop(unsafe { File::from_raw_fd(fd) });
+  }
+}
+
+#[derive(Deserialize)]
+pub struct ReceiveFdsArgs {
+  rid: u32,
+}
+
+pub async fn op_unix_receive_fds(
+  state: Rc<RefCell<OpState>>,
+  args: Value,
+  bufs: BufVec,
+) -> Result<Value, AnyError> {
+  assert_eq!(bufs.len(), 1, "Invalid number of arguments");
+  super::check_unstable2(&state, "Deno.UnixConn.receiveFds");
+  let args: ReceiveFdsArgs = serde_json::from_value(args)?;
+  let mut buf = bufs.into_iter().next().unwrap();
+
+  let resource = unix_stream_resource(&state, args.rid)?;
+  let stream = RcRef::map(&resource, |r| r.unix_stream.as_ref().unwrap())
+    .borrow_mut()
+    .await;
+  let socket = AsyncFd::new(dup_fd(stream.as_raw_fd())?)?;
+  let receive = async {
+    let mut cmsg_buffer = nix::cmsg_space!([RawFd; MAX_FDS]);
+    loop {
+      let mut guard = socket.readable().await?;
+      let iov = [IoVec::from_mut_slice(&mut buf[..])];
+      match recvmsg(
+        socket.get_ref().as_raw_fd(),
+        &iov,
+        Some(&mut cmsg_buffer),
+        receive_flags(),
+      ) {
+        Err(nix::Error::Sys(Errno::EAGAIN)) => guard.clear_ready(),
+        Err(err) => return Err(nix_io_error(err)),
+        Ok(message) => {
+          let mut fds = Vec::new();
+          for cmsg in message.cmsgs() {
+            if let ControlMessageOwned::ScmRights(received) = cmsg {
+              fds.extend(received);
+            }
+          }
+          if message.flags.contains(MsgFlags::MSG_CTRUNC) {
+            close_fds(fds);
+            return Err(custom_error(
+              "InvalidData",
+              format!("More than {} descriptors were sent", MAX_FDS),
+            ));
+          }
+          return Ok((message.bytes, fds));
+        }
+      }
+    }
+  };
+  let (nread, fds) = receive.try_or_cancel(resource.cancel_handle()).await?;
+  drop(stream);
+
+  let mut state = state.borrow_mut();
+  let mut resources = Vec::new();
+  let mut fds = fds.into_iter();
+  while let Some(fd) = fds.next() {
+    match add_fd_resource(&mut state, fd) {
+      Ok(resource) => resources.push(resource),
+      Err(err) => {
+        // Neither the resources that were added nor the descriptors that
+        // weren't reach JS, so they are all closed.
+        for resource in resources {
+          let rid = resource["rid"].as_u64().unwrap() as u32;
+          state.resource_table.close(rid);
+        }
+        close_fds(fds);
+        return Err(err);
+      }
+    }
+  }
+  Ok(json!({
+    "nread": nread,
+    "resources": resources,
+  }))
 }
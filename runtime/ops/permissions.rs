@@ -1,6 +1,6 @@
 // Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
 
-use crate::permissions::Permissions;
+use crate::permissions::PermissionsContainer;
 use deno_core::error::custom_error;
 use deno_core::error::uri_error;
 use deno_core::error::AnyError;
@@ -32,7 +32,7 @@ pub fn op_query_permission(
   _zero_copy: &mut [ZeroCopyBuf],
 ) -> Result<Value, AnyError> {
   let args: PermissionArgs = serde_json::from_value(args)?;
-  let permissions = state.borrow::<Permissions>();
+  let permissions = state.borrow::<PermissionsContainer>().lock();
   let path = args.path.as_deref();
   let perm = match args.name.as_ref() {
     "read" => permissions.query_read(&path.as_deref().map(Path::new)),
@@ -64,7 +64,7 @@ pub fn op_revoke_permission(
   _zero_copy: &mut [ZeroCopyBuf],
 ) -> Result<Value, AnyError> {
   let args: PermissionArgs = serde_json::from_value(args)?;
-  let permissions = state.borrow_mut::<Permissions>();
+  let mut permissions = state.borrow::<PermissionsContainer>().lock();
   let path = args.path.as_deref();
   let perm = match args.name.as_ref() {
     "read" => permissions.revoke_read(&path.as_deref().map(Path::new)),
@@ -96,7 +96,7 @@ pub fn op_request_permission(
   _zero_copy: &mut [ZeroCopyBuf],
 ) -> Result<Value, AnyError> {
   let args: PermissionArgs = serde_json::from_value(args)?;
-  let permissions = state.borrow_mut::<Permissions>();
+  let mut permissions = state.borrow::<PermissionsContainer>().lock();
   let path = args.path.as_deref();
   let perm = match args.name.as_ref() {
     "read" => permissions.request_read(&path.as_deref().map(Path::new)),
@@ -1,8 +1,9 @@
 // Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
 
 use super::io::{std_file_resource, StreamResource};
-use crate::permissions::Permissions;
+use crate::permissions::PermissionsContainer;
 use deno_core::error::bad_resource_id;
+use deno_core::error::custom_error;
 use deno_core::error::type_error;
 use deno_core::error::AnyError;
 use deno_core::serde_json;
@@ -18,8 +19,15 @@ use deno_core::ZeroCopyBuf;
 use serde::Deserialize;
 use std::borrow::Cow;
 use std::cell::RefCell;
+use std::io::Read;
+use std::io::Write;
 use std::rc::Rc;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+use std::time::Instant;
 use tokio::process::Command;
+use tokio::sync::oneshot;
 
 #[cfg(unix)]
 use std::os::unix::process::ExitStatusExt;
@@ -28,6 +36,9 @@ pub fn init(rt: &mut deno_core::JsRuntime) {
   super::reg_json_sync(rt, "op_run", op_run);
   super::reg_json_async(rt, "op_run_status", op_run_status);
   super::reg_json_sync(rt, "op_kill", op_kill);
+  super::reg_json_sync(rt, "op_exec_sync", op_exec_sync);
+  super::reg_json_async(rt, "op_exec_async", op_exec_async);
+  super::reg_json_sync(rt, "op_exec_output", op_exec_output);
 }
 
 fn clone_file(
@@ -61,10 +72,36 @@ struct RunArgs {
   stdin_rid: u32,
   stdout_rid: u32,
   stderr_rid: u32,
+  uid: Option<u32>,
+  gid: Option<u32>,
+  detached: Option<Detached>,
+  #[serde(default)]
+  clear_env: bool,
+  kill_on_drop: Option<bool>,
+  /// The time in milliseconds after which the child is killed.
+  timeout: Option<f64>,
+  #[serde(default)]
+  pty: bool,
+}
+
+/// How a child is detached from the process group of Deno.
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Detached {
+  /// The child leads a new process group.
+  Group,
+  /// The child leads a new session, and so has no controlling terminal.
+  Session,
 }
 
 struct ChildResource {
   child: AsyncRefCell<tokio::process::Child>,
+  /// The process group that the child leads, which is signalled as a whole
+  /// when the child times out or is dropped.
+  pgid: Option<i32>,
+  kill_on_drop: bool,
+  /// Stops the timer that kills the child once its status is known.
+  timeout: RefCell<Option<oneshot::Sender<()>>>,
 }
 
 impl Resource for ChildResource {
@@ -79,13 +116,145 @@ impl ChildResource {
   }
 }
 
+impl Drop for ChildResource {
+  fn drop(&mut self) {
+    // The child itself is killed by tokio, the rest of its group is killed
+    // here.
+    if let (true, Some(pgid)) = (self.kill_on_drop, self.pgid) {
+      kill(-pgid, SIGKILL).ok();
+    }
+  }
+}
+
+/// Kills the child, or the process group that it leads, once `timeout` has
+/// passed unless the returned sender is dropped first.
+fn kill_after(pid: i32, timeout: Duration) -> oneshot::Sender<()> {
+  let (tx, rx) = oneshot::channel::<()>();
+  tokio::spawn(async move {
+    tokio::select! {
+      _ = tokio::time::sleep(timeout) => {
+        kill(pid, SIGKILL).ok();
+      }
+      _ = rx => {}
+    }
+  });
+  tx
+}
+
+#[cfg(unix)]
+fn detach(c: &mut Command, detached: Detached) {
+  use nix::unistd::setpgid;
+  use nix::unistd::setsid;
+  use nix::unistd::Pid;
+  // Safety: only async-signal-safe functions are called before `exec()`.
+  unsafe {
+    c.pre_exec(move || {
+      let result = match detached {
+        Detached::Group => setpgid(Pid::from_raw(0), Pid::from_raw(0)),
+        Detached::Session => setsid().map(drop),
+      };
+      result.map_err(|_| std::io::Error::last_os_error())
+    });
+  }
+}
+
+/// The pseudo-terminal of a child, whose slave becomes the controlling
+/// terminal of the child.
+#[cfg(target_os = "linux")]
+struct Pty {
+  master: std::fs::File,
+  slave: std::fs::File,
+}
+
+#[cfg(target_os = "linux")]
+impl Pty {
+  fn open() -> Result<Self, AnyError> {
+    use nix::fcntl::fcntl;
+    use nix::fcntl::FcntlArg;
+    use nix::fcntl::FdFlag;
+    use nix::pty::openpty;
+    use nix::pty::Winsize;
+    use std::os::unix::io::FromRawFd;
+
+    let size = Winsize {
+      ws_row: 24,
+      ws_col: 80,
+      ws_xpixel: 0,
+      ws_ypixel: 0,
+    };
+    let pty = openpty(&size, None)?;
+    // Safety: the descriptors were just opened, and are owned by the files.
+    let (master, slave) = unsafe {
+      (
+        std::fs::File::from_raw_fd(pty.master),
+        std::fs::File::from_raw_fd(pty.slave),
+      )
+    };
+    for fd in &[pty.master, pty.slave] {
+      fcntl(*fd, FcntlArg::F_SETFD(FdFlag::FD_CLOEXEC))?;
+    }
+    Ok(Self { master, slave })
+  }
+
+  fn stdio(&self) -> Result<std::process::Stdio, AnyError> {
+    Ok(self.slave.try_clone()?.into())
+  }
+
+  /// Makes the slave the controlling terminal of the child, which needs to
+  /// lead a new session. The child still has the descriptor of the slave open
+  /// until `exec()`, whichever of its streams are connected to it.
+  fn attach(&self, c: &mut Command) {
+    use std::os::unix::io::AsRawFd;
+    let slave = self.slave.as_raw_fd();
+    // Safety: only async-signal-safe functions are called before `exec()`.
+    unsafe {
+      c.pre_exec(move || {
+        if libc::ioctl(slave, libc::TIOCSCTTY as _, 0) != 0 {
+          return Err(std::io::Error::last_os_error());
+        }
+        Ok(())
+      });
+    }
+  }
+}
+
+#[cfg(not(unix))]
+fn detach(c: &mut Command, detached: Detached) {
+  use winapi::um::winbase::CREATE_NEW_PROCESS_GROUP;
+  use winapi::um::winbase::DETACHED_PROCESS;
+  c.creation_flags(match detached {
+    Detached::Group => CREATE_NEW_PROCESS_GROUP,
+    Detached::Session => CREATE_NEW_PROCESS_GROUP | DETACHED_PROCESS,
+  });
+}
+
 fn op_run(
   state: &mut OpState,
   args: Value,
   _zero_copy: &mut [ZeroCopyBuf],
 ) -> Result<Value, AnyError> {
   let run_args: RunArgs = serde_json::from_value(args)?;
-  state.borrow::<Permissions>().check_run()?;
+  if run_args.uid.is_some() || run_args.gid.is_some() {
+    super::check_unstable(state, "RunOptions.uid");
+  }
+  if run_args.detached.is_some() {
+    super::check_unstable(state, "RunOptions.detached");
+  }
+  if run_args.clear_env {
+    super::check_unstable(state, "RunOptions.clearEnv");
+  }
+  if run_args.kill_on_drop.is_some() {
+    super::check_unstable(state, "RunOptions.killOnDrop");
+  }
+  if run_args.timeout.is_some() {
+    super::check_unstable(state, "RunOptions.timeout");
+  }
+  if run_args.pty {
+    super::check_unstable(state, "RunOptions.pty");
+  }
+  state.borrow::<PermissionsContainer>().check_run()?;
+
+  let timeout = timeout_duration(run_args.timeout)?;
 
   let args = run_args.cmd;
   let env = run_args.env;
@@ -97,39 +266,107 @@ fn op_run(
     c.arg(arg);
   });
   cwd.map(|d| c.current_dir(d));
+  if run_args.clear_env {
+    c.env_clear();
+  }
   for (key, value) in &env {
     c.env(key, value);
   }
 
+  #[cfg(unix)]
+  {
+    if let Some(uid) = run_args.uid {
+      c.uid(uid);
+    }
+    if let Some(gid) = run_args.gid {
+      c.gid(gid);
+    }
+  }
+  #[cfg(not(unix))]
+  {
+    if run_args.uid.is_some() || run_args.gid.is_some() {
+      return Err(type_error("uid and gid are not supported on Windows"));
+    }
+  }
+
+  // A child with a pseudo-terminal leads a new session, which the terminal
+  // belongs to.
+  let detached = if run_args.pty {
+    Some(Detached::Session)
+  } else {
+    run_args.detached
+  };
+  if let Some(detached) = detached {
+    detach(&mut c, detached);
+  }
+
+  #[cfg(target_os = "linux")]
+  let pty = if run_args.pty {
+    let pty = Pty::open()?;
+    pty.attach(&mut c);
+    Some(pty)
+  } else {
+    None
+  };
+  #[cfg(not(target_os = "linux"))]
+  {
+    if run_args.pty {
+      return Err(type_error("pty is only supported on Linux"));
+    }
+  }
+
   // TODO: make this work with other resources, eg. sockets
+  // Streams that would be inherited are connected to the pseudo-terminal.
+  #[cfg(target_os = "linux")]
+  let stdio = |name: &str| match (&pty, name) {
+    (Some(pty), "inherit") => pty.stdio(),
+    (_, name) => subprocess_stdio_map(name),
+  };
+  #[cfg(not(target_os = "linux"))]
+  let stdio = subprocess_stdio_map;
+
   if !run_args.stdin.is_empty() {
-    c.stdin(subprocess_stdio_map(run_args.stdin.as_ref())?);
+    c.stdin(stdio(run_args.stdin.as_ref())?);
   } else {
     let file = clone_file(state, run_args.stdin_rid)?;
     c.stdin(file);
   }
 
   if !run_args.stdout.is_empty() {
-    c.stdout(subprocess_stdio_map(run_args.stdout.as_ref())?);
+    c.stdout(stdio(run_args.stdout.as_ref())?);
   } else {
     let file = clone_file(state, run_args.stdout_rid)?;
     c.stdout(file);
   }
 
   if !run_args.stderr.is_empty() {
-    c.stderr(subprocess_stdio_map(run_args.stderr.as_ref())?);
+    c.stderr(stdio(run_args.stderr.as_ref())?);
   } else {
     let file = clone_file(state, run_args.stderr_rid)?;
     c.stderr(file);
   }
 
-  // We want to kill child when it's closed
-  c.kill_on_drop(true);
+  // We want to kill child when it's closed, unless asked not to.
+  let kill_on_drop = run_args.kill_on_drop.unwrap_or(true);
+  c.kill_on_drop(kill_on_drop);
 
   // Spawn the command.
   let mut child = c.spawn()?;
   let pid = child.id();
 
+  // A detached child leads its own process group on Unix, which is
+  // signalled instead of the child alone.
+  let pgid = match (cfg!(unix), detached, pid) {
+    (true, Some(_), Some(pid)) => Some(pid as i32),
+    _ => None,
+  };
+  let timeout = match (timeout, pid) {
+    (Some(timeout), Some(pid)) => {
+      Some(kill_after(pgid.map_or(pid as i32, |pgid| -pgid), timeout))
+    }
+    _ => None,
+  };
+
   let stdin_rid = match child.stdin.take() {
     Some(child_stdin) => {
       let rid = state
@@ -160,8 +397,22 @@ fn op_run(
     None => None,
   };
 
+  // The slave is closed by the parent, so that reading the master ends once
+  // the child has closed it.
+  #[cfg(target_os = "linux")]
+  let pty_rid = pty.map(|Pty { master, .. }| {
+    state
+      .resource_table
+      .add(StreamResource::pty(tokio::fs::File::from_std(master)))
+  });
+  #[cfg(not(target_os = "linux"))]
+  let pty_rid: Option<u32> = None;
+
   let child_resource = ChildResource {
     child: AsyncRefCell::new(child),
+    pgid,
+    kill_on_drop,
+    timeout: RefCell::new(timeout),
   };
   let child_rid = state.resource_table.add(child_resource);
 
@@ -171,6 +422,7 @@ fn op_run(
     "stdinRid": stdin_rid,
     "stdoutRid": stdout_rid,
     "stderrRid": stderr_rid,
+    "ptyRid": pty_rid,
   }))
 }
 
@@ -190,7 +442,7 @@ async fn op_run_status(
 
   {
     let s = state.borrow();
-    s.borrow::<Permissions>().check_run()?;
+    s.borrow::<PermissionsContainer>().check_run()?;
   }
 
   let resource = state
@@ -198,8 +450,14 @@ async fn op_run_status(
     .resource_table
     .get::<ChildResource>(rid)
     .ok_or_else(bad_resource_id)?;
-  let mut child = resource.borrow_mut().await;
+  let mut child = resource.clone().borrow_mut().await;
   let run_status = child.wait().await?;
+  // The child is reaped, so its pid must not be signalled anymore.
+  resource.timeout.borrow_mut().take();
+  Ok(status_json(run_status))
+}
+
+fn status_json(run_status: std::process::ExitStatus) -> Value {
   let code = run_status.code();
 
   #[cfg(unix)]
@@ -212,13 +470,372 @@ async fn op_run_status(
     .expect("Should have either an exit code or a signal.");
   let got_signal = signal.is_some();
 
-  Ok(json!({
+  json!({
      "gotSignal": got_signal,
      "exitCode": code.unwrap_or(-1),
      "exitSignal": signal.unwrap_or(-1),
-  }))
+  })
+}
+
+/// Converts a timeout option from milliseconds.
+fn timeout_duration(millis: Option<f64>) -> Result<Option<Duration>, AnyError> {
+  match millis {
+    Some(millis) if !millis.is_finite() || millis <= 0.0 => Err(type_error(
+      "timeout must be a positive number of milliseconds",
+    )),
+    Some(millis) => Ok(Some(Duration::from_secs_f64(millis / 1000.0))),
+    None => Ok(None),
+  }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExecArgs {
+  cmd: Vec<String>,
+  cwd: Option<String>,
+  env: Vec<(String, String)>,
+  #[serde(default)]
+  clear_env: bool,
+  /// The time in milliseconds after which the child is killed.
+  timeout: Option<f64>,
+  /// The most bytes that are captured of stdout, and of stderr.
+  max_output: Option<usize>,
+}
+
+/// The status and the captured output of a command run by `exec()`.
+struct ExecOutput {
+  status: std::process::ExitStatus,
+  stdout: Vec<u8>,
+  stderr: Vec<u8>,
+}
+
+/// Holds the output of `Deno.exec()` until `op_exec_output` copies it into
+/// buffers allocated by JavaScript.
+struct ExecOutputResource {
+  stdout: Vec<u8>,
+  stderr: Vec<u8>,
 }
 
+impl Resource for ExecOutputResource {
+  fn name(&self) -> Cow<str> {
+    "execOutput".into()
+  }
+}
+
+/// What the threads started by `exec()` report back.
+enum ExecEvent {
+  /// The child has exited, but hasn't been waited for yet.
+  Exited,
+  /// The input has been written to stdin.
+  Written(std::io::Result<()>),
+  Stdout(std::io::Result<Captured>),
+  Stderr(std::io::Result<Captured>),
+}
+
+/// The output read from a pipe, and whether there was more than the limit.
+struct Captured {
+  output: Vec<u8>,
+  overflowed: bool,
+}
+
+/// Runs a command to completion, and returns its status and output. The
+/// input is written to stdin, which is null otherwise. It's a copy of the JS
+/// buffer, which JS may change while the input is written on another thread.
+fn exec(
+  args: ExecArgs,
+  input: Option<Vec<u8>>,
+) -> Result<ExecOutput, AnyError> {
+  use std::io::ErrorKind;
+  use std::process::Stdio;
+
+  let timeout = timeout_duration(args.timeout)?;
+  let (program, rest) = args
+    .cmd
+    .split_first()
+    .ok_or_else(|| type_error("cmd must not be empty"))?;
+
+  let mut c = std::process::Command::new(program);
+  c.args(rest);
+  if let Some(cwd) = &args.cwd {
+    c.current_dir(cwd);
+  }
+  if args.clear_env {
+    c.env_clear();
+  }
+  for (key, value) in &args.env {
+    c.env(key, value);
+  }
+  c.stdin(match input {
+    Some(_) => Stdio::piped(),
+    None => Stdio::null(),
+  });
+  c.stdout(Stdio::piped());
+  c.stderr(Stdio::piped());
+
+  let deadline = timeout.map(|timeout| Instant::now() + timeout);
+  let mut child = c.spawn()?;
+
+  // The exit and the pipes are waited for on their own threads, so that a
+  // child that fills one of the pipes doesn't block, and so that the deadline
+  // also applies to pipes that a grandchild keeps open.
+  let (tx, rx) = mpsc::channel();
+  notify_exit(&child, tx.clone());
+  let mut pending_input = false;
+  if let (Some(mut stdin), Some(input)) = (child.stdin.take(), input) {
+    pending_input = true;
+    let tx = tx.clone();
+    thread::spawn(move || {
+      let result = match stdin.write_all(&input) {
+        // The child doesn't have to read all of its input.
+        Err(err) if err.kind() == ErrorKind::BrokenPipe => Ok(()),
+        result => result,
+      };
+      let _ = tx.send(ExecEvent::Written(result));
+    });
+  }
+  capture(
+    child.stdout.take(),
+    args.max_output,
+    tx.clone(),
+    ExecEvent::Stdout,
+  );
+  capture(child.stderr.take(), args.max_output, tx, ExecEvent::Stderr);
+
+  let mut exited = false;
+  let mut stdout = None;
+  let mut stderr = None;
+  let mut overflowed = false;
+  let mut timed_out = false;
+  let mut error = None;
+  while !(exited && !pending_input && stdout.is_some() && stderr.is_some()) {
+    let event = match deadline {
+      Some(deadline) => {
+        let now = Instant::now();
+        if now >= deadline {
+          timed_out = true;
+          break;
+        }
+        match rx.recv_timeout(deadline - now) {
+          Ok(event) => event,
+          Err(mpsc::RecvTimeoutError::Timeout) => continue,
+          Err(mpsc::RecvTimeoutError::Disconnected) => unreachable!(),
+        }
+      }
+      None => rx.recv().unwrap(),
+    };
+    let result = match event {
+      ExecEvent::Exited => {
+        exited = true;
+        Ok(())
+      }
+      ExecEvent::Written(result) => {
+        pending_input = false;
+        result
+      }
+      ExecEvent::Stdout(captured) => captured.map(|captured| {
+        overflowed |= captured.overflowed;
+        stdout = Some(captured.output);
+      }),
+      ExecEvent::Stderr(captured) => captured.map(|captured| {
+        overflowed |= captured.overflowed;
+        stderr = Some(captured.output);
+      }),
+    };
+    if let Err(err) = result {
+      error = Some(err);
+      break;
+    }
+    if overflowed {
+      break;
+    }
+  }
+
+  // The child is always waited for, and it's killed when it's no longer
+  // needed. Pipes that a grandchild keeps open are left to their threads.
+  if !exited && (timed_out || overflowed || error.is_some()) {
+    child.kill()?;
+    while !matches!(rx.recv(), Ok(ExecEvent::Exited)) {}
+  }
+  let status = child.wait()?;
+  if let Some(error) = error {
+    return Err(error.into());
+  }
+  if timed_out {
+    return Err(custom_error(
+      "TimedOut",
+      "The subprocess didn't finish within the timeout",
+    ));
+  }
+  if overflowed {
+    return Err(custom_error(
+      "RangeError",
+      format!(
+        "The output of the subprocess exceeded maxOutput of {} bytes",
+        args.max_output.unwrap_or_default()
+      ),
+    ));
+  }
+
+  Ok(ExecOutput {
+    status,
+    stdout: stdout.unwrap(),
+    stderr: stderr.unwrap(),
+  })
+}
+
+/// Sends `ExecEvent::Exited` once the child has exited, without waiting for
+/// it, so that it can't be replaced by another process before it's killed or
+/// waited for through `child`.
+#[cfg(unix)]
+fn notify_exit(child: &std::process::Child, tx: mpsc::Sender<ExecEvent>) {
+  let pid = child.id() as libc::id_t;
+  thread::spawn(move || {
+    loop {
+      let mut info: libc::siginfo_t = unsafe { std::mem::zeroed() };
+      let result = unsafe {
+        libc::waitid(libc::P_PID, pid, &mut info, libc::WEXITED | libc::WNOWAIT)
+      };
+      if result == 0
+        || std::io::Error::last_os_error().kind()
+          != std::io::ErrorKind::Interrupted
+      {
+        break;
+      }
+    }
+    let _ = tx.send(ExecEvent::Exited);
+  });
+}
+
+/// Sends `ExecEvent::Exited` once the child has exited. `exec()` waits for
+/// this before dropping `child`, which closes the handle.
+#[cfg(not(unix))]
+fn notify_exit(child: &std::process::Child, tx: mpsc::Sender<ExecEvent>) {
+  use std::os::windows::io::AsRawHandle;
+  use winapi::um::synchapi::WaitForSingleObject;
+  use winapi::um::winbase::INFINITE;
+  let handle = child.as_raw_handle() as usize;
+  thread::spawn(move || {
+    unsafe { WaitForSingleObject(handle as _, INFINITE) };
+    let _ = tx.send(ExecEvent::Exited);
+  });
+}
+
+/// Reads a pipe of a child on a new thread, up to `limit` bytes, and sends
+/// the output as the event made by `event`. More output than `limit` closes
+/// the pipe.
+fn capture<R: Read + Send + 'static>(
+  pipe: Option<R>,
+  limit: Option<usize>,
+  tx: mpsc::Sender<ExecEvent>,
+  event: fn(std::io::Result<Captured>) -> ExecEvent,
+) {
+  thread::spawn(move || {
+    let result = (|| {
+      let mut output = Vec::new();
+      let mut overflowed = false;
+      match (pipe, limit) {
+        (Some(pipe), Some(limit)) => {
+          pipe.take(limit as u64 + 1).read_to_end(&mut output)?;
+          if output.len() > limit {
+            output.truncate(limit);
+            overflowed = true;
+          }
+        }
+        (Some(mut pipe), None) => {
+          pipe.read_to_end(&mut output)?;
+        }
+        (None, _) => {}
+      }
+      Ok(Captured { output, overflowed })
+    })();
+    let _ = tx.send(event(result));
+  });
+}
+
+/// Keeps the output of `exec()` in a resource, to be copied out by
+/// `op_exec_output`.
+fn exec_json(state: &mut OpState, output: ExecOutput) -> Value {
+  let stdout_length = output.stdout.len();
+  let stderr_length = output.stderr.len();
+  let rid = state.resource_table.add(ExecOutputResource {
+    stdout: output.stdout,
+    stderr: output.stderr,
+  });
+  json!({
+    "status": status_json(output.status),
+    "rid": rid,
+    "stdoutLength": stdout_length,
+    "stderrLength": stderr_length,
+  })
+}
+
+fn op_exec_sync(
+  state: &mut OpState,
+  args: Value,
+  zero_copy: &mut [ZeroCopyBuf],
+) -> Result<Value, AnyError> {
+  super::check_unstable(state, "Deno.execSync");
+  let args: ExecArgs = serde_json::from_value(args)?;
+  state.borrow::<PermissionsContainer>().check_run()?;
+
+  let input = zero_copy.first().map(|buf| buf.to_vec());
+  let output = exec(args, input)?;
+  Ok(exec_json(state, output))
+}
+
+async fn op_exec_async(
+  state: Rc<RefCell<OpState>>,
+  args: Value,
+  zero_copy: BufVec,
+) -> Result<Value, AnyError> {
+  super::check_unstable2(&state, "Deno.exec");
+  let args: ExecArgs = serde_json::from_value(args)?;
+  {
+    let s = state.borrow();
+    s.borrow::<PermissionsContainer>().check_run()?;
+  }
+
+  let input = zero_copy.first().map(|buf| buf.to_vec());
+  let output = tokio::task::spawn_blocking(move || exec(args, input))
+    .await
+    .unwrap()?;
+  Ok(exec_json(&mut state.borrow_mut(), output))
+}
+
+#[derive(Deserialize)]
+struct ExecOutputArgs {
+  rid: u32,
+}
+
+/// Copies the output of `Deno.exec()` into the given stdout and stderr
+/// buffers, and closes its resource.
+fn op_exec_output(
+  state: &mut OpState,
+  args: Value,
+  zero_copy: &mut [ZeroCopyBuf],
+) -> Result<Value, AnyError> {
+  let args: ExecOutputArgs = serde_json::from_value(args)?;
+  if zero_copy.len() != 2 {
+    return Err(type_error("Expected a stdout and a stderr buffer"));
+  }
+  let resource = state
+    .resource_table
+    .take::<ExecOutputResource>(args.rid)
+    .ok_or_else(bad_resource_id)?;
+  let (stdout, stderr) = zero_copy.split_at_mut(1);
+  if stdout[0].len() != resource.stdout.len()
+    || stderr[0].len() != resource.stderr.len()
+  {
+    return Err(type_error("The buffers must fit the output exactly"));
+  }
+  stdout[0].copy_from_slice(&resource.stdout);
+  stderr[0].copy_from_slice(&resource.stderr);
+  Ok(json!({}))
+}
+
+#[cfg(unix)]
+const SIGKILL: i32 = libc::SIGKILL;
+
 #[cfg(not(unix))]
 const SIGINT: i32 = 2;
 #[cfg(not(unix))]
@@ -286,7 +903,7 @@ fn op_kill(
   _zero_copy: &mut [ZeroCopyBuf],
 ) -> Result<Value, AnyError> {
   super::check_unstable(state, "Deno.kill");
-  state.borrow::<Permissions>().check_run()?;
+  state.borrow::<PermissionsContainer>().check_run()?;
 
   let args: KillArgs = serde_json::from_value(args)?;
   kill(args.pid, args.signo)?;
@@ -1,7 +1,7 @@
 // Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
 
 use crate::metrics::Metrics;
-use crate::permissions::Permissions;
+use crate::permissions::PermissionsContainer;
 use deno_core::error::AnyError;
 use deno_core::serde_json;
 use deno_core::serde_json::json;
@@ -30,7 +30,7 @@ fn op_main_module(
   if main_url.as_url().scheme() == "file" {
     let main_path = std::env::current_dir().unwrap().join(main_url.to_string());
     state
-      .borrow::<Permissions>()
+      .borrow::<PermissionsContainer>()
       .check_read_blind(&main_path, "main_module")?;
   }
   Ok(json!(&main))
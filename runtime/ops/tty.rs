@@ -50,6 +50,7 @@ pub fn init(rt: &mut deno_core::JsRuntime) {
   super::reg_json_sync(rt, "op_set_raw", op_set_raw);
   super::reg_json_sync(rt, "op_isatty", op_isatty);
   super::reg_json_sync(rt, "op_console_size", op_console_size);
+  super::reg_json_sync(rt, "op_pty_resize", op_pty_resize);
 }
 
 #[derive(Deserialize)]
@@ -323,3 +324,54 @@ fn op_console_size(
 
   Ok(json!(size))
 }
+
+#[derive(Deserialize)]
+struct PtyResizeArgs {
+  rid: u32,
+  columns: u16,
+  rows: u16,
+}
+
+/// Sets the size of the pseudo-terminal of a subprocess, which is signalled
+/// with `SIGWINCH`.
+fn op_pty_resize(
+  state: &mut OpState,
+  args: Value,
+  _zero_copy: &mut [ZeroCopyBuf],
+) -> Result<Value, AnyError> {
+  super::check_unstable(state, "Deno.Pty.resize");
+
+  let args: PtyResizeArgs = serde_json::from_value(args)?;
+  let rid = args.rid;
+
+  std_file_resource(state, rid as u32, move |r| match r {
+    Ok(std_file) => {
+      #[cfg(unix)]
+      {
+        use std::os::unix::io::AsRawFd;
+
+        let fd = std_file.as_raw_fd();
+        let size = libc::winsize {
+          ws_row: args.rows,
+          ws_col: args.columns,
+          ws_xpixel: 0,
+          ws_ypixel: 0,
+        };
+        if unsafe { libc::ioctl(fd, libc::TIOCSWINSZ, &size as *const _) } != 0
+        {
+          return Err(Error::last_os_error().into());
+        }
+        Ok(())
+      }
+
+      #[cfg(windows)]
+      {
+        let _ = (std_file, args.columns, args.rows);
+        Err(not_supported())
+      }
+    }
+    Err(_) => Err(bad_resource_id()),
+  })?;
+
+  Ok(json!({}))
+}
@@ -0,0 +1,58 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+use deno_web::BlobUrlStore;
+
+pub fn init(rt: &mut deno_core::JsRuntime, blob_url_store: BlobUrlStore) {
+  rt.op_state()
+    .borrow_mut()
+    .put::<BlobUrlStore>(blob_url_store);
+  super::reg_json_sync(rt, "op_domain_to_ascii", deno_web::op_domain_to_ascii);
+  super::reg_json_sync(rt, "op_compression_new", deno_web::op_compression_new);
+  super::reg_json_sync(
+    rt,
+    "op_compression_write",
+    deno_web::op_compression_write,
+  );
+  super::reg_json_sync(
+    rt,
+    "op_compression_finish",
+    deno_web::op_compression_finish,
+  );
+  super::reg_json_sync(
+    rt,
+    "op_compression_take",
+    deno_web::op_compression_take,
+  );
+  super::reg_json_sync(
+    rt,
+    "op_create_object_url",
+    deno_web::op_create_object_url,
+  );
+  super::reg_json_sync(
+    rt,
+    "op_revoke_object_url",
+    deno_web::op_revoke_object_url,
+  );
+  super::reg_json_sync(
+    rt,
+    "op_resolve_object_url",
+    deno_web::op_resolve_object_url,
+  );
+  super::reg_json_sync(rt, "op_read_object_url", deno_web::op_read_object_url);
+  super::reg_json_sync(
+    rt,
+    "op_encoding_normalize_label",
+    deno_web::op_encoding_normalize_label,
+  );
+  super::reg_json_sync(
+    rt,
+    "op_encoding_decode_single",
+    deno_web::op_encoding_decode_single,
+  );
+  super::reg_json_sync(
+    rt,
+    "op_encoding_new_decoder",
+    deno_web::op_encoding_new_decoder,
+  );
+  super::reg_json_sync(rt, "op_encoding_decode", deno_web::op_encoding_decode);
+}
@@ -1,12 +1,26 @@
 // Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
-use crate::permissions::Permissions;
+use super::io::StreamResource;
+use super::io::TcpStreamResource;
+use crate::permissions::PermissionsContainer;
+use deno_core::error::bad_resource_id;
+use deno_core::error::custom_error;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_core::serde_json::Value;
+use deno_core::BufVec;
+use deno_core::OpState;
+use deno_websocket::accept_web_socket;
 use deno_websocket::op_ws_check_permission;
 use deno_websocket::op_ws_close;
 use deno_websocket::op_ws_create;
 use deno_websocket::op_ws_next_event;
 use deno_websocket::op_ws_send;
+use deno_websocket::AcceptArgs;
 use deno_websocket::WsCaData;
+use deno_websocket::WsIo;
 use deno_websocket::WsUserAgent;
+use std::cell::RefCell;
+use std::rc::Rc;
 
 pub fn init(
   rt: &mut deno_core::JsRuntime,
@@ -24,10 +38,72 @@ pub fn init(
   super::reg_json_sync(
     rt,
     "op_ws_check_permission",
-    op_ws_check_permission::<Permissions>,
+    op_ws_check_permission::<PermissionsContainer>,
+  );
+  super::reg_json_async(
+    rt,
+    "op_ws_create",
+    op_ws_create::<PermissionsContainer>,
   );
-  super::reg_json_async(rt, "op_ws_create", op_ws_create::<Permissions>);
   super::reg_json_async(rt, "op_ws_send", op_ws_send);
   super::reg_json_async(rt, "op_ws_close", op_ws_close);
   super::reg_json_async(rt, "op_ws_next_event", op_ws_next_event);
+  super::reg_json_async(rt, "op_ws_upgrade", op_ws_upgrade);
+}
+
+/// Takes the stream of an accepted TCP or TLS connection out of the resource
+/// table. Fails with `Busy`, leaving the connection in the table, if a read or
+/// write on it is still pending.
+fn take_conn_stream(
+  state: &mut OpState,
+  rid: u32,
+) -> Result<Box<dyn WsIo>, AnyError> {
+  if let Some(resource) = state.resource_table.get::<TcpStreamResource>(rid) {
+    check_not_busy(resource)?;
+    let resource = state
+      .resource_table
+      .take::<TcpStreamResource>(rid)
+      .ok_or_else(bad_resource_id)?;
+    let resource = Rc::try_unwrap(resource).map_err(|_| busy())?;
+    let (read_half, write_half) = resource.into_inner();
+    return Ok(Box::new(read_half.reunite(write_half)?));
+  }
+  match state.resource_table.get::<StreamResource>(rid) {
+    Some(resource) if resource.is_server_tls_stream() => {
+      check_not_busy(resource)?;
+      let resource = state
+        .resource_table
+        .take::<StreamResource>(rid)
+        .ok_or_else(bad_resource_id)?;
+      let resource = Rc::try_unwrap(resource).map_err(|_| busy())?;
+      Ok(Box::new(resource.into_server_tls_stream().unwrap()))
+    }
+    _ => Err(bad_resource_id()),
+  }
+}
+
+/// A resource only referenced by the resource table and `resource` itself has
+/// no pending read or write.
+fn check_not_busy<T>(resource: Rc<T>) -> Result<(), AnyError> {
+  if Rc::strong_count(&resource) > 2 {
+    return Err(busy());
+  }
+  Ok(())
+}
+
+fn busy() -> AnyError {
+  custom_error("Busy", "Connection is in use")
+}
+
+/// Upgrades a connection accepted by `Deno.listen()` or `Deno.listenTls()` to
+/// a server-side WebSocket.
+async fn op_ws_upgrade(
+  state: Rc<RefCell<OpState>>,
+  args: Value,
+  _bufs: BufVec,
+) -> Result<Value, AnyError> {
+  super::check_unstable2(&state, "Deno.upgradeWebSocket");
+  let args: AcceptArgs = serde_json::from_value(args)?;
+  let stream = take_conn_stream(&mut state.borrow_mut(), args.rid)?;
+  accept_web_socket(state, stream, args).await
 }
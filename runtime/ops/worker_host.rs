@@ -3,6 +3,7 @@
 use crate::permissions::resolve_fs_allowlist;
 use crate::permissions::PermissionState;
 use crate::permissions::Permissions;
+use crate::permissions::PermissionsContainer;
 use crate::permissions::UnaryPermission;
 use crate::web_worker::run_web_worker;
 use crate::web_worker::WebWorker;
@@ -37,7 +38,7 @@ use std::thread::JoinHandle;
 pub struct CreateWebWorkerArgs {
   pub name: String,
   pub worker_id: u32,
-  pub parent_permissions: Permissions,
+  pub parent_permissions: PermissionsContainer,
   pub permissions: Permissions,
   pub main_module: ModuleSpecifier,
   pub use_deno_namespace: bool,
@@ -148,6 +149,7 @@ fn merge_net_permissions(
       global_state: new_permissions.global_state,
       granted_list: new_permissions.granted_list,
       denied_list: new_permissions.denied_list,
+      blocked_list: target.blocked_list.clone(),
     }),
     PermissionState::Prompt => match new_permissions.global_state {
       //Throw
@@ -165,6 +167,7 @@ fn merge_net_permissions(
             global_state: new_permissions.global_state,
             granted_list: new_permissions.granted_list,
             denied_list: target.denied_list.clone(),
+            blocked_list: target.blocked_list.clone(),
           })
         } else {
           Err(custom_error(
@@ -178,6 +181,7 @@ fn merge_net_permissions(
         global_state: new_permissions.global_state,
         granted_list: new_permissions.granted_list,
         denied_list: new_permissions.denied_list,
+        blocked_list: target.blocked_list.clone(),
       }),
     },
     PermissionState::Denied => match new_permissions.global_state {
@@ -185,6 +189,7 @@ fn merge_net_permissions(
         global_state: new_permissions.global_state,
         granted_list: new_permissions.granted_list,
         denied_list: new_permissions.denied_list,
+        blocked_list: target.blocked_list.clone(),
       }),
       _ => Err(custom_error(
         "PermissionDenied",
@@ -233,6 +238,7 @@ fn merge_read_write_permissions(
       global_state: new_permissions.global_state,
       granted_list: new_permissions.granted_list,
       denied_list: new_permissions.denied_list,
+      blocked_list: target.blocked_list.clone(),
     }),
     PermissionState::Prompt => match new_permissions.global_state {
       //Throw
@@ -256,6 +262,7 @@ fn merge_read_write_permissions(
             global_state: new_permissions.global_state,
             granted_list: new_permissions.granted_list,
             denied_list: target.denied_list.clone(),
+            blocked_list: target.blocked_list.clone(),
           })
         } else {
           Err(custom_error(
@@ -269,6 +276,7 @@ fn merge_read_write_permissions(
         global_state: new_permissions.global_state,
         granted_list: new_permissions.granted_list,
         denied_list: new_permissions.denied_list,
+        blocked_list: target.blocked_list.clone(),
       }),
     },
     PermissionState::Denied => match new_permissions.global_state {
@@ -276,6 +284,7 @@ fn merge_read_write_permissions(
         global_state: new_permissions.global_state,
         granted_list: new_permissions.granted_list,
         denied_list: new_permissions.denied_list,
+        blocked_list: target.blocked_list.clone(),
       }),
       _ => Err(custom_error(
         "PermissionDenied",
@@ -322,6 +331,8 @@ fn create_worker_permissions(
       permission_args.write,
       &main_thread_permissions,
     )?,
+    origin_scopes: main_thread_permissions.origin_scopes.clone(),
+    creator_scopes: main_thread_permissions.creator_scopes.clone(),
   })
 }
 
@@ -469,12 +480,20 @@ fn op_create_worker(
   if use_deno_namespace {
     super::check_unstable(state, "Worker.deno.namespace");
   }
-  let parent_permissions = state.borrow::<Permissions>().clone();
-  let worker_permissions = if let Some(permissions) = args.permissions {
-    super::check_unstable(state, "Worker.deno.permissions");
-    create_worker_permissions(&parent_permissions, permissions)?
-  } else {
-    parent_permissions.clone()
+  let parent_permissions = state.borrow::<PermissionsContainer>().clone();
+  let worker_permissions = {
+    let parent_permissions = parent_permissions.lock();
+    let mut worker_permissions = if let Some(permissions) = args.permissions {
+      super::check_unstable(state, "Worker.deno.permissions");
+      create_worker_permissions(&parent_permissions, permissions)?
+    } else {
+      parent_permissions.clone()
+    };
+    // A worker is limited to the scopes of the code that created it, as its
+    // own modules, e.g. `blob:` or `data:` URLs, might match no scope.
+    worker_permissions.creator_scopes =
+      parent_permissions.worker_creator_scopes()?;
+    worker_permissions
   };
 
   let worker_id = state.take::<WorkerId>();
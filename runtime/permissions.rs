@@ -5,23 +5,25 @@ use crate::fs_util::resolve_from_cwd;
 use deno_core::error::custom_error;
 use deno_core::error::uri_error;
 use deno_core::error::AnyError;
+use deno_core::error::JsStackFrame;
+use deno_core::op_callers;
 use deno_core::url;
 use deno_core::ModuleSpecifier;
+use ipnet::IpNet;
 use serde::Deserialize;
 use serde::Serialize;
+use std::collections::BTreeMap;
 use std::collections::HashSet;
 use std::env::current_dir;
 use std::fmt;
 use std::hash::Hash;
 #[cfg(not(test))]
 use std::io;
+use std::net::IpAddr;
 use std::path::{Path, PathBuf};
-#[cfg(test)]
-use std::sync::atomic::AtomicBool;
-#[cfg(test)]
-use std::sync::atomic::Ordering;
-#[cfg(test)]
+use std::sync::Arc;
 use std::sync::Mutex;
+use std::sync::MutexGuard;
 
 const PERMISSION_EMOJI: &str = "⚠️";
 
@@ -66,6 +68,80 @@ pub struct UnaryPermission<T: Eq + Hash> {
   pub global_state: PermissionState,
   pub granted_list: HashSet<T>,
   pub denied_list: HashSet<T>,
+  /// Entries denied up front, e.g. by a permissions manifest. Unlike
+  /// `denied_list` they cover everything below them, take precedence over any
+  /// grant and are never prompted for.
+  #[serde(default)]
+  pub blocked_list: HashSet<T>,
+}
+
+impl UnaryPermission<PathBuf> {
+  fn grant_path(&mut self, path: PathBuf) {
+    self.granted_list.retain(|path_| !path_.starts_with(&path));
+    self.granted_list.insert(path);
+  }
+
+  fn deny_path(&mut self, path: PathBuf) {
+    self.denied_list.retain(|path_| !path.starts_with(path_));
+    self.denied_list.insert(path);
+    self.global_state = PermissionState::Denied;
+  }
+
+  fn grant_all(&mut self) {
+    self.granted_list.clear();
+    self.global_state = PermissionState::Granted;
+  }
+
+  /// Prompts for access to a single path. Besides the path itself, the user
+  /// may grant everything in the directory containing it, or in the path
+  /// itself when it is a directory, or the whole permission kind.
+  fn request_path(
+    &mut self,
+    resolved_path: PathBuf,
+    display_path: &Path,
+    kind: &str,
+  ) -> PermissionState {
+    let scope = if resolved_path.is_dir() {
+      Some((
+        resolved_path.clone(),
+        format!("all of \"{}\"", display_path.display()),
+      ))
+    } else {
+      resolved_path.parent().map(|parent| {
+        let display_parent = match display_path.parent() {
+          Some(p) if p.as_os_str().is_empty() => Path::new("."),
+          Some(p) => p,
+          None => parent,
+        };
+        (
+          parent.to_path_buf(),
+          format!("all of \"{}\"", display_parent.display()),
+        )
+      })
+    };
+    let response = permission_prompt(
+      &format!(
+        "Deno requests {} access to \"{}\"",
+        kind,
+        display_path.display()
+      ),
+      scope.as_ref().map(|(_, desc)| desc.as_str()),
+      Some(format!("all {} access", kind).as_str()),
+    );
+    match response {
+      PromptResponse::Allow => self.grant_path(resolved_path),
+      PromptResponse::AllowScope => match scope {
+        Some((scope_path, _)) => self.grant_path(scope_path),
+        None => self.grant_path(resolved_path),
+      },
+      PromptResponse::AllowAll => self.grant_all(),
+      PromptResponse::Deny => {
+        self.deny_path(resolved_path);
+        return PermissionState::Denied;
+      }
+    }
+    PermissionState::Granted
+  }
 }
 
 #[derive(Clone, Debug, Default, Deserialize, PartialEq)]
@@ -77,6 +153,25 @@ pub struct Permissions {
   pub run: PermissionState,
   pub plugin: PermissionState,
   pub hrtime: PermissionState,
+  /// Narrower permissions for the code of particular modules. They are only
+  /// enforced when the runtime captures the callers of ops, see
+  /// `deno_core::RuntimeOptions::capture_op_callers`.
+  #[serde(default)]
+  pub origin_scopes: Vec<OriginScope>,
+  /// The scopes of the code that created a worker, which apply to all of
+  /// the worker's code, whatever module it comes from.
+  #[serde(default)]
+  pub creator_scopes: Vec<OriginScope>,
+}
+
+/// Permissions for modules whose specifier starts with `prefix`, e.g.
+/// `https://deno.land/x/sqlite/`. They apply on top of the process wide
+/// permissions: an access made while code of such a module is on the stack
+/// must be allowed by both.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+pub struct OriginScope {
+  pub prefix: String,
+  pub permissions: Permissions,
 }
 
 pub fn resolve_fs_allowlist(allow: &Option<Vec<PathBuf>>) -> HashSet<PathBuf> {
@@ -90,6 +185,7 @@ pub fn resolve_fs_allowlist(allow: &Option<Vec<PathBuf>>) -> HashSet<PathBuf> {
 }
 
 #[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct PermissionsOptions {
   pub allow_env: bool,
   pub allow_hrtime: bool,
@@ -98,19 +194,35 @@ pub struct PermissionsOptions {
   pub allow_read: Option<Vec<PathBuf>>,
   pub allow_run: bool,
   pub allow_write: Option<Vec<PathBuf>>,
+  pub deny_env: bool,
+  pub deny_hrtime: bool,
+  pub deny_net: Option<Vec<String>>,
+  pub deny_plugin: bool,
+  pub deny_read: Option<Vec<PathBuf>>,
+  pub deny_run: bool,
+  pub deny_write: Option<Vec<PathBuf>>,
+  /// Permissions for the modules under each URL prefix, see `OriginScope`.
+  pub scopes: BTreeMap<String, PermissionsOptions>,
 }
 
 impl Permissions {
   pub fn from_options(opts: &PermissionsOptions) -> Self {
-    fn global_state_from_flag_bool(flag: bool) -> PermissionState {
-      if flag {
+    fn global_state_from_flag_bool(flag: bool, deny: bool) -> PermissionState {
+      if deny {
+        PermissionState::Denied
+      } else if flag {
         PermissionState::Granted
       } else {
         PermissionState::Prompt
       }
     }
-    fn global_state_from_option<T>(flag: &Option<Vec<T>>) -> PermissionState {
-      if matches!(flag, Some(v) if v.is_empty()) {
+    fn global_state_from_option<T>(
+      flag: &Option<Vec<T>>,
+      deny: &Option<Vec<T>>,
+    ) -> PermissionState {
+      if matches!(deny, Some(v) if v.is_empty()) {
+        PermissionState::Denied
+      } else if matches!(flag, Some(v) if v.is_empty()) {
         PermissionState::Granted
       } else {
         PermissionState::Prompt
@@ -118,31 +230,59 @@ impl Permissions {
     }
     Self {
       read: UnaryPermission::<PathBuf> {
-        global_state: global_state_from_option(&opts.allow_read),
+        global_state: global_state_from_option(
+          &opts.allow_read,
+          &opts.deny_read,
+        ),
         granted_list: resolve_fs_allowlist(&opts.allow_read),
+        blocked_list: resolve_fs_allowlist(&opts.deny_read),
         ..Default::default()
       },
       write: UnaryPermission::<PathBuf> {
-        global_state: global_state_from_option(&opts.allow_write),
+        global_state: global_state_from_option(
+          &opts.allow_write,
+          &opts.deny_write,
+        ),
         granted_list: resolve_fs_allowlist(&opts.allow_write),
+        blocked_list: resolve_fs_allowlist(&opts.deny_write),
         ..Default::default()
       },
       net: UnaryPermission::<String> {
-        global_state: global_state_from_option(&opts.allow_net),
+        global_state: global_state_from_option(&opts.allow_net, &opts.deny_net),
         granted_list: opts
           .allow_net
           .as_ref()
           .map(|v| v.iter().cloned().collect())
           .unwrap_or_else(HashSet::new),
+        blocked_list: opts
+          .deny_net
+          .as_ref()
+          .map(|v| v.iter().cloned().collect())
+          .unwrap_or_else(HashSet::new),
         ..Default::default()
       },
-      env: global_state_from_flag_bool(opts.allow_env),
-      run: global_state_from_flag_bool(opts.allow_run),
-      plugin: global_state_from_flag_bool(opts.allow_plugin),
-      hrtime: global_state_from_flag_bool(opts.allow_hrtime),
+      env: global_state_from_flag_bool(opts.allow_env, opts.deny_env),
+      run: global_state_from_flag_bool(opts.allow_run, opts.deny_run),
+      plugin: global_state_from_flag_bool(opts.allow_plugin, opts.deny_plugin),
+      hrtime: global_state_from_flag_bool(opts.allow_hrtime, opts.deny_hrtime),
+      origin_scopes: opts
+        .scopes
+        .iter()
+        .map(|(prefix, scope_opts)| OriginScope {
+          prefix: prefix.clone(),
+          permissions: Permissions::from_options(scope_opts),
+        })
+        .collect(),
+      creator_scopes: vec![],
     }
   }
 
+  /// Returns whether some permissions only apply to particular modules, in
+  /// which case the runtime needs to capture the callers of ops.
+  pub fn has_origin_scopes(&self) -> bool {
+    !self.origin_scopes.is_empty()
+  }
+
   /// Arbitrary helper. Resolves the path from CWD, and also gets a path that
   /// can be displayed without leaking the CWD when not allowed.
   fn resolved_and_display_path(&self, path: &Path) -> (PathBuf, PathBuf) {
@@ -179,11 +319,19 @@ impl Permissions {
       run: PermissionState::Granted,
       plugin: PermissionState::Granted,
       hrtime: PermissionState::Granted,
+      origin_scopes: vec![],
+      creator_scopes: vec![],
     }
   }
 
   pub fn query_read(&self, path: &Option<&Path>) -> PermissionState {
     let path = path.map(|p| resolve_from_cwd(p).unwrap());
+    if match path.as_ref() {
+      None => !self.read.blocked_list.is_empty(),
+      Some(path) => check_path_allowlist(path, &self.read.blocked_list),
+    } {
+      return PermissionState::Denied;
+    }
     if self.read.global_state == PermissionState::Denied
       && match path.as_ref() {
         None => true,
@@ -205,6 +353,12 @@ impl Permissions {
 
   pub fn query_write(&self, path: &Option<&Path>) -> PermissionState {
     let path = path.map(|p| resolve_from_cwd(p).unwrap());
+    if match path.as_ref() {
+      None => !self.write.blocked_list.is_empty(),
+      Some(path) => check_path_allowlist(path, &self.write.blocked_list),
+    } {
+      return PermissionState::Denied;
+    }
     if self.write.global_state == PermissionState::Denied
       && match path.as_ref() {
         None => true,
@@ -228,6 +382,12 @@ impl Permissions {
     &self,
     host: &Option<&(T, Option<u16>)>,
   ) -> PermissionState {
+    if match host.as_ref() {
+      None => !self.net.blocked_list.is_empty(),
+      Some(host) => check_host_allowlist(host, &self.net.blocked_list),
+    } {
+      return PermissionState::Denied;
+    }
     if self.net.global_state == PermissionState::Denied
       && match host.as_ref() {
         None => true,
@@ -268,33 +428,16 @@ impl Permissions {
       let (resolved_path, display_path) = self.resolved_and_display_path(path);
       let state = self.query_read(&Some(&resolved_path));
       if state == PermissionState::Prompt {
-        if permission_prompt(&format!(
-          "Deno requests read access to \"{}\"",
-          display_path.display()
-        )) {
-          self
-            .read
-            .granted_list
-            .retain(|path| !path.starts_with(&resolved_path));
-          self.read.granted_list.insert(resolved_path);
-          return PermissionState::Granted;
-        } else {
-          self
-            .read
-            .denied_list
-            .retain(|path| !resolved_path.starts_with(path));
-          self.read.denied_list.insert(resolved_path);
-          self.read.global_state = PermissionState::Denied;
-          return PermissionState::Denied;
-        }
+        return self.read.request_path(resolved_path, &display_path, "read");
       }
       state
     } else {
       let state = self.query_read(&None);
       if state == PermissionState::Prompt {
-        if permission_prompt("Deno requests read access") {
-          self.read.granted_list.clear();
-          self.read.global_state = PermissionState::Granted;
+        if permission_prompt("Deno requests read access", None, None)
+          != PromptResponse::Deny
+        {
+          self.read.grant_all();
           return PermissionState::Granted;
         } else {
           self.read.global_state = PermissionState::Denied;
@@ -310,33 +453,18 @@ impl Permissions {
       let (resolved_path, display_path) = self.resolved_and_display_path(path);
       let state = self.query_write(&Some(&resolved_path));
       if state == PermissionState::Prompt {
-        if permission_prompt(&format!(
-          "Deno requests write access to \"{}\"",
-          display_path.display()
-        )) {
-          self
-            .write
-            .granted_list
-            .retain(|path| !path.starts_with(&resolved_path));
-          self.write.granted_list.insert(resolved_path);
-          return PermissionState::Granted;
-        } else {
-          self
-            .write
-            .denied_list
-            .retain(|path| !resolved_path.starts_with(path));
-          self.write.denied_list.insert(resolved_path);
-          self.write.global_state = PermissionState::Denied;
-          return PermissionState::Denied;
-        }
+        return self
+          .write
+          .request_path(resolved_path, &display_path, "write");
       }
       state
     } else {
       let state = self.query_write(&None);
       if state == PermissionState::Prompt {
-        if permission_prompt("Deno requests write access") {
-          self.write.granted_list.clear();
-          self.write.global_state = PermissionState::Granted;
+        if permission_prompt("Deno requests write access", None, None)
+          != PromptResponse::Deny
+        {
+          self.write.grant_all();
           return PermissionState::Granted;
         } else {
           self.write.global_state = PermissionState::Denied;
@@ -355,32 +483,47 @@ impl Permissions {
       let state = self.query_net(&Some(host));
       if state == PermissionState::Prompt {
         let host_string = format_host(host);
-        if permission_prompt(&format!(
-          "Deno requests network access to \"{}\"",
-          host_string,
-        )) {
-          if host.1.is_none() {
+        let scope = host
+          .1
+          .map(|_| format!("\"{}\" on any port", host.0.as_ref()));
+        let response = permission_prompt(
+          &format!("Deno requests network access to \"{}\"", host_string),
+          scope.as_deref(),
+          Some("all network access"),
+        );
+        match response {
+          PromptResponse::Allow if host.1.is_some() => {
+            self.net.granted_list.insert(host_string);
+          }
+          PromptResponse::Allow | PromptResponse::AllowScope => {
             self
               .net
               .granted_list
               .retain(|h| !h.starts_with(&format!("{}:", host.0.as_ref())));
+            self.net.granted_list.insert(host.0.as_ref().to_string());
           }
-          self.net.granted_list.insert(host_string);
-          return PermissionState::Granted;
-        } else {
-          if host.1.is_some() {
-            self.net.denied_list.remove(host.0.as_ref());
+          PromptResponse::AllowAll => {
+            self.net.granted_list.clear();
+            self.net.global_state = PermissionState::Granted;
+          }
+          PromptResponse::Deny => {
+            if host.1.is_some() {
+              self.net.denied_list.remove(host.0.as_ref());
+            }
+            self.net.denied_list.insert(host_string);
+            self.net.global_state = PermissionState::Denied;
+            return PermissionState::Denied;
           }
-          self.net.denied_list.insert(host_string);
-          self.net.global_state = PermissionState::Denied;
-          return PermissionState::Denied;
         }
+        return PermissionState::Granted;
       }
       state
     } else {
       let state = self.query_net::<&str>(&None);
       if state == PermissionState::Prompt {
-        if permission_prompt("Deno requests network access") {
+        if permission_prompt("Deno requests network access", None, None)
+          != PromptResponse::Deny
+        {
           self.net.granted_list.clear();
           self.net.global_state = PermissionState::Granted;
           return PermissionState::Granted;
@@ -395,7 +538,12 @@ impl Permissions {
 
   pub fn request_env(&mut self) -> PermissionState {
     if self.env == PermissionState::Prompt {
-      if permission_prompt("Deno requests access to environment variables") {
+      if permission_prompt(
+        "Deno requests access to environment variables",
+        None,
+        None,
+      ) != PromptResponse::Deny
+      {
         self.env = PermissionState::Granted;
       } else {
         self.env = PermissionState::Denied;
@@ -406,7 +554,12 @@ impl Permissions {
 
   pub fn request_run(&mut self) -> PermissionState {
     if self.run == PermissionState::Prompt {
-      if permission_prompt("Deno requests to access to run a subprocess") {
+      if permission_prompt(
+        "Deno requests to access to run a subprocess",
+        None,
+        None,
+      ) != PromptResponse::Deny
+      {
         self.run = PermissionState::Granted;
       } else {
         self.run = PermissionState::Denied;
@@ -417,7 +570,9 @@ impl Permissions {
 
   pub fn request_plugin(&mut self) -> PermissionState {
     if self.plugin == PermissionState::Prompt {
-      if permission_prompt("Deno requests to open plugins") {
+      if permission_prompt("Deno requests to open plugins", None, None)
+        != PromptResponse::Deny
+      {
         self.plugin = PermissionState::Granted;
       } else {
         self.plugin = PermissionState::Denied;
@@ -428,7 +583,12 @@ impl Permissions {
 
   pub fn request_hrtime(&mut self) -> PermissionState {
     if self.hrtime == PermissionState::Prompt {
-      if permission_prompt("Deno requests access to high precision time") {
+      if permission_prompt(
+        "Deno requests access to high precision time",
+        None,
+        None,
+      ) != PromptResponse::Deny
+      {
         self.hrtime = PermissionState::Granted;
       } else {
         self.hrtime = PermissionState::Denied;
@@ -518,46 +678,70 @@ impl Permissions {
     self.hrtime
   }
 
-  pub fn check_read(&self, path: &Path) -> Result<(), AnyError> {
+  pub fn check_read(&mut self, path: &Path) -> Result<(), AnyError> {
     let (resolved_path, display_path) = self.resolved_and_display_path(path);
-    self.query_read(&Some(&resolved_path)).check(
-      &format!("read access to \"{}\"", display_path.display()),
-      "--allow-read",
-    )
+    let access = format!("read access to \"{}\"", display_path.display());
+    let mut state = self.query_read(&Some(&resolved_path));
+    if state == PermissionState::Prompt && can_prompt() {
+      state =
+        self
+          .read
+          .request_path(resolved_path.clone(), &display_path, "read");
+    }
+    state.check(&access, "--allow-read")?;
+    self.check_origin_scopes(&access, |scope| {
+      scope.query_read(&Some(&resolved_path))
+    })
   }
 
   /// As `check_read()`, but permission error messages will anonymize the path
-  /// by replacing it with the given `display`.
+  /// by replacing it with the given `display`. This never prompts, as the
+  /// prompt would show the path.
   pub fn check_read_blind(
-    &self,
+    &mut self,
     path: &Path,
     display: &str,
   ) -> Result<(), AnyError> {
     let resolved_path = resolve_from_cwd(path).unwrap();
+    let access = format!("read access to <{}>", display);
     self
       .query_read(&Some(&resolved_path))
-      .check(&format!("read access to <{}>", display), "--allow-read")
+      .check(&access, "--allow-read")?;
+    self.check_origin_scopes(&access, |scope| {
+      scope.query_read(&Some(&resolved_path))
+    })
   }
 
-  pub fn check_write(&self, path: &Path) -> Result<(), AnyError> {
+  pub fn check_write(&mut self, path: &Path) -> Result<(), AnyError> {
     let (resolved_path, display_path) = self.resolved_and_display_path(path);
-    self.query_write(&Some(&resolved_path)).check(
-      &format!("write access to \"{}\"", display_path.display()),
-      "--allow-write",
-    )
+    let access = format!("write access to \"{}\"", display_path.display());
+    let mut state = self.query_write(&Some(&resolved_path));
+    if state == PermissionState::Prompt && can_prompt() {
+      state =
+        self
+          .write
+          .request_path(resolved_path.clone(), &display_path, "write");
+    }
+    state.check(&access, "--allow-write")?;
+    self.check_origin_scopes(&access, |scope| {
+      scope.query_write(&Some(&resolved_path))
+    })
   }
 
   pub fn check_net<T: AsRef<str>>(
-    &self,
+    &mut self,
     host: &(T, Option<u16>),
   ) -> Result<(), AnyError> {
-    self.query_net(&Some(host)).check(
-      &format!("network access to \"{}\"", format_host(host)),
-      "--allow-net",
-    )
+    let access = self.net_access_message(host.0.as_ref(), &format_host(host));
+    let mut state = self.query_net(&Some(host));
+    if state == PermissionState::Prompt && can_prompt() {
+      state = self.request_net(&Some(host));
+    }
+    state.check(&access, "--allow-net")?;
+    self.check_origin_scopes(&access, |scope| scope.query_net(&Some(host)))
   }
 
-  pub fn check_net_url(&self, url: &url::Url) -> Result<(), AnyError> {
+  pub fn check_net_url(&mut self, url: &url::Url) -> Result<(), AnyError> {
     let hostname = url
       .host_str()
       .ok_or_else(|| uri_error("Missing host"))?
@@ -566,18 +750,40 @@ impl Permissions {
       None => hostname.clone(),
       Some(port) => format!("{}:{}", hostname, port),
     };
-    self
-      .query_net(&Some(&(hostname, url.port_or_known_default())))
-      .check(
-        &format!("network access to \"{}\"", display_host),
-        "--allow-net",
+    let host = (&hostname, url.port_or_known_default());
+    let access = self.net_access_message(&hostname, &display_host);
+    let mut state = self.query_net(&Some(&host));
+    if state == PermissionState::Prompt && can_prompt() {
+      state = self.request_net(&Some(&host));
+    }
+    state.check(&access, "--allow-net")?;
+    self.check_origin_scopes(&access, |scope| scope.query_net(&Some(&host)))
+  }
+
+  /// Builds the message used for network permission errors. CIDR entries are
+  /// matched against IP literals only: hostnames are checked before they are
+  /// resolved, so a name that resolves into an allowed range is still denied.
+  fn net_access_message(&self, hostname: &str, display_host: &str) -> String {
+    let has_cidr = self
+      .net
+      .granted_list
+      .iter()
+      .any(|entry| entry.parse::<IpNet>().is_ok());
+    if has_cidr && parse_ip_host(hostname).is_none() {
+      format!(
+        "network access to \"{}\" (CIDR ranges only match IP addresses, \
+        hostnames are not resolved before the check)",
+        display_host
       )
+    } else {
+      format!("network access to \"{}\"", display_host)
+    }
   }
 
   /// A helper function that determines if the module specifier is a local or
   /// remote, and performs a read or net check for the specifier.
   pub fn check_specifier(
-    &self,
+    &mut self,
     specifier: &ModuleSpecifier,
   ) -> Result<(), AnyError> {
     let url = specifier.as_url();
@@ -589,63 +795,309 @@ impl Permissions {
           specifier
         ))),
       },
-      "data" => Ok(()),
+      "data" | "blob" => Ok(()),
       _ => self.check_net_url(url),
     }
   }
 
-  pub fn check_env(&self) -> Result<(), AnyError> {
+  pub fn check_env(&mut self) -> Result<(), AnyError> {
+    let access = "access to environment variables";
+    let mut state = self.env;
+    if state == PermissionState::Prompt && can_prompt() {
+      state = self.request_env();
+    }
+    state.check(access, "--allow-env")?;
+    self.check_origin_scopes(access, |scope| scope.env)
+  }
+
+  pub fn check_run(&mut self) -> Result<(), AnyError> {
+    let access = "access to run a subprocess";
+    let mut state = self.run;
+    if state == PermissionState::Prompt && can_prompt() {
+      state = self.request_run();
+    }
+    state.check(access, "--allow-run")?;
+    self.check_origin_scopes(access, |scope| scope.run)
+  }
+
+  pub fn check_plugin(&mut self, path: &Path) -> Result<(), AnyError> {
+    let (_, display_path) = self.resolved_and_display_path(path);
+    let access = format!("access to open a plugin: {}", display_path.display());
+    let mut state = self.plugin;
+    if state == PermissionState::Prompt && can_prompt() {
+      state = self.request_plugin();
+    }
+    state.check(&access, "--allow-plugin")?;
+    self.check_origin_scopes(&access, |scope| scope.plugin)
+  }
+
+  pub fn check_hrtime(&mut self) -> Result<(), AnyError> {
+    let access = "access to high precision time";
+    let mut state = self.hrtime;
+    if state == PermissionState::Prompt && can_prompt() {
+      state = self.request_hrtime();
+    }
+    state.check(access, "--allow-hrtime")?;
+    self.check_origin_scopes(access, |scope| scope.hrtime)
+  }
+
+  /// Checks an access that the process wide permissions allow against the
+  /// scopes of the modules that called the current op.
+  fn check_origin_scopes(
+    &self,
+    access: &str,
+    query: impl Fn(&Permissions) -> PermissionState,
+  ) -> Result<(), AnyError> {
+    if self.origin_scopes.is_empty() && self.creator_scopes.is_empty() {
+      return Ok(());
+    }
+    match op_callers() {
+      Some(callers) => self.check_origin_scopes_for(&callers, access, query),
+      // Outside of ops, e.g. while loading modules, there are no callers.
+      None => self.check_creator_scopes(access, query),
+    }
+  }
+
+  /// Every frame must be allowed by the scope of its module, so a scoped
+  /// module can't gain access by calling into code that isn't scoped. The
+  /// error names the innermost frame that isn't allowed.
+  ///
+  /// When no frame can be attributed to a module, e.g. because the op's
+  /// binding was passed to `setTimeout()` directly, the access is denied, as
+  /// it might have been arranged by a scoped module.
+  fn check_origin_scopes_for(
+    &self,
+    frames: &[JsStackFrame],
+    access: &str,
+    query: impl Fn(&Permissions) -> PermissionState,
+  ) -> Result<(), AnyError> {
+    self.check_creator_scopes(access, &query)?;
+    if frames.is_empty() && !self.origin_scopes.is_empty() {
+      let message = format!(
+        "{} from code that can't be attributed to a module, which the \
+        scopes in the permissions manifest don't allow",
+        access
+      );
+      return Err(custom_error("PermissionDenied", message));
+    }
+    for frame in frames {
+      let file_name = match &frame.file_name {
+        Some(file_name) => file_name,
+        None => continue,
+      };
+      let scope = match self.origin_scope(file_name) {
+        Some(scope) => scope,
+        None => continue,
+      };
+      if query(&scope.permissions) != PermissionState::Granted {
+        let message = format!(
+          "{} from \"{}:{}:{}\", the \"{}\" scope in the permissions \
+          manifest doesn't allow it",
+          access,
+          file_name,
+          frame.line_number.unwrap_or(0),
+          frame.column_number.unwrap_or(0),
+          scope.prefix
+        );
+        return Err(custom_error("PermissionDenied", message));
+      }
+    }
+    Ok(())
+  }
+
+  /// Checks an access against the scopes of the code that created a worker,
+  /// which apply to all code.
+  fn check_creator_scopes(
+    &self,
+    access: &str,
+    query: impl Fn(&Permissions) -> PermissionState,
+  ) -> Result<(), AnyError> {
+    for scope in &self.creator_scopes {
+      if query(&scope.permissions) != PermissionState::Granted {
+        let message = format!(
+          "{} from a worker created by code of the \"{}\" scope in the \
+          permissions manifest, which doesn't allow it",
+          access, scope.prefix
+        );
+        return Err(custom_error("PermissionDenied", message));
+      }
+    }
+    Ok(())
+  }
+
+  /// Returns the scopes that apply to a worker created by the current op's
+  /// callers: those of the callers' modules, and those applying to all code.
+  /// Like other accesses, creating a worker is denied when no caller can be
+  /// attributed to a module.
+  pub fn worker_creator_scopes(&self) -> Result<Vec<OriginScope>, AnyError> {
+    let mut scopes = self.creator_scopes.clone();
+    if self.origin_scopes.is_empty() {
+      return Ok(scopes);
+    }
+    let callers = op_callers().unwrap_or_default();
+    if callers.is_empty() {
+      return Err(custom_error(
+        "PermissionDenied",
+        "Can't create a worker from code that can't be attributed to a \
+        module, which the scopes in the permissions manifest don't allow",
+      ));
+    }
+    for frame in callers.iter() {
+      let scope = frame
+        .file_name
+        .as_deref()
+        .and_then(|file_name| self.origin_scope(file_name));
+      if let Some(scope) = scope {
+        if !scopes.contains(scope) {
+          scopes.push(scope.clone());
+        }
+      }
+    }
+    Ok(scopes)
+  }
+
+  /// Returns the scope with the longest prefix matching `specifier`.
+  fn origin_scope(&self, specifier: &str) -> Option<&OriginScope> {
     self
-      .env
-      .check("access to environment variables", "--allow-env")
+      .origin_scopes
+      .iter()
+      .filter(|scope| specifier.starts_with(&scope.prefix))
+      .max_by_key(|scope| scope.prefix.len())
+  }
+}
+
+/// The permissions of a worker, as kept in its `OpState`. Clones share the
+/// same permissions, so that the module loader, which checks them on other
+/// tasks than the ops, keeps what is granted at its prompts.
+#[derive(Clone, Debug, Default)]
+pub struct PermissionsContainer(Arc<Mutex<Permissions>>);
+
+impl PermissionsContainer {
+  pub fn new(permissions: Permissions) -> Self {
+    Self(Arc::new(Mutex::new(permissions)))
+  }
+
+  pub fn allow_all() -> Self {
+    Self::new(Permissions::allow_all())
+  }
+
+  /// Locks the permissions, e.g. to query, request or revoke them. The lock
+  /// must not be held across an `await`.
+  pub fn lock(&self) -> MutexGuard<Permissions> {
+    self.0.lock().unwrap()
+  }
+
+  pub fn check_read(&self, path: &Path) -> Result<(), AnyError> {
+    self.lock().check_read(path)
+  }
+
+  pub fn check_read_blind(
+    &self,
+    path: &Path,
+    display: &str,
+  ) -> Result<(), AnyError> {
+    self.lock().check_read_blind(path, display)
+  }
+
+  pub fn check_write(&self, path: &Path) -> Result<(), AnyError> {
+    self.lock().check_write(path)
+  }
+
+  pub fn check_net<T: AsRef<str>>(
+    &self,
+    host: &(T, Option<u16>),
+  ) -> Result<(), AnyError> {
+    self.lock().check_net(host)
+  }
+
+  pub fn check_net_url(&self, url: &url::Url) -> Result<(), AnyError> {
+    self.lock().check_net_url(url)
+  }
+
+  pub fn check_specifier(
+    &self,
+    specifier: &ModuleSpecifier,
+  ) -> Result<(), AnyError> {
+    self.lock().check_specifier(specifier)
+  }
+
+  pub fn check_env(&self) -> Result<(), AnyError> {
+    self.lock().check_env()
   }
 
   pub fn check_run(&self) -> Result<(), AnyError> {
-    self.run.check("access to run a subprocess", "--allow-run")
+    self.lock().check_run()
   }
 
   pub fn check_plugin(&self, path: &Path) -> Result<(), AnyError> {
-    let (_, display_path) = self.resolved_and_display_path(path);
-    self.plugin.check(
-      &format!("access to open a plugin: {}", display_path.display()),
-      "--allow-plugin",
-    )
+    self.lock().check_plugin(path)
   }
 
   pub fn check_hrtime(&self) -> Result<(), AnyError> {
-    self
-      .hrtime
-      .check("access to high precision time", "--allow-hrtime")
+    self.lock().check_hrtime()
   }
 }
 
-impl deno_fetch::FetchPermissions for Permissions {
+impl deno_fetch::FetchPermissions for PermissionsContainer {
   fn check_net_url(&self, url: &url::Url) -> Result<(), AnyError> {
-    Permissions::check_net_url(self, url)
+    PermissionsContainer::check_net_url(self, url)
   }
 
   fn check_read(&self, p: &PathBuf) -> Result<(), AnyError> {
-    Permissions::check_read(self, p)
+    PermissionsContainer::check_read(self, p)
+  }
+
+  fn check_write(&self, p: &PathBuf) -> Result<(), AnyError> {
+    PermissionsContainer::check_write(self, p)
   }
 }
 
-impl deno_websocket::WebSocketPermissions for Permissions {
+impl deno_websocket::WebSocketPermissions for PermissionsContainer {
   fn check_net_url(&self, url: &url::Url) -> Result<(), AnyError> {
-    Permissions::check_net_url(self, url)
+    PermissionsContainer::check_net_url(self, url)
   }
 }
 
+/// Answer given by the user to a permission prompt.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum PromptResponse {
+  /// Deny the request.
+  Deny,
+  /// Grant exactly what was requested.
+  Allow,
+  /// Grant the wider scope offered by the prompt, e.g. the directory
+  /// containing the requested path or every port of the requested host.
+  AllowScope,
+  /// Grant every request of this permission kind.
+  AllowAll,
+}
+
 /// Shows the permission prompt and returns the answer according to the user input.
 /// This loops until the user gives the proper input.
+///
+/// `scope` and `all` describe the optional wider grants offered next to
+/// "grant" and "deny"; when they are `None` the choice isn't shown.
 #[cfg(not(test))]
-fn permission_prompt(message: &str) -> bool {
+fn permission_prompt(
+  message: &str,
+  scope: Option<&str>,
+  all: Option<&str>,
+) -> PromptResponse {
   if !atty::is(atty::Stream::Stdin) || !atty::is(atty::Stream::Stderr) {
-    return false;
+    return PromptResponse::Deny;
   };
-  let msg = format!(
-    "️{}  {}. Grant? [g/d (g = grant, d = deny)] ",
-    PERMISSION_EMOJI, message
-  );
+  let mut keys = vec!["g", "d"];
+  let mut descriptions = vec!["g = grant".to_string(), "d = deny".to_string()];
+  if let Some(scope) = scope {
+    keys.push("s");
+    descriptions.push(format!("s = grant {}", scope));
+  }
+  if let Some(all) = all {
+    keys.push("a");
+    descriptions.push(format!("a = grant {}", all));
+  }
+  let options = format!("[{} ({})]", keys.join("/"), descriptions.join(", "));
+  let msg = format!("️{}  {}. Grant? {} ", PERMISSION_EMOJI, message, options);
   // print to stderr so that if deno is > to a file this is still displayed.
   eprint!("{}", colors::bold(&msg));
   loop {
@@ -653,41 +1105,82 @@ fn permission_prompt(message: &str) -> bool {
     let stdin = io::stdin();
     let result = stdin.read_line(&mut input);
     if result.is_err() {
-      return false;
+      return PromptResponse::Deny;
+    };
+    let ch = match input.chars().next() {
+      None => return PromptResponse::Deny,
+      Some(ch) => ch,
     };
-    let ch = input.chars().next().unwrap();
     match ch.to_ascii_lowercase() {
-      'g' => return true,
-      'd' => return false,
+      'g' => return PromptResponse::Allow,
+      'd' => return PromptResponse::Deny,
+      's' if scope.is_some() => return PromptResponse::AllowScope,
+      'a' if all.is_some() => return PromptResponse::AllowAll,
       _ => {
         // If we don't get a recognized option try again.
-        let msg_again =
-          format!("Unrecognized option '{}' [g/d (g = grant, d = deny)] ", ch);
+        let msg_again = format!("Unrecognized option '{}' {} ", ch, options);
         eprint!("{}", colors::bold(&msg_again));
       }
     };
   }
 }
 
+/// Returns whether the user can answer a permission prompt, in which case
+/// `check_*` prompt for accesses that aren't permitted yet instead of
+/// failing.
+#[cfg(not(test))]
+fn can_prompt() -> bool {
+  atty::is(atty::Stream::Stdin) && atty::is(atty::Stream::Stderr)
+}
+
 #[cfg(test)]
 lazy_static! {
   /// Lock this when you use `set_prompt_result` in a test case.
   static ref PERMISSION_PROMPT_GUARD: Mutex<()> = Mutex::new(());
+  static ref STUB_PROMPT_VALUE: Mutex<PromptResponse> =
+    Mutex::new(PromptResponse::Allow);
+  static ref STUB_CAN_PROMPT: Mutex<bool> = Mutex::new(false);
 }
 
+// When testing, `check_*` only prompt when a test asks for it.
 #[cfg(test)]
-static STUB_PROMPT_VALUE: AtomicBool = AtomicBool::new(true);
+fn can_prompt() -> bool {
+  *STUB_CAN_PROMPT.lock().unwrap()
+}
 
 #[cfg(test)]
 fn set_prompt_result(value: bool) {
-  STUB_PROMPT_VALUE.store(value, Ordering::SeqCst);
+  set_prompt_response(if value {
+    PromptResponse::Allow
+  } else {
+    PromptResponse::Deny
+  });
+}
+
+#[cfg(test)]
+fn set_prompt_response(value: PromptResponse) {
+  *STUB_PROMPT_VALUE.lock().unwrap() = value;
+}
+
+#[cfg(test)]
+fn set_can_prompt(value: bool) {
+  *STUB_CAN_PROMPT.lock().unwrap() = value;
 }
 
 // When testing, permission prompt returns the value of STUB_PROMPT_VALUE
-// which we set from the test functions.
+// which we set from the test functions. Wider grants that the prompt doesn't
+// offer fall back to a plain grant, as the user couldn't have chosen them.
 #[cfg(test)]
-fn permission_prompt(_message: &str) -> bool {
-  STUB_PROMPT_VALUE.load(Ordering::SeqCst)
+fn permission_prompt(
+  _message: &str,
+  scope: Option<&str>,
+  all: Option<&str>,
+) -> PromptResponse {
+  match *STUB_PROMPT_VALUE.lock().unwrap() {
+    PromptResponse::AllowScope if scope.is_none() => PromptResponse::Allow,
+    PromptResponse::AllowAll if all.is_none() => PromptResponse::Allow,
+    value => value,
+  }
 }
 
 fn log_perm_access(message: &str) {
@@ -722,6 +1215,9 @@ fn check_host_allowlist<T: AsRef<str>>(
   let (hostname, port) = host;
   allowlist.contains(hostname.as_ref())
     || (port.is_some() && allowlist.contains(&format_host(host)))
+    || allowlist
+      .iter()
+      .any(|entry| check_net_pattern(entry, hostname.as_ref(), *port, false))
 }
 
 fn check_host_blocklist<T: AsRef<str>>(
@@ -729,13 +1225,71 @@ fn check_host_blocklist<T: AsRef<str>>(
   blocklist: &HashSet<String>,
 ) -> bool {
   let (hostname, port) = host;
-  match port {
+  let exact = match port {
     None => blocklist.iter().any(|host| {
       host == hostname.as_ref()
         || host.starts_with(&format!("{}:", hostname.as_ref()))
     }),
     Some(_) => blocklist.contains(&format_host(host)),
+  };
+  exact
+    || blocklist
+      .iter()
+      .any(|entry| check_net_pattern(entry, hostname.as_ref(), *port, true))
+}
+
+/// Parses a hostname as an IP address, accepting the bracketed form that
+/// `Url::host_str()` returns for IPv6 addresses.
+fn parse_ip_host(hostname: &str) -> Option<IpAddr> {
+  let hostname = hostname
+    .strip_prefix('[')
+    .and_then(|h| h.strip_suffix(']'))
+    .unwrap_or(hostname);
+  hostname.parse::<IpAddr>().ok()
+}
+
+/// Matches a host against a CIDR block (eg. "10.0.0.0/8") or a leading
+/// wildcard hostname (eg. "*.deno.land" or "*.deno.land:443"). Exact entries
+/// never match here and are handled by the callers.
+///
+/// CIDR blocks cover every port and only match IP literals. A wildcard
+/// matches any subdomain but not the domain itself. When `any_port` is set, a
+/// host without a port matches wildcard entries that name a port, which is
+/// how the blocklist treats exact entries as well.
+fn check_net_pattern(
+  entry: &str,
+  hostname: &str,
+  port: Option<u16>,
+  any_port: bool,
+) -> bool {
+  if let Ok(net) = entry.parse::<IpNet>() {
+    return match parse_ip_host(hostname) {
+      Some(ip) => net.contains(&ip),
+      None => false,
+    };
   }
+  let pattern = match entry.strip_prefix("*.") {
+    Some(pattern) => pattern,
+    None => return false,
+  };
+  let (domain, entry_port) = match pattern.rfind(':') {
+    Some(index) => match pattern[index + 1..].parse::<u16>() {
+      Ok(entry_port) => (&pattern[..index], Some(entry_port)),
+      Err(_) => return false,
+    },
+    None => (pattern, None),
+  };
+  let port_matches = match (entry_port, port) {
+    (None, _) => true,
+    (Some(_), None) => any_port,
+    (Some(entry_port), Some(port)) => entry_port == port,
+  };
+  let hostname = hostname.to_ascii_lowercase();
+  let domain = domain.to_ascii_lowercase();
+  port_matches
+    && parse_ip_host(&hostname).is_none()
+    && hostname.len() > domain.len()
+    && hostname.ends_with(&format!(".{}", domain))
 }
 
 fn format_host<T: AsRef<str>>(host: &(T, Option<u16>)) -> String {
@@ -764,7 +1318,7 @@ mod tests {
       PathBuf::from("/b/c"),
     ];
 
-    let perms = Permissions::from_options(&PermissionsOptions {
+    let mut perms = Permissions::from_options(&PermissionsOptions {
       allow_read: Some(allowlist.clone()),
       allow_write: Some(allowlist),
       ..Default::default()
@@ -819,7 +1373,7 @@ mod tests {
 
   #[test]
   fn test_check_net() {
-    let perms = Permissions::from_options(&PermissionsOptions {
+    let mut perms = Permissions::from_options(&PermissionsOptions {
       allow_net: Some(svec![
         "localhost",
         "deno.land",
@@ -903,6 +1457,218 @@ mod tests {
     }
   }
 
+  #[test]
+  fn test_check_net_patterns() {
+    let mut perms = Permissions::from_options(&PermissionsOptions {
+      allow_net: Some(svec![
+        "10.0.0.0/8",
+        "fd00::/8",
+        "*.corp.example",
+        "*.deno.land:443"
+      ]),
+      ..Default::default()
+    });
+
+    let domain_tests = vec![
+      ("10.0.0.1", 0, true),
+      ("10.255.255.255", 8080, true),
+      ("11.0.0.1", 80, false),
+      ("fd00::1", 443, true),
+      ("[fd12:3456::1]", 443, true),
+      ("fe80::1", 443, false),
+      ("db.corp.example", 5432, true),
+      ("a.b.corp.example", 80, true),
+      ("DB.Corp.Example", 80, true),
+      ("corp.example", 80, false),
+      ("evilcorp.example", 80, false),
+      ("x.deno.land", 443, true),
+      ("x.deno.land", 80, false),
+      ("deno.land", 443, false),
+    ];
+
+    let url_tests = vec![
+      ("https://10.1.2.3/", true),
+      ("http://10.1.2.3:8000/", true),
+      ("https://[fd00::1]/", true),
+      ("https://[::1]/", false),
+      ("https://api.corp.example/v1", true),
+      ("https://x.deno.land/std/mod.ts", true),
+      ("http://x.deno.land/std/mod.ts", false),
+    ];
+
+    for (hostname, port, is_ok) in domain_tests.iter() {
+      assert_eq!(*is_ok, perms.check_net(&(hostname, Some(*port))).is_ok());
+    }
+
+    for (url_str, is_ok) in url_tests.iter() {
+      let u = url::Url::parse(url_str).unwrap();
+      assert_eq!(*is_ok, perms.check_net_url(&u).is_ok());
+    }
+
+    // Hostnames are never resolved when matching CIDR ranges.
+    let err = perms.check_net(&("localhost", Some(80))).unwrap_err();
+    assert!(err.to_string().contains("hostnames are not resolved"));
+  }
+
+  #[test]
+  fn test_net_pattern_blocklist() {
+    let mut perms = Permissions::from_options(&PermissionsOptions {
+      allow_net: Some(svec![]),
+      ..Default::default()
+    });
+    perms.net.global_state = PermissionState::Denied;
+    perms.net.denied_list =
+      svec!["192.168.0.0/16", "*.ads.example:443"].into_iter().collect();
+
+    assert!(perms.check_net(&("192.168.1.1", Some(80))).is_err());
+    assert!(perms.check_net(&("x.ads.example", Some(443))).is_err());
+    assert_eq!(
+      perms.query_net(&Some(&("x.ads.example", None))),
+      PermissionState::Denied
+    );
+    assert_eq!(
+      perms.query_net(&Some(&("x.ads.example", Some(80)))),
+      PermissionState::Prompt
+    );
+  }
+
+  #[test]
+  fn test_deny_options() {
+    let mut perms = Permissions::from_options(&PermissionsOptions {
+      allow_read: Some(vec![]),
+      deny_read: Some(vec![PathBuf::from("/etc")]),
+      allow_write: Some(vec![PathBuf::from("/tmp")]),
+      deny_write: Some(vec![PathBuf::from("/tmp/locked")]),
+      allow_net: Some(svec!["*.deno.land", "github.com"]),
+      deny_net: Some(svec!["evil.deno.land", "github.com:22"]),
+      allow_env: true,
+      deny_env: true,
+      allow_run: true,
+      deny_plugin: true,
+      ..Default::default()
+    });
+
+    assert!(perms.check_read(Path::new("/usr/lib")).is_ok());
+    assert!(perms.check_read(Path::new("/etc")).is_err());
+    assert!(perms.check_read(Path::new("/etc/passwd")).is_err());
+    assert_eq!(perms.query_read(&None), PermissionState::Denied);
+    assert!(perms.check_write(Path::new("/tmp/foo")).is_ok());
+    assert!(perms.check_write(Path::new("/tmp/locked/foo")).is_err());
+    assert!(perms.check_net(&("x.deno.land", Some(443))).is_ok());
+    assert!(perms.check_net(&("evil.deno.land", Some(443))).is_err());
+    assert!(perms.check_net(&("github.com", Some(443))).is_ok());
+    assert!(perms.check_net(&("github.com", Some(22))).is_err());
+    assert!(perms.check_env().is_err());
+    assert!(perms.check_run().is_ok());
+    assert_eq!(perms.query_plugin(), PermissionState::Denied);
+  }
+
+  #[test]
+  fn test_origin_scopes() {
+    let mut scopes = BTreeMap::new();
+    scopes.insert(
+      "https://deno.land/x/sqlite/".to_string(),
+      PermissionsOptions {
+        allow_read: Some(vec![PathBuf::from("/db")]),
+        ..Default::default()
+      },
+    );
+    scopes.insert(
+      "https://deno.land/x/sqlite/net/".to_string(),
+      PermissionsOptions {
+        allow_net: Some(vec![]),
+        ..Default::default()
+      },
+    );
+    let mut perms = Permissions::from_options(&PermissionsOptions {
+      allow_read: Some(vec![]),
+      allow_net: Some(vec![]),
+      scopes,
+      ..Default::default()
+    });
+    assert!(perms.has_origin_scopes());
+
+    let frame = |file_name: &str| {
+      JsStackFrame::from_location(Some(file_name.to_string()), Some(1), Some(5))
+    };
+    let read_db =
+      |scope: &Permissions| scope.query_read(&Some(Path::new("/db/a.sqlite")));
+    let read_etc =
+      |scope: &Permissions| scope.query_read(&Some(Path::new("/etc/passwd")));
+    let net =
+      |scope: &Permissions| scope.query_net(&Some(&("deno.land", None)));
+
+    let main = vec![frame("file:///main.ts")];
+    assert!(perms.check_origin_scopes_for(&main, "", read_etc).is_ok());
+
+    let sqlite = vec![
+      frame("https://deno.land/x/sqlite/mod.ts"),
+      frame("file:///main.ts"),
+    ];
+    assert!(perms.check_origin_scopes_for(&sqlite, "", read_db).is_ok());
+    assert!(perms.check_origin_scopes_for(&sqlite, "", net).is_err());
+    let err = perms
+      .check_origin_scopes_for(&sqlite, "read access", read_etc)
+      .unwrap_err()
+      .to_string();
+    assert!(err.starts_with(
+      "read access from \"https://deno.land/x/sqlite/mod.ts:1:5\", the \
+      \"https://deno.land/x/sqlite/\" scope"
+    ));
+
+    // Only the longest matching prefix applies.
+    let sqlite_net = vec![frame("https://deno.land/x/sqlite/net/conn.ts")];
+    assert!(perms.check_origin_scopes_for(&sqlite_net, "", net).is_ok());
+    assert!(perms
+      .check_origin_scopes_for(&sqlite_net, "", read_db)
+      .is_err());
+
+    // Calling into code that isn't scoped doesn't escape the scope.
+    let via_std = vec![
+      frame("https://deno.land/std/fs/mod.ts"),
+      frame("https://deno.land/x/sqlite/mod.ts"),
+    ];
+    assert!(perms
+      .check_origin_scopes_for(&via_std, "", read_etc)
+      .is_err());
+
+    // Accesses that can't be attributed to a module are denied.
+    assert!(perms.check_origin_scopes_for(&[], "", read_etc).is_err());
+
+    // Outside of ops there are no callers to check.
+    assert!(perms.check_read(Path::new("/etc/passwd")).is_ok());
+    assert!(perms.worker_creator_scopes().is_err());
+
+    // The scopes of the code that created a worker apply to all of its code.
+    let mut worker = perms.clone();
+    worker.creator_scopes = vec![perms.origin_scopes[0].clone()];
+    let blob = vec![frame("blob:null/9b2cb4ba-1a34-4f3c-9a9f-3c1ad5e5a4e1")];
+    assert!(worker.check_origin_scopes_for(&blob, "", read_db).is_ok());
+    assert!(worker.check_origin_scopes_for(&blob, "", read_etc).is_err());
+    assert!(worker.check_read(Path::new("/etc/passwd")).is_err());
+  }
+
+  #[test]
+  fn test_request_blocked() {
+    let mut perms = Permissions::from_options(&PermissionsOptions {
+      deny_read: Some(vec![PathBuf::from("/secret")]),
+      ..Default::default()
+    });
+    let _guard = PERMISSION_PROMPT_GUARD.lock().unwrap();
+    set_prompt_response(PromptResponse::AllowAll);
+    assert_eq!(
+      perms.request_read(&Some(&Path::new("/secret/key"))),
+      PermissionState::Denied
+    );
+    assert_eq!(perms.request_read(&None), PermissionState::Denied);
+    assert_eq!(
+      perms.request_read(&Some(&Path::new("/public"))),
+      PermissionState::Granted
+    );
+    assert!(perms.check_read(Path::new("/public/file")).is_ok());
+    assert!(perms.check_read(Path::new("/secret/key")).is_err());
+  }
+
   #[test]
   fn check_specifiers() {
     let read_allowlist = if cfg!(target_os = "windows") {
@@ -910,7 +1676,7 @@ mod tests {
     } else {
       vec![PathBuf::from("/a")]
     };
-    let perms = Permissions::from_options(&PermissionsOptions {
+    let mut perms = Permissions::from_options(&PermissionsOptions {
       allow_read: Some(read_allowlist),
       allow_net: Some(svec!["localhost"]),
       ..Default::default()
@@ -934,6 +1700,13 @@ mod tests {
         .unwrap(),
         true,
       ),
+      (
+        ModuleSpecifier::resolve_url_or_path(
+          "blob:http://deno.land/8c5da4ac-3ef3-4a4d-9b5c-1c7a3e2a2bd4",
+        )
+        .unwrap(),
+        true,
+      ),
     ];
 
     if cfg!(target_os = "windows") {
@@ -963,7 +1736,7 @@ mod tests {
 
   #[test]
   fn check_invalid_specifiers() {
-    let perms = Permissions::allow_all();
+    let mut perms = Permissions::allow_all();
 
     let mut test_cases = vec![];
 
@@ -1023,6 +1796,8 @@ mod tests {
       run: PermissionState::Granted,
       hrtime: PermissionState::Granted,
       plugin: PermissionState::Granted,
+      origin_scopes: vec![],
+      creator_scopes: vec![],
     };
     let deserialized_perms: Permissions =
       serde_json::from_str(json_perms).unwrap();
@@ -1048,6 +1823,8 @@ mod tests {
       run: PermissionState::Granted,
       plugin: PermissionState::Granted,
       hrtime: PermissionState::Granted,
+      origin_scopes: vec![],
+      creator_scopes: vec![],
     };
     let perms2 = Permissions {
       read: UnaryPermission {
@@ -1069,6 +1846,8 @@ mod tests {
       run: PermissionState::Prompt,
       plugin: PermissionState::Prompt,
       hrtime: PermissionState::Prompt,
+      origin_scopes: vec![],
+      creator_scopes: vec![],
     };
     #[rustfmt::skip]
     {
@@ -1116,6 +1895,8 @@ mod tests {
       run: PermissionState::Prompt,
       plugin: PermissionState::Prompt,
       hrtime: PermissionState::Prompt,
+      origin_scopes: vec![],
+      creator_scopes: vec![],
     };
     #[rustfmt::skip]
     {
@@ -1153,6 +1934,87 @@ mod tests {
     };
   }
 
+  #[test]
+  fn test_request_scoped() {
+    let mut perms = Permissions::from_options(&PermissionsOptions::default());
+    #[rustfmt::skip]
+    {
+      let _guard = PERMISSION_PROMPT_GUARD.lock().unwrap();
+      set_prompt_response(PromptResponse::AllowScope);
+      assert_eq!(perms.request_read(&Some(&Path::new("/foo/bar.txt"))), PermissionState::Granted);
+      assert_eq!(perms.query_read(&Some(&Path::new("/foo/baz/qux.txt"))), PermissionState::Granted);
+      assert_eq!(perms.query_read(&Some(&Path::new("/other"))), PermissionState::Prompt);
+      set_prompt_response(PromptResponse::AllowAll);
+      assert_eq!(perms.request_write(&Some(&Path::new("/foo/bar.txt"))), PermissionState::Granted);
+      assert_eq!(perms.query_write(&None), PermissionState::Granted);
+      set_prompt_response(PromptResponse::AllowScope);
+      assert_eq!(perms.request_net(&Some(&("deno.land", Some(443)))), PermissionState::Granted);
+      assert_eq!(perms.query_net(&Some(&("deno.land", Some(8080)))), PermissionState::Granted);
+      assert_eq!(perms.query_net(&Some(&("github.com", Some(443)))), PermissionState::Prompt);
+      set_prompt_response(PromptResponse::AllowAll);
+      assert_eq!(perms.request_net(&Some(&("github.com", Some(443)))), PermissionState::Granted);
+      assert_eq!(perms.query_net::<&str>(&None), PermissionState::Granted);
+      set_prompt_response(PromptResponse::AllowAll);
+      assert_eq!(perms.request_env(), PermissionState::Granted);
+    };
+  }
+
+  #[test]
+  fn test_request_scoped_dir() {
+    let mut perms = Permissions::from_options(&PermissionsOptions::default());
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let _guard = PERMISSION_PROMPT_GUARD.lock().unwrap();
+    set_prompt_response(PromptResponse::AllowScope);
+    assert_eq!(perms.request_read(&Some(dir)), PermissionState::Granted);
+    assert_eq!(
+      perms.query_read(&Some(&dir.join("permissions.rs"))),
+      PermissionState::Granted
+    );
+    assert_eq!(
+      perms.query_read(&Some(&dir.parent().unwrap().join("Cargo.toml"))),
+      PermissionState::Prompt
+    );
+  }
+
+  #[test]
+  fn test_check_prompts() {
+    let mut perms = Permissions::from_options(&PermissionsOptions::default());
+    let _guard = PERMISSION_PROMPT_GUARD.lock().unwrap();
+    assert!(perms.check_read(Path::new("/foo/bar.txt")).is_err());
+    set_can_prompt(true);
+    set_prompt_response(PromptResponse::AllowScope);
+    assert!(perms.check_read(Path::new("/foo/bar.txt")).is_ok());
+    assert!(perms.check_read(Path::new("/foo/baz.txt")).is_ok());
+    set_prompt_response(PromptResponse::Deny);
+    assert!(perms.check_read(Path::new("/other")).is_err());
+    assert!(perms.check_read(Path::new("/foo/qux.txt")).is_ok());
+    assert!(perms.check_env().is_err());
+    assert_eq!(perms.query_env(), PermissionState::Denied);
+    set_prompt_response(PromptResponse::Allow);
+    assert!(perms.check_net(&("deno.land", Some(443))).is_ok());
+    set_prompt_response(PromptResponse::Deny);
+    assert!(perms.check_net(&("deno.land", Some(80))).is_err());
+    assert!(perms.check_net(&("deno.land", Some(443))).is_ok());
+    set_can_prompt(false);
+  }
+
+  #[test]
+  fn test_container_shares_grants() {
+    let container = PermissionsContainer::new(Permissions::from_options(
+      &PermissionsOptions::default(),
+    ));
+    let loader_permissions = container.clone();
+    let _guard = PERMISSION_PROMPT_GUARD.lock().unwrap();
+    set_can_prompt(true);
+    set_prompt_response(PromptResponse::AllowScope);
+    assert!(loader_permissions
+      .check_read(Path::new("/foo/mod.ts"))
+      .is_ok());
+    set_prompt_response(PromptResponse::Deny);
+    assert!(container.check_read(Path::new("/foo/data.json")).is_ok());
+    set_can_prompt(false);
+  }
+
   #[test]
   fn test_revoke() {
     let mut perms = Permissions {
@@ -1175,6 +2037,8 @@ mod tests {
       run: PermissionState::Granted,
       plugin: PermissionState::Prompt,
       hrtime: PermissionState::Denied,
+      origin_scopes: vec![],
+      creator_scopes: vec![],
     };
     #[rustfmt::skip]
     {
@@ -7,6 +7,7 @@ use crate::js;
 use crate::metrics::Metrics;
 use crate::ops;
 use crate::permissions::Permissions;
+use crate::permissions::PermissionsContainer;
 use crate::tokio_util::create_basic_runtime;
 use deno_core::error::AnyError;
 use deno_core::futures::channel::mpsc;
@@ -24,6 +25,7 @@ use deno_core::JsRuntime;
 use deno_core::ModuleLoader;
 use deno_core::ModuleSpecifier;
 use deno_core::RuntimeOptions;
+use deno_web::BlobUrlStore;
 use std::env;
 use std::rc::Rc;
 use std::sync::atomic::AtomicBool;
@@ -155,6 +157,7 @@ pub struct WebWorkerOptions {
   /// Sets `Deno.noColor` in JS runtime.
   pub no_color: bool,
   pub get_error_class_fn: Option<GetErrorClassFn>,
+  pub blob_url_store: BlobUrlStore,
 }
 
 impl WebWorker {
@@ -170,6 +173,7 @@ impl WebWorker {
       startup_snapshot: Some(js::deno_isolate_init()),
       js_error_create_fn: options.js_error_create_fn.clone(),
       get_error_class_fn: options.get_error_class_fn,
+      capture_op_callers: permissions.has_origin_scopes(),
       ..Default::default()
     });
 
@@ -210,7 +214,8 @@ impl WebWorker {
         let op_state = js_runtime.op_state();
         let mut op_state = op_state.borrow_mut();
         op_state.put::<Metrics>(Default::default());
-        op_state.put::<Permissions>(permissions);
+        op_state
+          .put::<PermissionsContainer>(PermissionsContainer::new(permissions));
         op_state.put::<ops::UnstableChecker>(ops::UnstableChecker {
           unstable: options.unstable,
         });
@@ -231,11 +236,7 @@ impl WebWorker {
       );
       ops::reg_json_sync(js_runtime, "op_close", deno_core::op_close);
       ops::reg_json_sync(js_runtime, "op_resources", deno_core::op_resources);
-      ops::reg_json_sync(
-        js_runtime,
-        "op_domain_to_ascii",
-        deno_web::op_domain_to_ascii,
-      );
+      ops::web::init(js_runtime, options.blob_url_store.clone());
       ops::io::init(js_runtime);
       ops::websocket::init(
         js_runtime,
@@ -499,6 +500,7 @@ mod tests {
       ts_version: "x".to_string(),
       no_color: true,
       get_error_class_fn: None,
+      blob_url_store: BlobUrlStore::default(),
     };
 
     let mut worker = WebWorker::from_options(
@@ -7,6 +7,7 @@ use crate::js;
 use crate::metrics::Metrics;
 use crate::ops;
 use crate::permissions::Permissions;
+use crate::permissions::PermissionsContainer;
 use deno_core::error::AnyError;
 use deno_core::futures::future::poll_fn;
 use deno_core::futures::future::FutureExt;
@@ -20,6 +21,7 @@ use deno_core::ModuleId;
 use deno_core::ModuleLoader;
 use deno_core::ModuleSpecifier;
 use deno_core::RuntimeOptions;
+use deno_web::BlobUrlStore;
 use std::env;
 use std::rc::Rc;
 use std::sync::Arc;
@@ -63,6 +65,7 @@ pub struct WorkerOptions {
   /// Sets `Deno.noColor` in JS runtime.
   pub no_color: bool,
   pub get_error_class_fn: Option<GetErrorClassFn>,
+  pub blob_url_store: BlobUrlStore,
   pub location: Option<Url>,
 }
 
@@ -77,6 +80,7 @@ impl MainWorker {
       startup_snapshot: Some(js::deno_isolate_init()),
       js_error_create_fn: options.js_error_create_fn.clone(),
       get_error_class_fn: options.get_error_class_fn,
+      capture_op_callers: permissions.has_origin_scopes(),
       ..Default::default()
     });
 
@@ -105,7 +109,8 @@ impl MainWorker {
         let op_state = js_runtime.op_state();
         let mut op_state = op_state.borrow_mut();
         op_state.put::<Metrics>(Default::default());
-        op_state.put::<Permissions>(permissions);
+        op_state
+          .put::<PermissionsContainer>(PermissionsContainer::new(permissions));
         op_state.put::<ops::UnstableChecker>(ops::UnstableChecker {
           unstable: options.unstable,
         });
@@ -126,11 +131,7 @@ impl MainWorker {
       ops::crypto::init(js_runtime, options.seed);
       ops::reg_json_sync(js_runtime, "op_close", deno_core::op_close);
       ops::reg_json_sync(js_runtime, "op_resources", deno_core::op_resources);
-      ops::reg_json_sync(
-        js_runtime,
-        "op_domain_to_ascii",
-        deno_web::op_domain_to_ascii,
-      );
+      ops::web::init(js_runtime, options.blob_url_store.clone());
       ops::fs_events::init(js_runtime);
       ops::fs::init(js_runtime);
       ops::io::init(js_runtime);
@@ -284,6 +285,7 @@ mod tests {
       ts_version: "x".to_string(),
       no_color: true,
       get_error_class_fn: None,
+      blob_url_store: BlobUrlStore::default(),
       location: None,
     };
 
@@ -27,6 +27,7 @@ use std::env;
 use std::io;
 use std::io::Read;
 use std::io::Write;
+use std::net::Ipv6Addr;
 use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::pin::Pin;
@@ -43,6 +44,7 @@ use std::task::Poll;
 use tempfile::TempDir;
 use tokio::net::TcpListener;
 use tokio::net::TcpStream;
+use tokio::net::UdpSocket;
 use tokio_rustls::rustls;
 use tokio_rustls::TlsAcceptor;
 use tokio_tungstenite::accept_async;
@@ -56,6 +58,7 @@ const REDIRECT_ABSOLUTE_PORT: u16 = 4550;
 const HTTPS_PORT: u16 = 5545;
 const WS_PORT: u16 = 4242;
 const WSS_PORT: u16 = 4243;
+const DNS_PORT: u16 = 4553;
 
 pub const PERMISSION_VARIANTS: [&str; 5] =
   ["read", "write", "env", "net", "run"];
@@ -304,6 +307,113 @@ async fn run_wss_server(addr: &SocketAddr) {
   }
 }
 
+/// Encodes a domain name as DNS labels, without compression.
+fn dns_name(name: &str) -> Vec<u8> {
+  let mut encoded = Vec::new();
+  for label in name.split('.').filter(|label| !label.is_empty()) {
+    encoded.push(label.len() as u8);
+    encoded.extend_from_slice(label.as_bytes());
+  }
+  encoded.push(0);
+  encoded
+}
+
+/// The records that the DNS server answers with, as their name, type and
+/// data.
+fn dns_zone() -> Vec<(&'static str, u16, Vec<u8>)> {
+  let mut mx = 10u16.to_be_bytes().to_vec();
+  mx.extend(dns_name("mail.deno.test"));
+  let mut srv = Vec::new();
+  for field in &[0u16, 5, 4545] {
+    srv.extend_from_slice(&field.to_be_bytes());
+  }
+  srv.extend(dns_name("www.deno.test"));
+  let mut txt = Vec::new();
+  for string in &["v=spf1 -all", "hello world"] {
+    txt.push(string.len() as u8);
+    txt.extend_from_slice(string.as_bytes());
+  }
+  vec![
+    ("www.deno.test", 1, vec![127, 0, 0, 1]),
+    ("www.deno.test", 28, Ipv6Addr::LOCALHOST.octets().to_vec()),
+    ("alias.deno.test", 5, dns_name("www.deno.test")),
+    ("deno.test", 15, mx),
+    ("deno.test", 16, txt),
+    ("_http._tcp.deno.test", 33, srv),
+  ]
+}
+
+/// Answers a DNS query from `dns_zone()`, or returns `None` if the query is
+/// malformed. Names that aren't in the zone get an NXDOMAIN response.
+fn dns_response(query: &[u8]) -> Option<Vec<u8>> {
+  // The question follows the 12 byte header.
+  let mut labels = Vec::new();
+  let mut pos = 12;
+  loop {
+    let len = *query.get(pos)? as usize;
+    pos += 1;
+    if len == 0 {
+      break;
+    }
+    let label = std::str::from_utf8(query.get(pos..pos + len)?).ok()?;
+    labels.push(label.to_ascii_lowercase());
+    pos += len;
+  }
+  let question = query.get(12..pos + 4)?;
+  let qtype = u16::from_be_bytes([question[pos - 12], question[pos - 11]]);
+  let name = labels.join(".");
+
+  let zone = dns_zone();
+  let answers: Vec<_> = zone
+    .iter()
+    .filter(|(rname, rtype, _)| *rname == name && *rtype == qtype)
+    .collect();
+  let rcode = if zone.iter().any(|(rname, _, _)| *rname == name) {
+    0
+  } else {
+    3
+  };
+
+  let mut response = query[..2].to_vec();
+  // A response to a recursive query, with recursion available.
+  response.extend_from_slice(&[0x81, 0x80 | rcode]);
+  response.extend_from_slice(&1u16.to_be_bytes());
+  response.extend_from_slice(&(answers.len() as u16).to_be_bytes());
+  response.extend_from_slice(&[0, 0, 0, 0]);
+  response.extend_from_slice(question);
+  for (_, rtype, data) in answers {
+    // A pointer to the name of the question.
+    response.extend_from_slice(&[0xc0, 12]);
+    response.extend_from_slice(&rtype.to_be_bytes());
+    // The IN class, and a TTL of one minute.
+    response.extend_from_slice(&1u16.to_be_bytes());
+    response.extend_from_slice(&60u32.to_be_bytes());
+    response.extend_from_slice(&(data.len() as u16).to_be_bytes());
+    response.extend_from_slice(data);
+  }
+  Some(response)
+}
+
+/// A name server for the `Deno.resolveDns` tests.
+async fn run_dns_server(addr: &SocketAddr) {
+  let socket = UdpSocket::bind(addr).await.unwrap();
+  let mut buf = [0; 512];
+  loop {
+    let (len, peer) = match socket.recv_from(&mut buf).await {
+      Ok(received) => received,
+      Err(e) => {
+        println!("dns server error: {:?}", e);
+        continue;
+      }
+    };
+    if let Some(response) = dns_response(&buf[..len]) {
+      if let Err(e) = socket.send_to(&response, peer).await {
+        println!("dns server error: {:?}", e);
+      }
+    }
+  }
+}
+
 async fn absolute_redirect(
   req: Request<Body>,
 ) -> hyper::Result<Response<Body>> {
@@ -744,6 +854,8 @@ pub async fn run_all_servers() {
   let ws_server_fut = run_ws_server(&ws_addr);
   let wss_addr = SocketAddr::from(([127, 0, 0, 1], WSS_PORT));
   let wss_server_fut = run_wss_server(&wss_addr);
+  let dns_addr = SocketAddr::from(([127, 0, 0, 1], DNS_PORT));
+  let dns_server_fut = run_dns_server(&dns_addr);
 
   let main_server_fut = wrap_main_server();
   let main_server_https_fut = wrap_main_https_server();
@@ -753,6 +865,7 @@ pub async fn run_all_servers() {
       redirect_server_fut,
       ws_server_fut,
       wss_server_fut,
+      dns_server_fut,
       another_redirect_server_fut,
       inf_redirects_server_fut,
       double_redirects_server_fut,
@@ -1443,6 +1556,28 @@ pub fn parse_max_mem(output: &str) -> Option<u64> {
 mod tests {
   use super::*;
 
+  #[test]
+  fn dns_response_answers() {
+    let mut query = vec![0xab, 0xcd, 0x01, 0x00, 0, 1, 0, 0, 0, 0, 0, 0];
+    query.extend(dns_name("WWW.deno.test"));
+    query.extend_from_slice(&[0, 1, 0, 1]);
+    let response = dns_response(&query).unwrap();
+    assert_eq!(&response[..4], &[0xab, 0xcd, 0x81, 0x80]);
+    // One question and one answer.
+    assert_eq!(&response[4..8], &[0, 1, 0, 1]);
+    assert_eq!(&response[12..query.len()], &query[12..]);
+    assert_eq!(&response[response.len() - 4..], &[127, 0, 0, 1]);
+
+    let mut query = vec![0, 1, 0x01, 0x00, 0, 1, 0, 0, 0, 0, 0, 0];
+    query.extend(dns_name("missing.deno.test"));
+    query.extend_from_slice(&[0, 1, 0, 1]);
+    let response = dns_response(&query).unwrap();
+    assert_eq!(response[3], 0x83);
+    assert_eq!(&response[6..8], &[0, 0]);
+
+    assert!(dns_response(&query[..20]).is_none());
+  }
+
   #[test]
   fn parse_wrk_output_1() {
     const TEXT: &str = include_str!("./testdata/wrk1.txt");